@@ -8,14 +8,20 @@ use bevy_ecs::{
     system::{IntoSystem, System},
     world::World,
 };
+use bevy_utils::HashMap;
 use criterion::{criterion_group, criterion_main, Bencher, Criterion};
 use evergreen_utility_ai::{
-    aggregator::{sum, IntoAggregator},
-    component::{run_all_entity_flows, EntityFlow},
+    aggregator::{product, sum, IntoAggregator},
+    component::{
+        run_action_selectors, run_all_action_selectors, run_all_entity_flows, ActionSelector,
+        Actions, ComputedScores, EntityFlow,
+    },
     evaluator::constant,
-    flow::{FlowNodeConfig, WorldFlowExt},
+    flow::{FlowNodeConfig, FlowNodeConfigs, FlowScratch, WorldFlowExt},
+    score::Score,
+    selector::highest_score,
 };
-use evergreen_utility_ai_macros::{FlowLabel, ScoreLabel};
+use evergreen_utility_ai_macros::{ActionLabel, FlowLabel, ScoreLabel};
 
 #[derive(FlowLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 struct BenchFlow;
@@ -23,46 +29,70 @@ struct BenchFlow;
 #[derive(ScoreLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 struct BenchScore(u8);
 
+#[derive(ActionLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct BenchAction(u8);
+
 fn run_entity_flows(c: &mut Criterion) {
     c.bench_function("run_all_entity_flows/count-100/deep-1/wide-30", |b| {
-        bench_run_all_entity_flows(b, 100, 3, 30);
+        bench_run_all_entity_flows(b, 100, 3, 30, false);
     });
     c.bench_function("run_all_entity_flows/count-100/deep-3/wide-5", |b| {
-        bench_run_all_entity_flows(b, 100, 3, 5);
+        bench_run_all_entity_flows(b, 100, 3, 5, false);
     });
     c.bench_function("run_all_entity_flows/count-100/deep-3/wide-15", |b| {
-        bench_run_all_entity_flows(b, 100, 3, 15);
+        bench_run_all_entity_flows(b, 100, 3, 15, false);
     });
     c.bench_function("run_all_entity_flows/count-100/deep-3/wide-30", |b| {
-        bench_run_all_entity_flows(b, 100, 3, 30);
+        bench_run_all_entity_flows(b, 100, 3, 30, false);
     });
     c.bench_function("run_all_entity_flows/count-10000/deep-1/wide-30", |b| {
-        bench_run_all_entity_flows(b, 10_000, 3, 30);
+        bench_run_all_entity_flows(b, 10_000, 3, 30, false);
     });
     c.bench_function("run_all_entity_flows/count-10000/deep-3/wide-5", |b| {
-        bench_run_all_entity_flows(b, 10_000, 3, 5);
+        bench_run_all_entity_flows(b, 10_000, 3, 5, false);
     });
     c.bench_function("run_all_entity_flows/count-10000/deep-3/wide-15", |b| {
-        bench_run_all_entity_flows(b, 10_000, 3, 15);
+        bench_run_all_entity_flows(b, 10_000, 3, 15, false);
     });
     c.bench_function("run_all_entity_flows/count-10000/deep-3/wide-30", |b| {
-        bench_run_all_entity_flows(b, 10_000, 3, 30);
+        bench_run_all_entity_flows(b, 10_000, 3, 30, false);
     });
     c.bench_function("run_all_entity_flows/count-1000000/deep-1/wide-30", |b| {
-        bench_run_all_entity_flows(b, 1_000_000, 1, 30);
+        bench_run_all_entity_flows(b, 1_000_000, 1, 30, false);
     });
     c.bench_function("run_all_entity_flows/count-1000000/deep-3/wide-5", |b| {
-        bench_run_all_entity_flows(b, 1_000_000, 3, 5);
+        bench_run_all_entity_flows(b, 1_000_000, 3, 5, false);
     });
     c.bench_function("run_all_entity_flows/count-1000000/deep-3/wide-15", |b| {
-        bench_run_all_entity_flows(b, 1_000_000, 3, 15);
+        bench_run_all_entity_flows(b, 1_000_000, 3, 15, false);
     });
     c.bench_function("run_all_entity_flows/count-1000000/deep-3/wide-30", |b| {
-        bench_run_all_entity_flows(b, 1_000_000, 3, 30);
+        bench_run_all_entity_flows(b, 1_000_000, 3, 30, false);
     });
 }
 
-fn bench_run_all_entity_flows(b: &mut Bencher, entities: usize, depth: usize, width: u8) {
+fn run_unchanged_entity_flows(c: &mut Criterion) {
+    c.bench_function(
+        "run_unchanged_entity_flows/count-10000/deep-3/wide-30",
+        |b| {
+            bench_run_all_entity_flows(b, 10_000, 3, 30, true);
+        },
+    );
+    c.bench_function(
+        "run_unchanged_entity_flows/count-1000000/deep-3/wide-30",
+        |b| {
+            bench_run_all_entity_flows(b, 1_000_000, 3, 30, true);
+        },
+    );
+}
+
+fn bench_run_all_entity_flows(
+    b: &mut Bencher,
+    entities: usize,
+    depth: usize,
+    width: u8,
+    skip_unchanged: bool,
+) {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
 
@@ -73,6 +103,7 @@ fn bench_run_all_entity_flows(b: &mut Bencher, entities: usize, depth: usize, wi
     }
 
     world.flow_scope(BenchFlow, |world, flow| {
+        flow.skip_unchanged(skip_unchanged);
         flow.initialize(world);
     });
 
@@ -136,6 +167,202 @@ fn bench_run_flow(b: &mut Bencher, depth: usize, width: u8) {
     });
 }
 
+fn run_wide_aggregator(c: &mut Criterion) {
+    c.bench_function("run_wide_aggregator/wide-5", |b| {
+        bench_run_wide_aggregator(b, 5);
+    });
+    c.bench_function("run_wide_aggregator/wide-15", |b| {
+        bench_run_wide_aggregator(b, 15);
+    });
+    c.bench_function("run_wide_aggregator/wide-30", |b| {
+        bench_run_wide_aggregator(b, 30);
+    });
+}
+
+fn bench_run_wide_aggregator(b: &mut Bencher, width: usize) {
+    let mut world = World::new();
+    let children = (0..width)
+        .map(|_| FlowNodeConfig::evaluator(constant(0.5)))
+        .collect::<FlowNodeConfigs>();
+    world.add_nodes(
+        BenchFlow,
+        FlowNodeConfig::aggregator(sum(), children).label(BenchScore(0)),
+    );
+
+    world.flow_scope(BenchFlow, |world, flow| {
+        flow.initialize(world);
+    });
+
+    b.iter(|| {
+        world.flow_scope(BenchFlow, |world, flow| {
+            black_box(flow.run_readonly(world, Entity::PLACEHOLDER));
+        });
+    });
+}
+
+fn run_short_circuit(c: &mut Criterion) {
+    c.bench_function("run_short_circuit/deep-10/wide-30/disabled", |b| {
+        bench_run_short_circuit(b, 10, 30, false);
+    });
+    c.bench_function("run_short_circuit/deep-10/wide-30/enabled", |b| {
+        bench_run_short_circuit(b, 10, 30, true);
+    });
+}
+
+fn bench_run_short_circuit(b: &mut Bencher, depth: usize, width: usize, short_circuit: bool) {
+    let mut world = World::new();
+    let mut root = FlowNodeConfig::aggregator(
+        product(),
+        (0..width)
+            .map(|_| create_deep_node(depth))
+            .collect::<FlowNodeConfigs>(),
+    );
+    // Siblings are run from the last added, so the zero determines the
+    // product before any of the deep children are run.
+    root = root.add_child(constant(0.));
+    world.add_nodes(BenchFlow, root.label(BenchScore(0)));
+
+    world.flow_scope(BenchFlow, |world, flow| {
+        flow.short_circuit(short_circuit).initialize(world);
+    });
+
+    let mut scores = HashMap::default();
+    let mut scratch = FlowScratch::default();
+    b.iter(|| {
+        world.flow_scope(BenchFlow, |world, flow| {
+            flow.run_readonly_with_scratch(world, Entity::PLACEHOLDER, &mut scores, &mut scratch);
+            black_box(&scores);
+        });
+    });
+}
+
+fn run_flow_into(c: &mut Criterion) {
+    c.bench_function("run_flow_into/deep-3/wide-30/fresh", |b| {
+        bench_run_flow(b, 3, 30);
+    });
+    c.bench_function("run_flow_into/deep-3/wide-30/reused", |b| {
+        bench_run_flow_into(b, 3, 30);
+    });
+    c.bench_function("run_flow_into/deep-3/wide-30/scratch", |b| {
+        bench_run_flow_with_scratch(b, 3, 30);
+    });
+    c.bench_function("run_flow_into/deep-10/wide-30/fresh", |b| {
+        bench_run_flow(b, 10, 30);
+    });
+    c.bench_function("run_flow_into/deep-10/wide-30/reused", |b| {
+        bench_run_flow_into(b, 10, 30);
+    });
+    c.bench_function("run_flow_into/deep-10/wide-30/scratch", |b| {
+        bench_run_flow_with_scratch(b, 10, 30);
+    });
+}
+
+fn bench_run_flow_into(b: &mut Bencher, depth: usize, width: u8) {
+    let mut world = World::new();
+    for i in 0..width {
+        world.add_nodes(BenchFlow, create_deep_node(depth).label(BenchScore(i)));
+    }
+
+    world.flow_scope(BenchFlow, |world, flow| {
+        flow.initialize(world);
+    });
+
+    let mut scores = HashMap::default();
+    b.iter(|| {
+        world.flow_scope(BenchFlow, |world, flow| {
+            flow.run_readonly_into(world, Entity::PLACEHOLDER, &mut scores);
+            black_box(&scores);
+        });
+    });
+}
+
+fn bench_run_flow_with_scratch(b: &mut Bencher, depth: usize, width: u8) {
+    let mut world = World::new();
+    for i in 0..width {
+        world.add_nodes(BenchFlow, create_deep_node(depth).label(BenchScore(i)));
+    }
+
+    world.flow_scope(BenchFlow, |world, flow| {
+        flow.initialize(world);
+    });
+
+    let mut scores = HashMap::default();
+    let mut scratch = FlowScratch::default();
+    b.iter(|| {
+        world.flow_scope(BenchFlow, |world, flow| {
+            flow.run_readonly_with_scratch(world, Entity::PLACEHOLDER, &mut scores, &mut scratch);
+            black_box(&scores);
+        });
+    });
+}
+
+fn run_selectors(c: &mut Criterion) {
+    let per_entity = || ActionSelector::new(highest_score());
+    c.bench_function("run_selectors/count-10000/serial", |b| {
+        bench_run_selectors(b, 10_000, false, per_entity);
+    });
+    c.bench_function("run_selectors/count-10000/parallel", |b| {
+        bench_run_selectors(b, 10_000, true, per_entity);
+    });
+    c.bench_function("run_selectors/count-1000000/serial", |b| {
+        bench_run_selectors(b, 1_000_000, false, per_entity);
+    });
+    c.bench_function("run_selectors/count-1000000/parallel", |b| {
+        bench_run_selectors(b, 1_000_000, true, per_entity);
+    });
+}
+
+fn run_shared_selectors(c: &mut Criterion) {
+    c.bench_function("run_shared_selectors/count-100000/per-entity", |b| {
+        bench_run_selectors(b, 100_000, true, || ActionSelector::new(highest_score()));
+    });
+    c.bench_function("run_shared_selectors/count-100000/shared-locked", |b| {
+        let shared = ActionSelector::new(highest_score());
+        bench_run_selectors(b, 100_000, true, || shared.clone());
+    });
+    c.bench_function("run_shared_selectors/count-100000/shared-stateless", |b| {
+        let shared = ActionSelector::stateless(highest_score());
+        bench_run_selectors(b, 100_000, true, || shared.clone());
+    });
+}
+
+fn bench_run_selectors(
+    b: &mut Bencher,
+    entities: usize,
+    parallel: bool,
+    selector: impl Fn() -> ActionSelector,
+) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+
+    let world = app.world_mut();
+
+    for i in 0..entities {
+        let mut scores = ComputedScores::default();
+        let mut actions = Actions::new(BenchAction(0));
+        for j in 0..30 {
+            scores.insert(
+                BenchScore(j),
+                Score::new((i + j as usize) as f32 % 30. / 30.),
+            );
+            actions = actions.with(BenchScore(j), BenchAction(j));
+        }
+        world.spawn((selector(), scores, actions));
+    }
+    world.flush();
+
+    let mut run_selectors: Box<dyn System<In = (), Out = ()>> = if parallel {
+        Box::new(IntoSystem::into_system(run_all_action_selectors))
+    } else {
+        Box::new(IntoSystem::into_system(run_action_selectors))
+    };
+    run_selectors.initialize(world);
+
+    b.iter(|| {
+        run_selectors.run((), world);
+    });
+}
+
 fn create_deep_node(depth: usize) -> FlowNodeConfig {
     let mut current = FlowNodeConfig::evaluator(constant(0.5));
 
@@ -146,5 +373,15 @@ fn create_deep_node(depth: usize) -> FlowNodeConfig {
     current
 }
 
-criterion_group!(benches, run_flow, run_entity_flows);
+criterion_group!(
+    benches,
+    run_flow,
+    run_wide_aggregator,
+    run_short_circuit,
+    run_flow_into,
+    run_entity_flows,
+    run_unchanged_entity_flows,
+    run_selectors,
+    run_shared_selectors
+);
 criterion_main!(benches);
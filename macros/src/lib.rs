@@ -1,8 +1,8 @@
 use bevy_macro_utils::derive_label;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::format_ident;
-use syn::{parse_macro_input, DeriveInput};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, Index, Lit, Member};
 
 #[proc_macro_derive(FlowLabel)]
 pub fn derive_flow_label(input: TokenStream) -> TokenStream {
@@ -39,6 +39,113 @@ pub fn derive_action_label(input: TokenStream) -> TokenStream {
     derive_label(input, "ActionLabel", &trait_path, &dyn_eq_path)
 }
 
+/// Derives `Scoreable` for a struct by normalizing one of its numeric fields
+/// from `min..=max` to a score, clamping values outside of the range.
+///
+/// The `#[score(...)]` attribute accepts:
+/// - `max = <number>`: The value that scores `Score::MAX`. Required.
+/// - `min = <number>`: The value that scores `Score::MIN`. Defaults to `0`.
+/// - `field = <index or name>`: The field to score. Defaults to the only field.
+/// - `invert`: Scores `max` as `Score::MIN` and `min` as `Score::MAX` instead.
+#[proc_macro_derive(Scoreable, attributes(score))]
+pub fn derive_scoreable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match scoreable_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+fn scoreable_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "Scoreable can only be derived for structs",
+        ));
+    };
+
+    let mut min = 0.0;
+    let mut max = None;
+    let mut field = None;
+    let mut invert = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("score"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("max") {
+                max = Some(parse_number(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("min") {
+                min = parse_number(&meta.value()?.parse()?)?;
+            } else if meta.path.is_ident("field") {
+                field = Some(meta.value()?.parse::<Member>()?);
+            } else if meta.path.is_ident("invert") {
+                invert = true;
+            } else {
+                return Err(meta.error("expected `max`, `min`, `field` or `invert`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let Some(max) = max else {
+        return Err(syn::Error::new(
+            input.span(),
+            "missing `#[score(max = ...)]` attribute",
+        ));
+    };
+    if max <= min {
+        return Err(syn::Error::new(
+            input.span(),
+            "`max` must be greater than `min`",
+        ));
+    }
+
+    let field = match field {
+        Some(field) => field,
+        None if data.fields.len() == 1 => match &data.fields {
+            Fields::Named(fields) => Member::Named(fields.named[0].ident.clone().unwrap()),
+            _ => Member::Unnamed(Index::from(0)),
+        },
+        None => {
+            return Err(syn::Error::new(
+                input.span(),
+                "structs without exactly one field must specify `#[score(field = ...)]`",
+            ))
+        }
+    };
+
+    let mut score_path = evergreen_utility_ai_path();
+    score_path.segments.push(format_ident!("score").into());
+    let (min, range) = (min as f32, (max - min) as f32);
+    let normalized = quote! { (self.#field as f32 - #min) / #range };
+    let value = if invert {
+        quote! { 1.0 - #normalized }
+    } else {
+        normalized
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #score_path::Scoreable for #name #ty_generics #where_clause {
+            fn score(&self) -> #score_path::Score {
+                #score_path::Score::new(#value)
+            }
+        }
+    })
+}
+
+/// Parses an integer or float literal as a number.
+fn parse_number(lit: &Lit) -> syn::Result<f64> {
+    match lit {
+        Lit::Int(int) => int.base10_parse(),
+        Lit::Float(float) => float.base10_parse(),
+        lit => Err(syn::Error::new(lit.span(), "expected a number")),
+    }
+}
+
 fn evergreen_utility_ai_path() -> syn::Path {
     syn::Path::from(syn::Ident::new("evergreen_utility_ai", Span::call_site()))
 }
@@ -2,38 +2,76 @@
 //! entities into a single score.
 
 use alloc::{borrow::Cow, boxed::Box, format};
-use core::marker::PhantomData;
+use core::{marker::PhantomData, ops::RangeInclusive};
 
-use bevy_ecs::{component::Component, entity::Entity, system::SystemInput, world::World};
+use bevy_ecs::{
+    component::{Component, Tick},
+    entity::Entity,
+    system::SystemInput,
+    world::World,
+};
 use bevy_hierarchy::Children;
 use bevy_math::Curve;
 use smallvec::SmallVec;
 
 use crate::{
     evaluator::{EvaluationCtx, Evaluator},
-    flow::{FlowNodeConfig, IntoFlowNodeConfigs},
+    flow::{FlowAccess, FlowNodeConfig, IntoFlowNodeConfigs},
     mapper::{IntoMapper, Mapper, Mapping, MappingCtx},
     score::{Score, Scoreable},
 };
 
 mod average;
+mod blend;
+mod bottleneck;
+mod difference;
+mod logic;
 mod maximum;
 mod median;
+mod min_or_max;
 mod minimum;
+mod normalize;
+mod or_empty;
 mod product;
+mod ratio;
+mod reduce;
+mod require_count;
 mod sum;
+mod switch_on_threshold;
 mod system;
 
 pub use average::*;
+pub use blend::*;
+pub use bottleneck::*;
+pub use difference::*;
+pub use logic::*;
 pub use maximum::*;
 pub use median::*;
+pub use min_or_max::*;
 pub use minimum::*;
+pub use normalize::*;
+pub use or_empty::*;
 pub use product::*;
+pub use ratio::*;
+pub use reduce::*;
+pub use require_count::*;
 pub use sum::*;
+pub use switch_on_threshold::*;
 pub use system::*;
 
 /// Trait for types that view the target [`Entity`] in a [`World`] and children
 /// [`Score`]s and aggregate them into a single [`Score`].
+///
+/// An aggregator with no children returns the identity of the operation where
+/// it has one: AND-style aggregators like [`minimum`], [`product`] and
+/// [`all_of`] return [`Score::MAX`], as an empty conjunction is vacuously true,
+/// while the other built-in aggregators return [`Score::MIN`]. Use
+/// [`IntoAggregator::when_empty`] to return another score instead. When all
+/// children of an aggregator in a [`Flow`] have absent scores, it is not run
+/// and scores [`Score::MIN`], unless it opts in with
+/// [`Aggregator::aggregates_absent`], as [`IntoAggregator::when_empty`] does.
+///
+/// [`Flow`]: crate::flow::Flow
 pub trait Aggregator: Send + Sync + 'static {
     /// Returns the name of the aggregator.
     fn name(&self) -> Cow<'static, str>;
@@ -43,8 +81,97 @@ pub trait Aggregator: Send + Sync + 'static {
         let _ = world;
     }
 
+    /// Checks the change ticks stored by the aggregator, such as the last run
+    /// tick of a system, and clamps them to prevent change detection false
+    /// positives when the world's change tick overflows.
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        let _ = change_tick;
+    }
+
     /// Aggregates the children scores of the target entity.
     fn aggregate(&mut self, ctx: AggregationCtx) -> Score;
+
+    /// Returns the parts of the world read by the aggregator, not counting the
+    /// scores of its children. Called after the aggregator has been
+    /// initialized.
+    ///
+    /// Defaults to [`FlowAccess::all`].
+    fn access(&self) -> FlowAccess {
+        FlowAccess::all()
+    }
+
+    /// Returns the index into `scores` of the child score that most influenced
+    /// the aggregated score, such as the highest score for [`maximum`], or
+    /// [`None`] if no single child dominates. Used to explain aggregated scores
+    /// in a [`FlowTrace`].
+    ///
+    /// Defaults to [`None`].
+    ///
+    /// [`FlowTrace`]: crate::flow::FlowTrace
+    fn explain(&self, scores: &[Score]) -> Option<usize> {
+        let _ = scores;
+        None
+    }
+
+    /// Returns the condition under which a single child score determines the
+    /// aggregated score, if any. Flows with [`Flow::short_circuit`] enabled
+    /// skip the remaining children once a child score meets the condition,
+    /// and then aggregate only the child scores computed so far. Aggregators
+    /// must only declare a condition if any such partial list of scores
+    /// aggregates to the same score as the full list.
+    ///
+    /// Defaults to [`None`].
+    ///
+    /// [`Flow::short_circuit`]: crate::flow::Flow::short_circuit
+    fn short_circuits(&self) -> Option<ShortCircuit> {
+        None
+    }
+
+    /// Returns the range of child counts the aggregator is meant to be used
+    /// with, if it is restricted, such as exactly two for [`difference`].
+    /// [`Flow::validate`] reports aggregators whose child count is outside of
+    /// the range.
+    ///
+    /// Defaults to [`None`].
+    ///
+    /// [`Flow::validate`]: crate::flow::Flow::validate
+    fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+        None
+    }
+
+    /// Returns `true` if the aggregator is run with no child scores when all
+    /// of its children in a [`Flow`] have absent scores, such as [`or_empty`]
+    /// returning its default. Otherwise it is not run and scores
+    /// [`Score::MIN`].
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`Flow`]: crate::flow::Flow
+    fn aggregates_absent(&self) -> bool {
+        false
+    }
+}
+
+/// Condition under which a single child score determines the score of an
+/// [`Aggregator`], as returned by [`Aggregator::short_circuits`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ShortCircuit {
+    /// The aggregated score is determined once any child score is
+    /// [`Score::MIN`], such as for [`product`].
+    AnyMin,
+    /// The aggregated score is determined once any child score is less than
+    /// the given score, such as for [`IntoAggregator::input_threshold`].
+    AnyBelow(Score),
+}
+
+impl ShortCircuit {
+    /// Returns `true` if the given child score meets the condition.
+    pub fn is_met_by(&self, score: Score) -> bool {
+        match *self {
+            ShortCircuit::AnyMin => score == Score::MIN,
+            ShortCircuit::AnyBelow(threshold) => score < threshold,
+        }
+    }
 }
 
 /// Verifies that [`Aggregator`] is dyn-compatible.
@@ -83,6 +210,11 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.mapper.check_change_tick(change_tick);
+                self.aggregator.check_change_tick(change_tick);
+            }
+
             fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
                 let world = ctx.world;
                 let target = ctx.aggregation.target;
@@ -93,6 +225,22 @@ pub trait IntoAggregator<Marker>: Sized {
                     mapping: Mapping { target, value },
                 })
             }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
         }
 
         MapAggregator {
@@ -101,6 +249,31 @@ pub trait IntoAggregator<Marker>: Sized {
         }
     }
 
+    /// Maps the output score of this aggregator with the given function,
+    /// which is passed the world, the target entity and the score. This is a
+    /// shorthand for [`IntoAggregator::map`] with a [`Mapper`] that reads the world.
+    fn map_world(
+        self,
+        f: impl Fn(&World, Entity, Score) -> Score + Send + Sync + 'static,
+    ) -> impl Aggregator {
+        struct WorldMapper<F>(F);
+
+        impl<F> Mapper<Score> for WorldMapper<F>
+        where
+            F: Fn(&World, Entity, Score) -> Score + Send + Sync + 'static,
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Borrowed("map_world")
+            }
+
+            fn map(&mut self, ctx: MappingCtx<Score>) -> Score {
+                (self.0)(ctx.world, ctx.mapping.target, ctx.mapping.value)
+            }
+        }
+
+        self.map(WorldMapper(f))
+    }
+
     /// Inverts the output score of this aggregator.
     fn invert(self) -> impl Aggregator {
         struct InvertAggregator<A> {
@@ -119,10 +292,34 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
             fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
                 let score = self.aggregator.aggregate(ctx);
                 Score::new(1. - score.get())
             }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
         }
 
         InvertAggregator {
@@ -150,9 +347,33 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
             fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
                 self.aggregator.aggregate(ctx) * self.weight
             }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
         }
 
         WeightAggregator {
@@ -183,10 +404,34 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
             fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
                 let score = self.aggregator.aggregate(ctx);
                 self.curve.sample(score.get()).unwrap_or(Score::MIN)
             }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
         }
 
         CurveAggregator {
@@ -195,6 +440,66 @@ pub trait IntoAggregator<Marker>: Sized {
         }
     }
 
+    /// Applies the given [`Curve`] to this aggregator's output score. Unlike
+    /// [`IntoAggregator::curve`], output scores outside of the curve's domain
+    /// are clamped into it, so they map to the nearest valid output.
+    fn curve_clamped(self, curve: impl Curve<Score> + Send + Sync + 'static) -> impl Aggregator {
+        struct CurveClampedAggregator<C: Curve<Score> + Send + Sync + 'static, A: Aggregator> {
+            curve: C,
+            aggregator: A,
+        }
+
+        impl<C: Curve<Score> + Send + Sync + 'static, A: Aggregator> Aggregator
+            for CurveClampedAggregator<C, A>
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.curve_clamped({})",
+                    self.aggregator.name(),
+                    core::any::type_name::<C>(),
+                ))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.aggregator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
+            fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+                let score = self.aggregator.aggregate(ctx);
+                self.curve.sample_clamped(score.get())
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
+        }
+
+        CurveClampedAggregator {
+            curve,
+            aggregator: self.into_aggregator(),
+        }
+    }
+
     /// Applies the given [`Curve`] to this aggregator's input scores. If the
     /// curve cannot be sampled at any input score value, that score is set to
     /// [`Score::MIN`].
@@ -219,6 +524,10 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
             fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
                 self.aggregator.aggregate(AggregationCtx {
                     world: ctx.world,
@@ -233,6 +542,31 @@ pub trait IntoAggregator<Marker>: Sized {
                     },
                 })
             }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                // Indices only line up if every score could be sampled.
+                let sampled = scores
+                    .iter()
+                    .flat_map(|score| self.curve.sample(score.get()))
+                    .collect::<SmallVec<[Score; 4]>>();
+                if sampled.len() == scores.len() {
+                    self.aggregator.explain(&sampled)
+                } else {
+                    None
+                }
+            }
         }
 
         CurveInputAggregator {
@@ -263,6 +597,10 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
             fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
                 let score = self.aggregator.aggregate(ctx);
                 if score < self.threshold {
@@ -271,6 +609,26 @@ pub trait IntoAggregator<Marker>: Sized {
                     score
                 }
             }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
         }
 
         OutputThresholdAggregator {
@@ -279,21 +637,21 @@ pub trait IntoAggregator<Marker>: Sized {
         }
     }
 
-    /// Applies the given threshold to this aggregator's input scores. If any
-    /// input score is less than the threshold, the aggregator returns
-    /// [`Score::MIN`].
-    fn input_threshold(self, threshold: impl Into<Score>) -> impl Aggregator {
-        struct InputThresholdAggregator<A> {
-            threshold: Score,
+    /// Clamps this aggregator's output score to be at least the given minimum.
+    /// Unlike [`IntoAggregator::threshold`], scores below the minimum are
+    /// raised to it rather than set to [`Score::MIN`].
+    fn clamp_min(self, min: impl Into<Score>) -> impl Aggregator {
+        struct ClampMinAggregator<A> {
+            min: Score,
             aggregator: A,
         }
 
-        impl<A: Aggregator> Aggregator for InputThresholdAggregator<A> {
+        impl<A: Aggregator> Aggregator for ClampMinAggregator<A> {
             fn name(&self) -> Cow<'static, str> {
                 Cow::Owned(format!(
-                    "{}.input_threshold({})",
+                    "{}.clamp_min({})",
                     self.aggregator.name(),
-                    self.threshold,
+                    self.min
                 ))
             }
 
@@ -301,43 +659,54 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
             fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
-                if ctx
-                    .aggregation
-                    .scores
-                    .iter()
-                    .any(|&score| score < self.threshold)
-                {
-                    Score::MIN
-                } else {
-                    self.aggregator.aggregate(ctx)
-                }
+                self.aggregator.aggregate(ctx).max(self.min)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
             }
         }
 
-        InputThresholdAggregator {
-            threshold: threshold.into(),
+        ClampMinAggregator {
+            min: min.into(),
             aggregator: self.into_aggregator(),
         }
     }
 
-    /// Converts this aggregator into a [`Evaluator`] that scores the given
-    /// [`Component`] for the children entities of the target entity, and then
-    /// aggregates the scores using this aggregator. If the target entity does
-    /// not have any children with the component, the evaluator returns
-    /// [`Score::MIN`].
-    fn score_children<C: Component + Scoreable>(self) -> impl Evaluator {
-        struct ChildrenEvaluator<A: Aggregator, C: Component + Scoreable> {
+    /// Clamps this aggregator's output score to be at most the given maximum.
+    fn clamp_max(self, max: impl Into<Score>) -> impl Aggregator {
+        struct ClampMaxAggregator<A> {
+            max: Score,
             aggregator: A,
-            _component: PhantomData<C>,
         }
 
-        impl<A: Aggregator, C: Component + Scoreable> Evaluator for ChildrenEvaluator<A, C> {
+        impl<A: Aggregator> Aggregator for ClampMaxAggregator<A> {
             fn name(&self) -> Cow<'static, str> {
                 Cow::Owned(format!(
-                    "{}.score_children({})",
+                    "{}.clamp_max({})",
                     self.aggregator.name(),
-                    core::any::type_name::<C>(),
+                    self.max
                 ))
             }
 
@@ -345,67 +714,329 @@ pub trait IntoAggregator<Marker>: Sized {
                 self.aggregator.initialize(world);
             }
 
-            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
-                // Get the children entities of the target entity.
-                let Some(children) = ctx.world.get::<Children>(ctx.evaluation.target) else {
-                    return Score::MIN;
-                };
-                // Score the children's components and collect them.
-                let scores = children
-                    .into_iter()
-                    .flat_map(|&entity| ctx.world.get::<C>(entity).map(|c| c.score()))
-                    .collect::<SmallVec<_>>();
-                // Return early if none of the children have the component.
-                if scores.is_empty() {
-                    return Score::MIN;
-                }
-                // Evaluate the wrapped aggregator with the scores.
-                self.aggregator.aggregate(AggregationCtx {
-                    world: ctx.world,
-                    aggregation: Aggregation {
-                        target: ctx.evaluation.target,
-                        scores,
-                    },
-                })
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
+            fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+                self.aggregator.aggregate(ctx).min(self.max)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
             }
         }
 
-        ChildrenEvaluator {
+        ClampMaxAggregator {
+            max: max.into(),
             aggregator: self.into_aggregator(),
-            _component: PhantomData::<C>,
         }
     }
 
-    /// Sets the children of this aggregator to the given [`Aggregator`]s and/or [`Evaluator`]s.
-    fn with_children<M>(self, other: impl IntoFlowNodeConfigs<M>) -> FlowNodeConfig {
-        FlowNodeConfig::aggregator(self, other)
-    }
-}
+    /// Rounds this aggregator's output score to the nearest of `levels` evenly
+    /// spaced values in `[0, 1]`. For example, 3 levels yields scores of 0.0,
+    /// 0.5 or 1.0.
+    ///
+    /// # Panics
+    ///
+    /// If `levels` is less than 2.
+    fn quantize(self, levels: usize) -> impl Aggregator {
+        assert!(
+            levels >= 2,
+            "cannot quantize scores into fewer than 2 levels"
+        );
+
+        struct QuantizeAggregator<A> {
+            levels: usize,
+            aggregator: A,
+        }
 
-/// All [`Aggregator`]s can be converted into themselves.
-impl<A: Aggregator> IntoAggregator<()> for A {
-    type Aggregator = A;
+        impl<A: Aggregator> Aggregator for QuantizeAggregator<A> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.quantize({})",
+                    self.aggregator.name(),
+                    self.levels
+                ))
+            }
 
-    fn into_aggregator(self) -> Self::Aggregator {
-        self
-    }
-}
+            fn initialize(&mut self, world: &mut World) {
+                self.aggregator.initialize(world);
+            }
 
-/// [`World`] and [`Aggregation`] pair passed to [`Aggregator`]s.
-#[derive(Clone, Debug)]
-pub struct AggregationCtx<'w> {
-    /// The world in which the [`Aggregator`] is being evaluated.
-    pub world: &'w World,
-    /// The aggregation of scores being evaluated.
-    pub aggregation: Aggregation,
-}
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
 
-/// [`SystemInput`] type for [`Aggregator`] systems.
-#[derive(Clone, PartialEq, Debug)]
-pub struct Aggregation {
-    /// The entity that is being scored.
+            fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+                self.aggregator.aggregate(ctx).quantize(self.levels)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
+        }
+
+        QuantizeAggregator {
+            levels,
+            aggregator: self.into_aggregator(),
+        }
+    }
+
+    /// Applies the given threshold to this aggregator's input scores. If any
+    /// input score is less than the threshold, the aggregator returns
+    /// [`Score::MIN`].
+    fn input_threshold(self, threshold: impl Into<Score>) -> impl Aggregator {
+        struct InputThresholdAggregator<A> {
+            threshold: Score,
+            aggregator: A,
+        }
+
+        impl<A: Aggregator> Aggregator for InputThresholdAggregator<A> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.input_threshold({})",
+                    self.aggregator.name(),
+                    self.threshold,
+                ))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.aggregator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
+            fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+                if ctx
+                    .aggregation
+                    .scores
+                    .iter()
+                    .any(|&score| score < self.threshold)
+                {
+                    Score::MIN
+                } else {
+                    self.aggregator.aggregate(ctx)
+                }
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                scores
+                    .iter()
+                    .position(|&score| score < self.threshold)
+                    .or_else(|| self.aggregator.explain(scores))
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                if self.threshold > Score::MIN {
+                    Some(ShortCircuit::AnyBelow(self.threshold))
+                } else {
+                    self.aggregator.short_circuits()
+                }
+            }
+        }
+
+        InputThresholdAggregator {
+            threshold: threshold.into(),
+            aggregator: self.into_aggregator(),
+        }
+    }
+
+    /// Overrides the name of this aggregator, as returned by
+    /// [`Aggregator::name`], with the given name. Useful for giving nodes a
+    /// stable name in logs and traces.
+    fn named(self, name: impl Into<Cow<'static, str>>) -> impl Aggregator {
+        struct NamedAggregator<A> {
+            name: Cow<'static, str>,
+            aggregator: A,
+        }
+
+        impl<A: Aggregator> Aggregator for NamedAggregator<A> {
+            fn name(&self) -> Cow<'static, str> {
+                self.name.clone()
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.aggregator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
+            fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+                self.aggregator.aggregate(ctx)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.aggregator.access()
+            }
+
+            fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+                self.aggregator.required_arity()
+            }
+
+            fn aggregates_absent(&self) -> bool {
+                self.aggregator.aggregates_absent()
+            }
+
+            fn explain(&self, scores: &[Score]) -> Option<usize> {
+                self.aggregator.explain(scores)
+            }
+
+            fn short_circuits(&self) -> Option<ShortCircuit> {
+                self.aggregator.short_circuits()
+            }
+        }
+
+        NamedAggregator {
+            name: name.into(),
+            aggregator: self.into_aggregator(),
+        }
+    }
+
+    /// Returns the given score instead when this aggregator has no child
+    /// scores, including when all of its children are absent. Equivalent to
+    /// [`or_empty`] with this aggregator.
+    fn when_empty(self, default: impl Into<Score>) -> impl Aggregator {
+        or_empty(default, self)
+    }
+
+    /// Converts this aggregator into a [`Evaluator`] that scores the given
+    /// [`Component`] for the children entities of the target entity, and then
+    /// aggregates the scores using this aggregator. If the target entity does
+    /// not have any children with the component, the evaluator returns
+    /// [`Score::MIN`].
+    fn score_children<C: Component + Scoreable>(self) -> impl Evaluator {
+        struct ChildrenEvaluator<A: Aggregator, C: Component + Scoreable> {
+            aggregator: A,
+            _component: PhantomData<C>,
+        }
+
+        impl<A: Aggregator, C: Component + Scoreable> Evaluator for ChildrenEvaluator<A, C> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.score_children({})",
+                    self.aggregator.name(),
+                    core::any::type_name::<C>(),
+                ))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.aggregator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.aggregator.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                // Get the children entities of the target entity.
+                let Some(children) = ctx.world.get::<Children>(ctx.evaluation.target) else {
+                    return Score::MIN;
+                };
+                // Score the children's components and collect them.
+                let scores = children
+                    .into_iter()
+                    .flat_map(|&entity| ctx.world.get::<C>(entity).map(|c| c.score()))
+                    .collect::<SmallVec<_>>();
+                // Return early if none of the children have the component.
+                if scores.is_empty() && !self.aggregator.aggregates_absent() {
+                    return Score::MIN;
+                }
+                // Evaluate the wrapped aggregator with the scores.
+                self.aggregator.aggregate(AggregationCtx {
+                    world: ctx.world,
+                    aggregation: Aggregation {
+                        target: ctx.evaluation.target,
+                        scores,
+                    },
+                })
+            }
+        }
+
+        ChildrenEvaluator {
+            aggregator: self.into_aggregator(),
+            _component: PhantomData::<C>,
+        }
+    }
+
+    /// Sets the children of this aggregator to the given [`Aggregator`]s and/or [`Evaluator`]s.
+    fn with_children<M>(self, other: impl IntoFlowNodeConfigs<M>) -> FlowNodeConfig {
+        FlowNodeConfig::aggregator(self, other)
+    }
+}
+
+/// All [`Aggregator`]s can be converted into themselves.
+impl<A: Aggregator> IntoAggregator<()> for A {
+    type Aggregator = A;
+
+    fn into_aggregator(self) -> Self::Aggregator {
+        self
+    }
+}
+
+/// [`World`] and [`Aggregation`] pair passed to [`Aggregator`]s.
+#[derive(Clone, Debug)]
+pub struct AggregationCtx<'w> {
+    /// The world in which the [`Aggregator`] is being evaluated.
+    pub world: &'w World,
+    /// The aggregation of scores being evaluated.
+    pub aggregation: Aggregation,
+}
+
+/// [`SystemInput`] type for [`Aggregator`] systems.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Aggregation {
+    /// The entity that is being scored.
     pub target: Entity,
-    /// The computed children scores.
+    /// The computed children scores, in the order the children were added.
     pub scores: SmallVec<[Score; 4]>,
 }
 
@@ -420,19 +1051,139 @@ impl SystemInput for Aggregation {
 
 #[cfg(test)]
 mod tests {
-    use bevy_ecs::{entity::Entity, world::World};
-    use bevy_math::curve::FunctionCurve;
-    use smallvec::smallvec;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use bevy_ecs::{entity::Entity, system::Resource, world::World};
+    use bevy_math::curve::{FunctionCurve, Interval};
+    use smallvec::{smallvec, SmallVec};
 
     use crate::{
         aggregator::{
-            average, geometric_mean, harmonic_mean, maximum, median, minimum, product, sum,
-            Aggregation, AggregationCtx, Aggregator, IntoAggregator,
+            abs_difference, all_of, any_of, average, blend_aggregators, bottleneck, difference,
+            geometric_mean, harmonic_mean, maximum, median, min_or_max, minimum, normalize,
+            or_empty, power_mean, product, ratio, reduce, require_count, root_mean_square, sum,
+            switch_on_threshold, Aggregation, AggregationCtx, Aggregator, IntoAggregator,
         },
+        component::{run_all_entity_flows, ComputedScores, EntityFlow},
+        evaluator::{constant, target},
+        flow::WorldFlowExt,
         mapper::Mapping,
         score::Score,
+        tests::{Fuel, FuelScore, Health, HealthScore, TestFlow, TotalHealthScore},
     };
 
+    #[test]
+    fn all_of_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = all_of();
+        aggregator.initialize(&mut world);
+        assert_eq!(aggregator.name(), "all_of");
+
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: smallvec![0.3.into(), 0.6.into(), 0.8.into()],
+            },
+        });
+        assert_eq!(output, Score::new(0.3));
+
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: smallvec![],
+            },
+        });
+        assert_eq!(output, Score::MAX);
+    }
+
+    #[test]
+    fn any_of_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = any_of();
+        aggregator.initialize(&mut world);
+        assert_eq!(aggregator.name(), "any_of");
+
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: smallvec![0.3.into(), 0.6.into(), 0.8.into()],
+            },
+        });
+        assert_eq!(output, Score::new(0.8));
+
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: smallvec![],
+            },
+        });
+        assert_eq!(output, Score::MIN);
+    }
+
+    #[test]
+    fn empty_aggregations() {
+        fn aggregate_empty(mut aggregator: impl Aggregator) -> Score {
+            let mut world = World::new();
+            aggregator.initialize(&mut world);
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores: SmallVec::new(),
+                },
+            })
+        }
+
+        // AND-style aggregators are vacuously satisfied.
+        assert_eq!(aggregate_empty(minimum()), Score::MAX);
+        assert_eq!(aggregate_empty(product()), Score::MAX);
+        assert_eq!(aggregate_empty(all_of()), Score::MAX);
+        assert_eq!(aggregate_empty(bottleneck()), Score::MAX);
+
+        assert_eq!(aggregate_empty(maximum()), Score::MIN);
+        assert_eq!(aggregate_empty(sum()), Score::MIN);
+        assert_eq!(aggregate_empty(any_of()), Score::MIN);
+
+        assert_eq!(aggregate_empty(or_empty(Score::MIN, product())), Score::MIN);
+        assert_eq!(aggregate_empty(or_empty(0.5, sum())), Score::new(0.5));
+        assert_eq!(aggregate_empty(product().when_empty(0.5)), Score::new(0.5));
+    }
+
+    #[test]
+    fn all_absent_children() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                minimum()
+                    .with_children(target::<Health>())
+                    .label(HealthScore),
+                product()
+                    .when_empty(0.5)
+                    .with_children(target::<Health>())
+                    .label(TotalHealthScore),
+                or_empty(0.75, sum())
+                    .invert()
+                    .with_children(target::<Fuel>())
+                    .label(FuelScore),
+            ),
+        );
+        let npc = world.spawn_empty().id();
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.score(HealthScore), Some(Score::MIN));
+        // Aggregators opting in with `aggregates_absent` are still run.
+        assert_eq!(scores.score(TotalHealthScore), Some(Score::new(0.5)));
+        assert_eq!(scores.score(FuelScore), Some(Score::new(0.25)));
+    }
+
     #[test]
     fn average_aggregator() {
         let mut world = World::new();
@@ -451,6 +1202,125 @@ mod tests {
         assert_eq!(output, Score::new(0.2));
     }
 
+    #[test]
+    fn blend_aggregators_aggregator() {
+        #[derive(Resource)]
+        struct Difficulty(f32);
+
+        let mut world = World::new();
+        world.insert_resource(Difficulty(0.0));
+
+        let mut aggregator = blend_aggregators(average(), maximum(), |world| {
+            world.resource::<Difficulty>().0
+        });
+        aggregator.initialize(&mut world);
+        let mut aggregate = |world: &World| {
+            aggregator.aggregate(AggregationCtx {
+                world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores: smallvec![0.25.into(), 0.75.into(), 0.5.into()],
+                },
+            })
+        };
+
+        assert_eq!(aggregate(&world), Score::new(0.5));
+        world.resource_mut::<Difficulty>().0 = 0.5;
+        assert_eq!(aggregate(&world), Score::new(0.625));
+        world.resource_mut::<Difficulty>().0 = 1.0;
+        assert_eq!(aggregate(&world), Score::new(0.75));
+        // The blend factor is clamped.
+        world.resource_mut::<Difficulty>().0 = 2.0;
+        assert_eq!(aggregate(&world), Score::new(0.75));
+    }
+
+    #[test]
+    fn clamp_aggregator() {
+        let mut world = World::new();
+
+        let aggregate = |aggregator: &mut dyn Aggregator, world: &mut World, score: f32| {
+            aggregator.initialize(world);
+            aggregator.aggregate(AggregationCtx {
+                world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores: smallvec![score.into()],
+                },
+            })
+        };
+
+        for (score, clamped) in [(0.2, 0.4), (0.4, 0.4), (0.6, 0.6)] {
+            let mut aggregator = sum().clamp_min(0.4);
+            assert_eq!(
+                aggregate(&mut aggregator, &mut world, score),
+                Score::new(clamped)
+            );
+        }
+        for (score, clamped) in [(0.2, 0.2), (0.4, 0.4), (0.6, 0.4)] {
+            let mut aggregator = sum().clamp_max(0.4);
+            assert_eq!(
+                aggregate(&mut aggregator, &mut world, score),
+                Score::new(clamped)
+            );
+        }
+    }
+
+    #[test]
+    fn difference_aggregator() {
+        let mut world = World::new();
+
+        let mut difference = difference();
+        difference.initialize(&mut world);
+        let mut abs_difference = abs_difference();
+        abs_difference.initialize(&mut world);
+
+        let aggregate = |aggregator: &mut dyn Aggregator, scores: SmallVec<[Score; 4]>| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        assert_eq!(
+            aggregate(&mut difference, smallvec![0.75.into(), 0.25.into()]),
+            Score::new(0.5)
+        );
+        assert_eq!(
+            aggregate(&mut difference, smallvec![0.25.into(), 0.75.into()]),
+            Score::MIN
+        );
+        assert_eq!(
+            aggregate(&mut difference, smallvec![0.75.into()]),
+            Score::MIN
+        );
+        assert_eq!(aggregate(&mut difference, smallvec![]), Score::MIN);
+        assert_eq!(
+            aggregate(
+                &mut difference,
+                smallvec![0.75.into(), 0.25.into(), 0.5.into()]
+            ),
+            Score::new(0.5)
+        );
+
+        assert_eq!(abs_difference.name(), "abs_difference");
+        assert_eq!(
+            aggregate(&mut abs_difference, smallvec![0.75.into(), 0.25.into()]),
+            Score::new(0.5)
+        );
+        assert_eq!(
+            aggregate(&mut abs_difference, smallvec![0.25.into(), 0.75.into()]),
+            Score::new(0.5)
+        );
+        assert_eq!(
+            aggregate(&mut abs_difference, smallvec![0.75.into()]),
+            Score::MIN
+        );
+        assert_eq!(aggregate(&mut abs_difference, smallvec![]), Score::MIN);
+    }
+
     #[test]
     fn curve_aggregator() {
         let mut world = World::new();
@@ -470,6 +1340,27 @@ mod tests {
         assert_eq!(output, Score::MAX);
     }
 
+    #[test]
+    fn curve_clamped_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = sum().curve_clamped(FunctionCurve::new(
+            Interval::new(0.2, 0.8).unwrap(),
+            Score::new,
+        ));
+        aggregator.initialize(&mut world);
+
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: smallvec![0.5.into(), 0.4.into()],
+            },
+        });
+
+        assert_eq!(output, Score::new(0.8));
+    }
+
     #[test]
     fn curve_input_aggregator() {
         let mut world = World::new();
@@ -525,6 +1416,36 @@ mod tests {
         assert_eq!(output, Score::new(0.6));
     }
 
+    #[test]
+    fn map_world_aggregator() {
+        #[derive(Resource)]
+        struct Scale(f32);
+
+        let mut world = World::new();
+        world.insert_resource(Scale(0.5));
+
+        let mut aggregator = sum().map_world(|world, _, score| score * world.resource::<Scale>().0);
+        aggregator.initialize(&mut world);
+
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: smallvec![0.25.into(), 0.5.into()],
+            },
+        });
+
+        assert_eq!(output, Score::new(0.375));
+    }
+
+    #[test]
+    fn named_aggregator() {
+        let aggregator = sum()
+            .named("total")
+            .map(|mapping: Mapping<Score>| mapping.value * 2.);
+        assert!(aggregator.name().starts_with("total.map("));
+    }
+
     #[test]
     fn maximum_aggregator() {
         let mut world = World::new();
@@ -579,6 +1500,96 @@ mod tests {
         assert_eq!(output, Score::new(0.16363636));
     }
 
+    #[test]
+    fn switch_on_threshold_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = switch_on_threshold(0.5, maximum(), average());
+        aggregator.initialize(&mut world);
+        let mut aggregate = |scores: SmallVec<[Score; 4]>| {
+            aggregator
+                .aggregate(AggregationCtx {
+                    world: &world,
+                    aggregation: Aggregation {
+                        target: Entity::PLACEHOLDER,
+                        scores,
+                    },
+                })
+                .get()
+        };
+
+        // A strong option is taken as is.
+        assert_eq!(aggregate(smallvec![0.2.into(), 0.8.into()]), 0.8);
+        assert_eq!(aggregate(smallvec![0.1.into(), 0.5.into()]), 0.5);
+        // Weak options are averaged.
+        assert_eq!(aggregate(smallvec![0.125.into(), 0.375.into()]), 0.25);
+        assert_eq!(aggregate(SmallVec::new()), 0.0);
+    }
+
+    #[test]
+    fn power_mean_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregate = |aggregator: &mut dyn Aggregator, scores: SmallVec<[Score; 4]>| {
+            aggregator.initialize(&mut world);
+            aggregator
+                .aggregate(AggregationCtx {
+                    world: &world,
+                    aggregation: Aggregation {
+                        target: Entity::PLACEHOLDER,
+                        scores,
+                    },
+                })
+                .get()
+        };
+
+        let scores: SmallVec<[Score; 4]> = smallvec![0.1.into(), 0.2.into(), 0.3.into()];
+        let pairs: [(f32, &mut dyn Aggregator); 4] = [
+            (1.0, &mut average()),
+            (2.0, &mut root_mean_square()),
+            (0.0, &mut geometric_mean()),
+            (-1.0, &mut harmonic_mean()),
+        ];
+        for (p, expected) in pairs {
+            let output = aggregate(&mut power_mean(p), scores.clone());
+            let expected = aggregate(expected, scores.clone());
+            assert!((output - expected).abs() < 1e-6, "p = {p}");
+        }
+
+        // Zero scores make negative powers infinite.
+        let output = aggregate(&mut power_mean(-1.0), smallvec![0.0.into(), 0.5.into()]);
+        assert_eq!(output, 0.0);
+        assert_eq!(aggregate(&mut power_mean(2.0), smallvec![]), 0.0);
+    }
+
+    #[test]
+    fn root_mean_square_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = root_mean_square();
+        aggregator.initialize(&mut world);
+
+        let mut aggregate = |scores: SmallVec<[Score; 4]>| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        assert_eq!(
+            aggregate(smallvec![0.3.into(), 0.4.into()]),
+            Score::new(0.35355338)
+        );
+        assert_eq!(
+            aggregate(smallvec![0.5.into(), 0.5.into()]),
+            Score::new(0.5)
+        );
+        assert_eq!(aggregate(smallvec![]), Score::MIN);
+    }
+
     #[test]
     fn median_aggregator() {
         let mut world = World::new();
@@ -597,6 +1608,114 @@ mod tests {
         assert_eq!(output, Score::new(0.3));
     }
 
+    #[test]
+    fn median_matches_sorted() {
+        let mut world = World::new();
+
+        let mut aggregator = median();
+        aggregator.initialize(&mut world);
+
+        // Simple LCG so the inputs are pseudo-random but reproducible.
+        let mut seed = 0x2545_f491_u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            Score::new((seed >> 8) as f32 / (1 << 24) as f32)
+        };
+
+        for len in 1..40 {
+            let scores = (0..len).map(|_| next()).collect::<SmallVec<[Score; 4]>>();
+
+            let mut sorted = scores.clone();
+            sorted.sort_unstable();
+            let expected = if len % 2 == 0 {
+                Score::new((sorted[len / 2 - 1].get() + sorted[len / 2].get()) / 2.0)
+            } else {
+                sorted[len / 2]
+            };
+
+            let output = aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            });
+            assert_eq!(output, expected, "{len} scores");
+        }
+    }
+
+    #[test]
+    fn min_or_max_aggregator() {
+        let mut world = World::new();
+
+        let use_max = Arc::new(AtomicBool::new(true));
+        let mut aggregator = min_or_max(Arc::clone(&use_max));
+        aggregator.initialize(&mut world);
+
+        let mut aggregate = || {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores: smallvec![0.3.into(), 0.15.into(), 0.5.into()],
+                },
+            })
+        };
+
+        assert_eq!(aggregate(), Score::new(0.5));
+        use_max.store(false, Ordering::Relaxed);
+        assert_eq!(aggregate(), Score::new(0.15));
+        use_max.store(true, Ordering::Relaxed);
+        assert_eq!(aggregate(), Score::new(0.5));
+    }
+
+    #[test]
+    fn min_or_max_rescores_skipped_entities() {
+        let mut world = World::new();
+        let use_max = Arc::new(AtomicBool::new(true));
+        world.add_nodes(
+            TestFlow,
+            min_or_max(Arc::clone(&use_max))
+                .with_children((constant(0.2), constant(0.8)))
+                .label(HealthScore),
+        );
+        world.flow_scope(TestFlow, |_, flow| {
+            flow.skip_unchanged(true);
+        });
+        let npc = world.spawn(EntityFlow::new(TestFlow)).id();
+
+        let mut run = || {
+            world.run_system_cached(run_all_entity_flows).unwrap();
+            world.get::<ComputedScores>(npc).unwrap().get(HealthScore)
+        };
+
+        assert_eq!(run(), Some(Score::new(0.8)));
+        use_max.store(false, Ordering::Relaxed);
+        assert_eq!(run(), Some(Score::new(0.2)));
+    }
+
+    #[test]
+    fn bottleneck_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = bottleneck();
+        aggregator.initialize(&mut world);
+        assert_eq!(aggregator.name(), "bottleneck");
+
+        let scores: SmallVec<[Score; 4]> =
+            smallvec![0.3.into(), 0.15.into(), 0.5.into(), 0.15.into()];
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: scores.clone(),
+            },
+        });
+        assert_eq!(output, Score::new(0.15));
+        assert_eq!(aggregator.explain(&scores), Some(1));
+        assert_eq!(aggregator.explain(&[]), None);
+    }
+
     #[test]
     fn minimum_aggregator() {
         let mut world = World::new();
@@ -615,6 +1734,33 @@ mod tests {
         assert_eq!(output, Score::new(0.15));
     }
 
+    #[test]
+    fn normalize_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = normalize(average());
+        aggregator.initialize(&mut world);
+        assert_eq!(aggregator.name(), "normalize(average)");
+
+        let mut aggregate = |scores| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        // Rescaled to [0.25, 0.5, 1.0].
+        assert_eq!(
+            aggregate(smallvec![0.1.into(), 0.2.into(), 0.4.into()]),
+            Score::new(1.75 / 3.)
+        );
+        assert_eq!(aggregate(smallvec![0.0.into(), 0.0.into()]), Score::MIN);
+        assert_eq!(aggregate(smallvec![]), Score::MIN);
+    }
+
     #[test]
     fn product_aggregator() {
         let mut world = World::new();
@@ -633,6 +1779,121 @@ mod tests {
         assert_eq!(output, Score::new(0.0225));
     }
 
+    #[test]
+    fn ratio_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = ratio();
+        aggregator.initialize(&mut world);
+
+        let mut aggregate = |scores: SmallVec<[Score; 4]>| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        assert_eq!(
+            aggregate(smallvec![0.25.into(), 0.5.into()]),
+            Score::new(0.5)
+        );
+        assert_eq!(aggregate(smallvec![0.5.into(), 0.25.into()]), Score::MAX);
+        assert_eq!(aggregate(smallvec![0.5.into(), 0.0.into()]), Score::MIN);
+        assert_eq!(aggregate(smallvec![0.5.into()]), Score::MIN);
+    }
+
+    #[test]
+    fn reduce_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = reduce(Score::MIN, |acc, score| acc.max(score * score));
+        aggregator.initialize(&mut world);
+
+        let mut aggregate = |scores: SmallVec<[Score; 4]>| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        assert_eq!(
+            aggregate(smallvec![0.25.into(), 0.5.into(), 0.375.into()]),
+            Score::new(0.25)
+        );
+        assert_eq!(aggregate(SmallVec::new()), Score::MIN);
+
+        let mut aggregator = reduce(0.5, |acc, score| acc * score);
+        aggregator.initialize(&mut world);
+        let output = aggregator.aggregate(AggregationCtx {
+            world: &world,
+            aggregation: Aggregation {
+                target: Entity::PLACEHOLDER,
+                scores: SmallVec::new(),
+            },
+        });
+        assert_eq!(output, Score::new(0.5));
+    }
+
+    #[test]
+    fn or_empty_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = or_empty(Score::MAX, minimum());
+        aggregator.initialize(&mut world);
+        assert_eq!(aggregator.name(), "or_empty(1.0000, minimum)");
+
+        let mut aggregate = |scores| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        assert_eq!(aggregate(SmallVec::new()), Score::MAX);
+        assert_eq!(
+            aggregate(smallvec![0.6.into(), 0.25.into()]),
+            Score::new(0.25)
+        );
+        assert_eq!(aggregate(smallvec![Score::MIN]), Score::MIN);
+    }
+
+    #[test]
+    fn require_count_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = require_count(3, sum());
+        aggregator.initialize(&mut world);
+
+        let mut aggregate = |scores| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        assert_eq!(aggregate(smallvec![0.1.into(), 0.2.into()]), Score::MIN);
+        assert_eq!(
+            aggregate(smallvec![0.1.into(), 0.2.into(), 0.25.into()]),
+            Score::new(0.55)
+        );
+        assert_eq!(
+            aggregate(smallvec![0.1.into(), 0.1.into(), 0.1.into(), 0.1.into()]),
+            Score::new(0.4)
+        );
+    }
+
     #[test]
     fn sum_aggregator() {
         let mut world = World::new();
@@ -673,6 +1934,30 @@ mod tests {
         assert_eq!(output, Score::new(0.3));
     }
 
+    #[test]
+    fn quantize_aggregator() {
+        let mut world = World::new();
+
+        let mut aggregator = average().quantize(3);
+        aggregator.initialize(&mut world);
+        let mut aggregate = |scores: SmallVec<[Score; 4]>| {
+            aggregator.aggregate(AggregationCtx {
+                world: &world,
+                aggregation: Aggregation {
+                    target: Entity::PLACEHOLDER,
+                    scores,
+                },
+            })
+        };
+
+        assert_eq!(aggregate(smallvec![0.1.into(), 0.2.into()]), Score::MIN);
+        assert_eq!(
+            aggregate(smallvec![0.4.into(), 0.6.into()]),
+            Score::new(0.5)
+        );
+        assert_eq!(aggregate(smallvec![0.7.into(), 0.9.into()]), Score::MAX);
+    }
+
     #[test]
     fn threshold_aggregator() {
         let mut world = World::new();
@@ -1,8 +1,11 @@
-use alloc::borrow::Cow;
+use alloc::{borrow::Cow, format};
+
+use bevy_math::ops;
 
 use crate::{
-    aggregator::{AggregationCtx, Aggregator},
-    score::Score,
+    aggregator::{AggregationCtx, Aggregator, ShortCircuit},
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
 };
 
 /// Creates an [`Aggregator`] that returns the average score of its children.
@@ -20,14 +23,11 @@ impl Aggregator for AverageAggregator {
     }
 
     fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
-        let len: usize = ctx.aggregation.scores.len();
-
-        if len == 0 {
-            return Score::MIN;
-        }
+        ctx.aggregation.scores.mean()
+    }
 
-        let sum: Score = ctx.aggregation.scores.into_iter().sum();
-        Score::new(sum.get() / len as f32)
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
     }
 }
 
@@ -52,8 +52,15 @@ impl Aggregator for GeometricMeanAggregator {
             return Score::MIN;
         }
 
-        let product = scores.iter().fold(1.0, |acc, score| acc * score.get());
-        Score::new(product.powf(1.0 / len as f32))
+        Score::new(scores.product_f32().powf(1.0 / len as f32))
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn short_circuits(&self) -> Option<ShortCircuit> {
+        Some(ShortCircuit::AnyMin)
     }
 }
 
@@ -83,4 +90,93 @@ impl Aggregator for HarmonicMeanAggregator {
             .fold(0.0, |acc, score| acc + 1.0 / score.get());
         Score::new(len as f32 / sum)
     }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn short_circuits(&self) -> Option<ShortCircuit> {
+        Some(ShortCircuit::AnyMin)
+    }
+}
+
+/// Creates an [`Aggregator`] that returns the root mean square (quadratic
+/// mean) of its children, which weighs higher scores more than [`average`].
+/// If no child scores are provided, [`Score::MIN`] is returned.
+#[doc(alias = "quadratic_mean")]
+pub fn root_mean_square() -> impl Aggregator {
+    RootMeanSquareAggregator
+}
+
+struct RootMeanSquareAggregator;
+
+impl Aggregator for RootMeanSquareAggregator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("root_mean_square")
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        let scores = ctx.aggregation.scores;
+
+        let len = scores.len();
+        if len == 0 {
+            return Score::MIN;
+        }
+
+        let sum = scores
+            .iter()
+            .fold(0.0, |acc, score| acc + score.get() * score.get());
+        Score::new(ops::powf(sum / len as f32, 0.5))
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+}
+
+/// Creates an [`Aggregator`] that returns the power mean (generalized mean)
+/// of its children with the given exponent `p`.
+///
+/// A `p` of 1 is the arithmetic mean ([`average`]), 2 is the
+/// [`root_mean_square`] and -1 is the [`harmonic_mean`]. A `p` of 0 is the
+/// limit as `p` approaches 0, the [`geometric_mean`]. Higher values of `p`
+/// weigh higher scores more.
+///
+/// If no child scores are provided, or if `p` is negative and any child score
+/// is zero, [`Score::MIN`] is returned.
+pub fn power_mean(p: f32) -> impl Aggregator {
+    PowerMeanAggregator(p)
+}
+
+struct PowerMeanAggregator(f32);
+
+impl Aggregator for PowerMeanAggregator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("power_mean({})", self.0))
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        let scores = ctx.aggregation.scores;
+        let p = self.0;
+
+        let len = scores.len();
+        if len == 0 {
+            return Score::MIN;
+        }
+        if p == 0.0 {
+            return Score::new(ops::powf(scores.product_f32(), 1.0 / len as f32));
+        }
+        if p < 0.0 && scores.contains(&Score::MIN) {
+            return Score::MIN;
+        }
+
+        let sum = scores
+            .iter()
+            .fold(0.0, |acc, score| acc + ops::powf(score.get(), p));
+        Score::new(ops::powf(sum / len as f32, 1.0 / p))
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
 }
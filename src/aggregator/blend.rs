@@ -0,0 +1,75 @@
+use alloc::{borrow::Cow, format};
+
+use bevy_ecs::{component::Tick, world::World};
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator, IntoAggregator},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Creates an [`Aggregator`] that aggregates the child scores with both `a`
+/// and `b`, and linearly blends their results as `a * (1 - t) + b * t`. The
+/// blend factor `t` is read from the world on every run and clamped to
+/// `[0, 1]`. For example, `t` could come from a difficulty resource to shift
+/// from averaging options to taking the best one.
+///
+/// As `t` may read anything from the world, this aggregator reports
+/// [`FlowAccess::all`].
+///
+/// # Panics
+///
+/// If `t` returns NaN.
+pub fn blend_aggregators<MA, MB>(
+    a: impl IntoAggregator<MA>,
+    b: impl IntoAggregator<MB>,
+    t: impl Fn(&World) -> f32 + Send + Sync + 'static,
+) -> impl Aggregator {
+    BlendAggregator {
+        a: a.into_aggregator(),
+        b: b.into_aggregator(),
+        t,
+    }
+}
+
+struct BlendAggregator<A: Aggregator, B: Aggregator, T> {
+    a: A,
+    b: B,
+    t: T,
+}
+
+impl<A, B, T> Aggregator for BlendAggregator<A, B, T>
+where
+    A: Aggregator,
+    B: Aggregator,
+    T: Fn(&World) -> f32 + Send + Sync + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "blend_aggregators({}, {})",
+            self.a.name(),
+            self.b.name()
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.a.initialize(world);
+        self.b.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.a.check_change_tick(change_tick);
+        self.b.check_change_tick(change_tick);
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        let t = Score::new((self.t)(ctx.world)).get();
+        let a = self.a.aggregate(ctx.clone()).get();
+        let b = self.b.aggregate(ctx).get();
+        Score::new(a * (1. - t) + b * t)
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::all()
+    }
+}
@@ -0,0 +1,12 @@
+use crate::aggregator::{minimum, Aggregator, IntoAggregator};
+
+/// Creates an [`Aggregator`] that returns the lowest score of its children,
+/// like [`minimum`], but is named "bottleneck" in traces and logs. Its
+/// [`Aggregator::explain`] reports the first child with the lowest score, the
+/// one limiting the aggregated score. If no child scores are provided,
+/// [`Score::MAX`] is returned.
+///
+/// [`Score::MAX`]: crate::score::Score::MAX
+pub fn bottleneck() -> impl Aggregator {
+    minimum().named("bottleneck")
+}
@@ -0,0 +1,68 @@
+use alloc::borrow::Cow;
+use core::ops::RangeInclusive;
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Creates an [`Aggregator`] that subtracts the score of its second child from
+/// the score of its first child, in the order the children were added. The
+/// difference is directional: it saturates at [`Score::MIN`] when the second
+/// score is higher, so swapping the children changes the result. Use
+/// [`abs_difference`] to ignore the order.
+///
+/// If fewer than two child scores are provided, [`Score::MIN`] is returned.
+/// Scores of children beyond the first two are ignored, and [`Flow::validate`]
+/// warns about any other number of children.
+///
+/// [`Flow::validate`]: crate::flow::Flow::validate
+pub fn difference() -> impl Aggregator {
+    DifferenceAggregator { absolute: false }
+}
+
+/// Creates an [`Aggregator`] that returns the absolute difference between the
+/// scores of its first two children, regardless of their order.
+///
+/// If fewer than two child scores are provided, [`Score::MIN`] is returned.
+/// Scores of children beyond the first two are ignored, and [`Flow::validate`]
+/// warns about any other number of children.
+///
+/// [`Flow::validate`]: crate::flow::Flow::validate
+pub fn abs_difference() -> impl Aggregator {
+    DifferenceAggregator { absolute: true }
+}
+
+struct DifferenceAggregator {
+    absolute: bool,
+}
+
+impl Aggregator for DifferenceAggregator {
+    fn name(&self) -> Cow<'static, str> {
+        if self.absolute {
+            Cow::Borrowed("abs_difference")
+        } else {
+            Cow::Borrowed("difference")
+        }
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        let [a, b, ..] = *ctx.aggregation.scores else {
+            return Score::MIN;
+        };
+        if self.absolute {
+            a.max(b) - a.min(b)
+        } else {
+            a - b
+        }
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+        Some(2..=2)
+    }
+}
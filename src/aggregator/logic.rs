@@ -0,0 +1,64 @@
+use alloc::borrow::Cow;
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator, ShortCircuit},
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
+};
+
+/// Creates an [`Aggregator`] that computes the fuzzy AND of its children,
+/// which is the lowest child score. The minimum is used rather than the
+/// product so that `all_of` is idempotent: combining a score with itself
+/// leaves it unchanged. If no child scores are provided, [`Score::MAX`] is
+/// returned, as an empty conjunction is vacuously true.
+pub fn all_of() -> impl Aggregator {
+    AllOfAggregator
+}
+
+struct AllOfAggregator;
+
+impl Aggregator for AllOfAggregator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("all_of")
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        ctx.aggregation
+            .scores
+            .iter()
+            .copied()
+            .min()
+            .unwrap_or(Score::MAX)
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn short_circuits(&self) -> Option<ShortCircuit> {
+        Some(ShortCircuit::AnyMin)
+    }
+}
+
+/// Creates an [`Aggregator`] that computes the fuzzy OR of its children,
+/// which is the highest child score. If no child scores are provided,
+/// [`Score::MIN`] is returned, as an empty disjunction is false.
+pub fn any_of() -> impl Aggregator {
+    AnyOfAggregator
+}
+
+struct AnyOfAggregator;
+
+impl Aggregator for AnyOfAggregator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("any_of")
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        ctx.aggregation.scores.max_score()
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+}
@@ -2,7 +2,8 @@ use alloc::borrow::Cow;
 
 use crate::{
     aggregator::{AggregationCtx, Aggregator},
-    score::Score,
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
 };
 
 /// Creates an [`Aggregator`] that returns the highest score of its children.
@@ -19,10 +20,14 @@ impl Aggregator for MaximumAggregator {
     }
 
     fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
-        ctx.aggregation
-            .scores
-            .into_iter()
-            .max()
-            .unwrap_or(Score::MIN)
+        ctx.aggregation.scores.max_score()
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn explain(&self, scores: &[Score]) -> Option<usize> {
+        scores.max_index()
     }
 }
@@ -2,7 +2,8 @@ use alloc::borrow::Cow;
 
 use crate::{
     aggregator::{AggregationCtx, Aggregator},
-    score::Score,
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
 };
 
 /// Creates an [`Aggregator`] that returns the median score of its children.
@@ -20,20 +21,24 @@ impl Aggregator for MedianAggregator {
 
     fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
         let mut scores = ctx.aggregation.scores;
-        scores.sort_unstable();
-
         let len = scores.len();
         if len == 0 {
             return Score::MIN;
         }
 
-        if len % 2 == 0 {
-            let mid = len / 2;
-            let left = scores[mid - 1];
-            let right = scores[mid];
+        // Partially sorts the scores so that the upper middle score is in
+        // place, with all lower scores before it.
+        let mid = len / 2;
+        let (lower, &mut right, _) = scores.select_nth_unstable(mid);
+        if len.is_multiple_of(2) {
+            let left = lower.max_score();
             Score::new((left.get() + right.get()) / 2.0)
         } else {
-            scores[len / 2]
+            right
         }
     }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
 }
@@ -0,0 +1,51 @@
+use alloc::{borrow::Cow, sync::Arc};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator},
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
+};
+
+/// Creates an [`Aggregator`] that returns the highest score of its children
+/// while `use_max` is `true`, and the lowest score otherwise. Keep a clone of
+/// the handle to switch strategies at runtime without rebuilding the flow.
+/// If no child scores are provided, [`Score::MIN`] is returned.
+///
+/// As the aggregator reads the flag rather than the world, flows containing it
+/// are never skipped by [`Flow::skip_unchanged`].
+///
+/// [`Flow::skip_unchanged`]: crate::flow::Flow::skip_unchanged
+pub fn min_or_max(use_max: Arc<AtomicBool>) -> impl Aggregator {
+    MinOrMaxAggregator(use_max)
+}
+
+struct MinOrMaxAggregator(Arc<AtomicBool>);
+
+impl Aggregator for MinOrMaxAggregator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("min_or_max")
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        let scores = ctx.aggregation.scores;
+        if self.0.load(Ordering::Relaxed) {
+            scores.max_score()
+        } else {
+            scores.min_score()
+        }
+    }
+
+    fn access(&self) -> FlowAccess {
+        // The flag is not part of the world, so changes to it are not tracked.
+        FlowAccess::all()
+    }
+
+    fn explain(&self, scores: &[Score]) -> Option<usize> {
+        if self.0.load(Ordering::Relaxed) {
+            scores.max_index()
+        } else {
+            scores.min_index()
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use alloc::borrow::Cow;
 
 use crate::{
-    aggregator::{AggregationCtx, Aggregator},
-    score::Score,
+    aggregator::{AggregationCtx, Aggregator, ShortCircuit},
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
 };
 
 /// Creates an [`Aggregator`] that returns the lowest score of its children.
-/// If no child scores are provided, [`Score::MIN`] is returned.
+/// If no child scores are provided, [`Score::MAX`] is returned, as nothing
+/// constrains the score.
 pub fn minimum() -> impl Aggregator {
     MinimumAggregator
 }
@@ -21,8 +23,21 @@ impl Aggregator for MinimumAggregator {
     fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
         ctx.aggregation
             .scores
-            .into_iter()
+            .iter()
+            .copied()
             .min()
-            .unwrap_or(Score::MIN)
+            .unwrap_or(Score::MAX)
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn short_circuits(&self) -> Option<ShortCircuit> {
+        Some(ShortCircuit::AnyMin)
+    }
+
+    fn explain(&self, scores: &[Score]) -> Option<usize> {
+        scores.min_index()
     }
 }
@@ -0,0 +1,57 @@
+use alloc::{borrow::Cow, format};
+
+use bevy_ecs::{component::Tick, world::World};
+
+use crate::{
+    aggregator::{Aggregation, AggregationCtx, Aggregator, IntoAggregator},
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
+};
+
+/// Creates an [`Aggregator`] that divides the scores of its children by the
+/// highest child score, so that the highest becomes [`Score::MAX`] and the
+/// rest are rescaled proportionally, and then aggregates them with the given
+/// aggregator. If the highest score is zero, the scores are left unchanged.
+/// If no child scores are provided, [`Score::MIN`] is returned.
+pub fn normalize<M>(inner: impl IntoAggregator<M>) -> impl Aggregator {
+    NormalizeAggregator(inner.into_aggregator())
+}
+
+struct NormalizeAggregator<A: Aggregator>(A);
+
+impl<A: Aggregator> Aggregator for NormalizeAggregator<A> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("normalize({})", self.0.name()))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.0.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.0.check_change_tick(change_tick);
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        let Aggregation { target, mut scores } = ctx.aggregation;
+        if scores.is_empty() {
+            return Score::MIN;
+        }
+
+        let max = scores.max_score();
+        if max > Score::MIN {
+            for score in &mut scores {
+                *score = *score / max;
+            }
+        }
+
+        self.0.aggregate(AggregationCtx {
+            world: ctx.world,
+            aggregation: Aggregation { target, scores },
+        })
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.0.access()
+    }
+}
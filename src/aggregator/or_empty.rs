@@ -0,0 +1,73 @@
+use alloc::{borrow::Cow, format};
+use core::ops::RangeInclusive;
+
+use bevy_ecs::{component::Tick, world::World};
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator, IntoAggregator, ShortCircuit},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Creates an [`Aggregator`] that returns `default` when there are no child
+/// scores, including when all of its children in a [`Flow`] are absent, and
+/// otherwise aggregates them with the given aggregator. Use this
+/// to override the score built-in aggregators return for no scores, such as
+/// to treat having nothing to aggregate as fully satisfied for a [`sum`].
+///
+/// [`sum`]: crate::aggregator::sum
+/// [`Flow`]: crate::flow::Flow
+pub fn or_empty<M>(default: impl Into<Score>, inner: impl IntoAggregator<M>) -> impl Aggregator {
+    OrEmptyAggregator {
+        default: default.into(),
+        inner: inner.into_aggregator(),
+    }
+}
+
+struct OrEmptyAggregator<A: Aggregator> {
+    default: Score,
+    inner: A,
+}
+
+impl<A: Aggregator> Aggregator for OrEmptyAggregator<A> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("or_empty({}, {})", self.default, self.inner.name()))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.inner.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.inner.check_change_tick(change_tick);
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        if ctx.aggregation.scores.is_empty() {
+            return self.default;
+        }
+        self.inner.aggregate(ctx)
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.inner.access()
+    }
+
+    fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+        self.inner.required_arity()
+    }
+
+    fn explain(&self, scores: &[Score]) -> Option<usize> {
+        self.inner.explain(scores)
+    }
+
+    fn aggregates_absent(&self) -> bool {
+        true
+    }
+
+    // Short-circuiting leaves at least the child score that met the condition,
+    // so the inner aggregator's condition still holds.
+    fn short_circuits(&self) -> Option<ShortCircuit> {
+        self.inner.short_circuits()
+    }
+}
@@ -1,12 +1,14 @@
 use alloc::borrow::Cow;
 
 use crate::{
-    aggregator::{AggregationCtx, Aggregator},
-    score::Score,
+    aggregator::{AggregationCtx, Aggregator, ShortCircuit},
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
 };
 
 /// Creates an [`Aggregator`] that multiplies the scores of its children.
-/// If no child scores are provided, [`Score::MIN`] is returned.
+/// If no child scores are provided, [`Score::MAX`] is returned, as that is the
+/// identity of multiplication.
 pub fn product() -> impl Aggregator {
     ProductAggregator
 }
@@ -19,9 +21,14 @@ impl Aggregator for ProductAggregator {
     }
 
     fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
-        if ctx.aggregation.scores.is_empty() {
-            return Score::MIN;
-        }
-        ctx.aggregation.scores.into_iter().product()
+        Score::new(ctx.aggregation.scores.product_f32())
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn short_circuits(&self) -> Option<ShortCircuit> {
+        Some(ShortCircuit::AnyMin)
     }
 }
@@ -0,0 +1,45 @@
+use alloc::borrow::Cow;
+use core::ops::RangeInclusive;
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Creates an [`Aggregator`] that divides the score of its first child by the
+/// score of its second child, in the order the children were added. The ratio
+/// saturates at [`Score::MAX`] when the first score is higher, and is
+/// [`Score::MIN`] when the second score is zero.
+///
+/// If fewer than two child scores are provided, [`Score::MIN`] is returned.
+/// Scores of children beyond the first two are ignored, and [`Flow::validate`]
+/// warns about any other number of children.
+///
+/// [`Flow::validate`]: crate::flow::Flow::validate
+pub fn ratio() -> impl Aggregator {
+    RatioAggregator
+}
+
+struct RatioAggregator;
+
+impl Aggregator for RatioAggregator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("ratio")
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        let [a, b, ..] = *ctx.aggregation.scores else {
+            return Score::MIN;
+        };
+        a / b
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn required_arity(&self) -> Option<RangeInclusive<usize>> {
+        Some(2..=2)
+    }
+}
@@ -0,0 +1,46 @@
+use alloc::{borrow::Cow, format};
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Creates an [`Aggregator`] that folds the child scores with the given
+/// function, starting from `init`. If no child scores are provided, `init` is
+/// returned.
+pub fn reduce(
+    init: impl Into<Score>,
+    f: impl Fn(Score, Score) -> Score + Send + Sync + 'static,
+) -> impl Aggregator {
+    ReduceAggregator {
+        init: init.into(),
+        f,
+    }
+}
+
+struct ReduceAggregator<F> {
+    init: Score,
+    f: F,
+}
+
+impl<F> Aggregator for ReduceAggregator<F>
+where
+    F: Fn(Score, Score) -> Score + Send + Sync + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("reduce({})", self.init))
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        ctx.aggregation
+            .scores
+            .iter()
+            .copied()
+            .fold(self.init, &self.f)
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+}
@@ -0,0 +1,54 @@
+use alloc::{borrow::Cow, format};
+
+use bevy_ecs::{component::Tick, world::World};
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator, IntoAggregator},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Creates an [`Aggregator`] that returns [`Score::MIN`] unless at least `min`
+/// child scores are provided, and otherwise aggregates them with the given
+/// aggregator. Unlike [`IntoAggregator::input_threshold`], which gates on the
+/// values of the scores, this gates on their count.
+pub fn require_count<M>(min: usize, inner: impl IntoAggregator<M>) -> impl Aggregator {
+    RequireCountAggregator {
+        min,
+        inner: inner.into_aggregator(),
+    }
+}
+
+struct RequireCountAggregator<A: Aggregator> {
+    min: usize,
+    inner: A,
+}
+
+impl<A: Aggregator> Aggregator for RequireCountAggregator<A> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "require_count({}, {})",
+            self.min,
+            self.inner.name()
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.inner.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.inner.check_change_tick(change_tick);
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        if ctx.aggregation.scores.len() < self.min {
+            return Score::MIN;
+        }
+        self.inner.aggregate(ctx)
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.inner.access()
+    }
+}
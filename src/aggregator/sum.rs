@@ -2,7 +2,8 @@ use alloc::borrow::Cow;
 
 use crate::{
     aggregator::{AggregationCtx, Aggregator},
-    score::Score,
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
 };
 
 /// Creates an [`Aggregator`] that sums the scores of its children.
@@ -19,9 +20,10 @@ impl Aggregator for SumAggregator {
     }
 
     fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
-        if ctx.aggregation.scores.is_empty() {
-            return Score::MIN;
-        }
-        ctx.aggregation.scores.into_iter().sum()
+        Score::new(ctx.aggregation.scores.sum_f32())
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
     }
 }
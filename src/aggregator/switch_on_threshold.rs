@@ -0,0 +1,74 @@
+use alloc::{borrow::Cow, format};
+
+use bevy_ecs::{component::Tick, world::World};
+
+use crate::{
+    aggregator::{AggregationCtx, Aggregator, IntoAggregator},
+    flow::FlowAccess,
+    score::{Score, ScoreSliceExt},
+};
+
+/// Creates an [`Aggregator`] that aggregates the child scores with `high` if
+/// the highest child score is at least `threshold`, and with `low` otherwise.
+/// For example, `switch_on_threshold(0.5, maximum(), average())` takes the best
+/// option unless all options are weak, in which case it averages them.
+pub fn switch_on_threshold<MH, ML>(
+    threshold: impl Into<Score>,
+    high: impl IntoAggregator<MH>,
+    low: impl IntoAggregator<ML>,
+) -> impl Aggregator {
+    SwitchOnThresholdAggregator {
+        threshold: threshold.into(),
+        high: high.into_aggregator(),
+        low: low.into_aggregator(),
+    }
+}
+
+struct SwitchOnThresholdAggregator<H: Aggregator, L: Aggregator> {
+    threshold: Score,
+    high: H,
+    low: L,
+}
+
+impl<H: Aggregator, L: Aggregator> Aggregator for SwitchOnThresholdAggregator<H, L> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "switch_on_threshold({}, {}, {})",
+            self.threshold,
+            self.high.name(),
+            self.low.name()
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.high.initialize(world);
+        self.low.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.high.check_change_tick(change_tick);
+        self.low.check_change_tick(change_tick);
+    }
+
+    fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
+        if ctx.aggregation.scores.max_score() >= self.threshold {
+            self.high.aggregate(ctx)
+        } else {
+            self.low.aggregate(ctx)
+        }
+    }
+
+    fn access(&self) -> FlowAccess {
+        let mut access = self.high.access();
+        access.extend(&self.low.access());
+        access
+    }
+
+    fn explain(&self, scores: &[Score]) -> Option<usize> {
+        if scores.max_score() >= self.threshold {
+            self.high.explain(scores)
+        } else {
+            self.low.explain(scores)
+        }
+    }
+}
@@ -1,42 +1,150 @@
 //! Provides components for associating entities with flows, actions, and
 //! storing their computed scores.
 
-use alloc::{sync::Arc, vec::Vec};
+use alloc::{collections::VecDeque, sync::Arc, vec::Vec};
+use core::ops::Range;
+#[cfg(feature = "bevy_time")]
+use core::time::Duration;
 
 use bevy_ecs::{
+    archetype::ArchetypeId,
     batching::BatchingStrategy,
-    component::{Component, ComponentId},
+    change_detection::{DetectChanges, DetectChangesMut, Ref, CHECK_TICK_THRESHOLD},
+    component::{Component, ComponentId, Tick},
     entity::Entity,
-    query::QueryState,
+    event::{Event, Events},
+    query::{QueryFilter, QueryState},
+    system::{IntoSystem, Local, System},
     world::{DeferredWorld, World},
 };
+#[cfg(feature = "bevy_time")]
+use bevy_time::Time;
 use bevy_utils::{HashMap, HashSet, Parallel};
 use parking_lot::Mutex;
+use smallvec::SmallVec;
 
 use crate::{
-    flow::WorldFlowExt,
+    flow::{Flow, FlowScratch, Flows, WorldFlowExt},
     label::{
         ActionLabel, FlowLabel, InternedActionLabel, InternedFlowLabel, InternedScoreLabel,
         ScoreLabel,
     },
     score::Score,
-    selector::{IntoSelector, Selector},
+    selector::{IntoSelector, MultiSelector, Selection, SelectionCtx, Selector, StatelessSelector},
 };
 
 /// [`System`] that runs all entity associated flows in parallel.
 ///
+/// Entities whose flow has [`Flow::skip_unchanged`] enabled are only re-scored
+/// if their inputs changed since this system last ran. The scores of entities
+/// with a [`ScoreHistory`] are also recorded there.
+///
+/// With the `diagnostic` feature, the duration of the runs and the number of
+/// entities scored are totaled per frame by the `UtilityAiDiagnosticsPlugin`,
+/// if added.
+///
+/// Use [`run_entity_flows_filtered`] to only run the flows of some entities.
+///
+/// [`System`]: bevy_ecs::system::System
+pub fn run_all_entity_flows(
+    world: &mut World,
+    entities: &mut QueryState<(Entity, Ref<EntityFlow>, Option<&ComputedScores>)>,
+) {
+    run_entity_flows_filtered(world, entities);
+}
+
+/// [`System`] that runs the flows of the entities matching the [`QueryFilter`]
+/// `F` in parallel, such as `With<ActiveAi>` to only score entities near the
+/// player. Otherwise behaves like [`run_all_entity_flows`].
+///
 /// [`System`]: bevy_ecs::system::System
-pub fn run_all_entity_flows(world: &mut World, entities: &mut QueryState<(Entity, &EntityFlow)>) {
-    type ComputedScoresQueue = Vec<(Entity, HashMap<InternedScoreLabel, Score>)>;
+pub fn run_entity_flows_filtered<F: QueryFilter + 'static>(
+    world: &mut World,
+    entities: &mut QueryState<(Entity, Ref<EntityFlow>, Option<&ComputedScores>), F>,
+) {
+    let last_run = world.last_change_tick();
+    run_entity_flows(world, entities, last_run, |_| true);
+}
+
+/// Returns a [`System`] that spreads the scoring of entities across frames, by
+/// partitioning them into `bucket_count` buckets by [`Entity::index`] and only
+/// running the flows of one bucket per run, cycling through the buckets. Each
+/// entity is therefore re-scored every `bucket_count` runs. Otherwise behaves
+/// like [`run_all_entity_flows`].
+///
+/// # Panics
+///
+/// If `bucket_count` is zero.
+pub fn run_entity_flows_staggered(bucket_count: u32) -> impl System<In = (), Out = ()> {
+    assert!(bucket_count > 0, "bucket_count must be non-zero");
+    IntoSystem::into_system(
+        move |world: &mut World,
+              entities: &mut QueryState<(Entity, Ref<EntityFlow>, Option<&ComputedScores>)>,
+              mut buckets: Local<StaggeredBuckets>| {
+            let bucket = buckets.next % bucket_count;
+            buckets.next = (bucket + 1) % bucket_count;
+            buckets
+                .last_runs
+                .resize(bucket_count as usize, Tick::new(0));
+
+            // Changes are tracked since this bucket was last scored, rather than
+            // since this system last ran.
+            let last_run = buckets.last_runs[bucket as usize];
+            buckets.last_runs[bucket as usize] = world.read_change_tick();
+            run_entity_flows(world, entities, last_run, |entity| {
+                entity.index() % bucket_count == bucket
+            });
+        },
+    )
+}
+
+/// State of [`run_entity_flows_staggered`].
+#[derive(Default)]
+struct StaggeredBuckets {
+    /// The bucket to score on the next run.
+    next: u32,
+    /// The change tick at which each bucket was last scored.
+    last_runs: Vec<Tick>,
+}
+
+/// Runs the flows of the entities for which `include` returns `true`, treating
+/// changes since `last_run` as new.
+fn run_entity_flows<F: QueryFilter>(
+    world: &mut World,
+    entities: &mut QueryState<(Entity, Ref<EntityFlow>, Option<&ComputedScores>), F>,
+    last_run: Tick,
+    include: impl Fn(Entity) -> bool + Sync,
+) {
+    #[cfg(feature = "diagnostic")]
+    let start = bevy_utils::Instant::now();
+
+    /// Thread-local buffers reused across all entities run on a thread.
+    #[derive(Default)]
+    struct ComputedScoresQueue {
+        /// Scratch map passed to [`Flow::run_readonly_with_scratch`].
+        scores: HashMap<InternedScoreLabel, Score>,
+        /// Scratch buffer passed to [`Flow::run_readonly_with_scratch`].
+        scratch: FlowScratch,
+        /// The entities that were run, their archetype and the generation of
+        /// their flow when they were run, and their range in `flat`.
+        entities: Vec<(Entity, ArchetypeId, u64, Range<usize>)>,
+        /// The computed scores of all entities, back to back.
+        flat: Vec<(InternedScoreLabel, Score)>,
+    }
 
     let flows = entities
         .iter(world)
-        .map(|(_, flow)| flow.0)
+        .map(|(_, flow, _)| flow.0)
         .collect::<HashSet<_>>();
 
+    let this_run = world.read_change_tick();
+
     // Make sure all of the entity associated flows are initialized so we can
     // skip initialization in the parallel loop.
     for &flow in &flows {
+        if world.get_flow(flow).is_some_and(Flow::is_initialized) {
+            continue;
+        }
         world
             .try_flow_scope(flow, |world, flow| {
                 flow.initialize(world);
@@ -44,7 +152,24 @@ pub fn run_all_entity_flows(world: &mut World, entities: &mut QueryState<(Entity
             .ok();
     }
 
-    drop(flows);
+    // Flows whose entities can be skipped if none of the components they read
+    // changed on them.
+    let skippable = flows
+        .into_iter()
+        .filter(|&flow| {
+            let Some(flow) = world.get_flow(flow) else {
+                return false;
+            };
+            let access = flow.access();
+            flow.skips_unchanged()
+                && !access.reads_all()
+                && access.resource_reads().all(|resource| {
+                    world
+                        .get_resource_change_ticks_by_id(resource)
+                        .is_some_and(|ticks| !ticks.is_changed(last_run, this_run))
+                })
+        })
+        .collect::<HashSet<_>>();
 
     let mut queue = Parallel::<ComputedScoresQueue>::default();
 
@@ -53,22 +178,260 @@ pub fn run_all_entity_flows(world: &mut World, entities: &mut QueryState<(Entity
         .batching_strategy(BatchingStrategy::new().min_batch_size(500))
         .for_each_init(
             || queue.borrow_local_mut(),
-            |queue, (entity, label)| {
+            |queue, (entity, label, computed)| {
+                if !include(entity) {
+                    return;
+                }
                 let Some(flow) = world.get_flow(label.0) else {
                     return;
                 };
 
-                let scores = flow.run_readonly(world, entity);
-                queue.push((entity, scores));
+                let entity_ref = world.entity(entity);
+                let archetype = entity_ref.archetype().id();
+                if skippable.contains(&label.0)
+                    && !label.last_changed().is_newer_than(last_run, this_run)
+                    && computed.is_some_and(|computed| {
+                        // Any change to the flow itself invalidates its scores.
+                        computed.archetype == Some(archetype)
+                            && computed.generation == flow.generation()
+                    })
+                    && !flow
+                        .access()
+                        .is_entity_changed(entity_ref, last_run, this_run)
+                {
+                    return;
+                }
+
+                let queue = &mut **queue;
+                flow.run_readonly_with_scratch(
+                    world,
+                    entity,
+                    &mut queue.scores,
+                    &mut queue.scratch,
+                );
+                let start = queue.flat.len();
+                queue.flat.extend(queue.scores.drain());
+                queue.entities.push((
+                    entity,
+                    archetype,
+                    flow.generation(),
+                    start..queue.flat.len(),
+                ));
             },
         );
 
-    for (entity, scores) in queue.drain() {
-        world
-            .commands()
-            .entity(entity)
-            .insert(ComputedScores(scores));
+    #[cfg(feature = "diagnostic")]
+    let scored = queue.iter_mut().map(|queue| queue.entities.len()).sum();
+
+    for queue in queue.iter_mut() {
+        for (entity, archetype, generation, range) in queue.entities.drain(..) {
+            let scores = &queue.flat[range];
+            if let Some(mut history) = world.get_mut::<ScoreHistory>(entity) {
+                for &(label, score) in scores {
+                    history.record(label, score);
+                }
+            }
+            if let Some(mut computed) = world.get_mut::<ComputedScores>(entity) {
+                computed.replace(scores.iter().copied(), Some(archetype), generation);
+            } else {
+                world.entity_mut(entity).insert(ComputedScores {
+                    scores: scores.iter().copied().collect(),
+                    archetype: None,
+                    generation,
+                });
+            }
+        }
+        queue.flat.clear();
+    }
+
+    #[cfg(feature = "diagnostic")]
+    crate::diagnostic::record_scoring(world, start.elapsed(), scored);
+}
+
+/// [`System`] that runs the [`ActionSelector`] of every entity against its
+/// [`ComputedScores`] and stores the chosen action in [`Actions::current`].
+/// The current action is left unchanged when the selector returns [`None`].
+///
+/// An [`ActionChanged`] event is sent for every entity whose current action
+/// changed, if the event has been registered with the app. Selected actions
+/// are also marked in the entity's [`ActionCooldowns`], if any.
+///
+/// Use [`run_all_action_selectors`] to run the selectors in parallel.
+///
+/// [`System`]: bevy_ecs::system::System
+pub fn run_action_selectors(
+    world: &mut World,
+    entities: &mut QueryState<(Entity, &ActionSelector, &ComputedScores, &Actions)>,
+    mut selected: Local<Vec<(Entity, InternedActionLabel)>>,
+) {
+    selected.clear();
+    for (entity, selector, scores, actions) in entities.iter(world) {
+        let action = selector.select(SelectionCtx {
+            world,
+            selection: Selection {
+                target: entity,
+                scores,
+                actions,
+            },
+        });
+        if let Some(action) = action {
+            selected.push((entity, action));
+        }
+    }
+    apply_selected_actions(world, &selected);
+}
+
+/// [`System`] that runs the [`ActionSelector`]s of all entities in parallel.
+/// Otherwise behaves like [`run_action_selectors`], except that the order of
+/// the [`ActionChanged`] events is unspecified.
+///
+/// Entities sharing a cloned [`ActionSelector`] contend for its lock and are
+/// effectively run one at a time, unless it was created with
+/// [`ActionSelector::stateless`]. Selectors are initialized when their
+/// [`ActionSelector`] is inserted, so this system does not initialize them.
+///
+/// [`System`]: bevy_ecs::system::System
+pub fn run_all_action_selectors(
+    world: &mut World,
+    entities: &mut QueryState<(Entity, &ActionSelector, &ComputedScores, &Actions)>,
+    mut selected: Local<Vec<(Entity, InternedActionLabel)>>,
+) {
+    let mut queue = Parallel::<Vec<(Entity, InternedActionLabel)>>::default();
+
+    entities
+        .par_iter(world)
+        .batching_strategy(BatchingStrategy::new().min_batch_size(500))
+        .for_each_init(
+            || queue.borrow_local_mut(),
+            |queue, (entity, selector, scores, actions)| {
+                let action = selector.select(SelectionCtx {
+                    world,
+                    selection: Selection {
+                        target: entity,
+                        scores,
+                        actions,
+                    },
+                });
+                if let Some(action) = action {
+                    queue.push((entity, action));
+                }
+            },
+        );
+
+    selected.clear();
+    queue.drain_into(&mut selected);
+    apply_selected_actions(world, &selected);
+}
+
+/// Stores the actions selected by [`run_action_selectors`] or
+/// [`run_all_action_selectors`] in the entities' [`Actions`], marks them in
+/// their [`ActionCooldowns`] and sends the [`ActionChanged`] events.
+fn apply_selected_actions(world: &mut World, selected: &[(Entity, InternedActionLabel)]) {
+    let mut changed = Vec::new();
+    for &(entity, action) in selected {
+        let Some(from) = world.get::<Actions>(entity).map(|actions| actions.current) else {
+            continue;
+        };
+        if action != from {
+            world
+                .get_mut::<Actions>(entity)
+                .unwrap()
+                .set_current(action);
+            changed.push(ActionChanged {
+                entity,
+                from,
+                to: action,
+            });
+        }
+    }
+
+    #[cfg(feature = "bevy_time")]
+    if let Some(now) = world.get_resource::<Time>().map(Time::elapsed) {
+        for &(entity, action) in selected {
+            if let Some(mut cooldowns) = world.get_mut::<ActionCooldowns>(entity) {
+                cooldowns.mark_selected(action, now);
+            }
+        }
+    }
+
+    if let Some(mut events) = world.get_resource_mut::<Events<ActionChanged>>() {
+        events.send_batch(changed);
+    }
+}
+
+/// [`System`] that runs the [`MultiActionSelector`] of every entity against its
+/// [`ComputedScores`] and stores the chosen actions in its [`SelectedActions`].
+/// [`SelectedActions`] is only marked as changed when the selection differs
+/// from the previous one.
+///
+/// [`System`]: bevy_ecs::system::System
+pub fn run_multi_action_selectors(
+    world: &mut World,
+    entities: &mut QueryState<(Entity, &MultiActionSelector, &ComputedScores, &Actions)>,
+    mut selected: Local<Vec<(Entity, SelectedActions)>>,
+) {
+    selected.clear();
+    for (entity, selector, scores, actions) in entities.iter(world) {
+        let actions = selector.0.lock().select_many(SelectionCtx {
+            world,
+            selection: Selection {
+                target: entity,
+                scores,
+                actions,
+            },
+        });
+        selected.push((entity, SelectedActions(actions)));
+    }
+    for (entity, actions) in selected.drain(..) {
+        if let Some(mut current) = world.get_mut::<SelectedActions>(entity) {
+            current.set_if_neq(actions);
+        }
+    }
+}
+
+/// [`System`] that checks the change ticks stored by the nodes of all
+/// [`Flows`] and by the selectors of all entities, such as the last run ticks
+/// of system nodes. Bevy only checks the change ticks of the systems in its
+/// schedules, so without this, system nodes in long-running apps can report
+/// changes that did not happen once the world's change tick overflows. Like
+/// [`World::check_change_ticks`], the ticks are only checked once the change
+/// tick has advanced far enough since the last check.
+///
+/// Added by [`UtilityAiPlugin`].
+///
+/// [`UtilityAiPlugin`]: crate::plugin::UtilityAiPlugin
+pub fn check_flow_change_ticks(world: &mut World, mut last_check: Local<Tick>) {
+    let change_tick = world.change_tick();
+    if change_tick.get().wrapping_sub(last_check.get()) < CHECK_TICK_THRESHOLD {
+        return;
+    }
+    *last_check = change_tick;
+
+    if let Some(mut flows) = world.get_resource_mut::<Flows>() {
+        flows
+            .bypass_change_detection()
+            .check_change_ticks(change_tick);
+    }
+    let mut selectors = world.query::<&ActionSelector>();
+    for selector in selectors.iter(world) {
+        selector.check_change_tick(change_tick);
     }
+    let mut selectors = world.query::<&MultiActionSelector>();
+    for selector in selectors.iter(world) {
+        selector.0.lock().check_change_tick(change_tick);
+    }
+}
+
+/// [`Event`] sent by [`run_action_selectors`] when an entity's
+/// [`Actions::current`] changes.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ActionChanged {
+    /// The entity whose action changed.
+    pub entity: Entity,
+    /// The previous action.
+    pub from: InternedActionLabel,
+    /// The newly selected action.
+    pub to: InternedActionLabel,
 }
 
 /// A [`Component`] that associates an entity with a [`Flow`].
@@ -89,41 +452,272 @@ impl EntityFlow {
 }
 
 /// A [`Component`] that associates an entity with a [`Selector`].
-#[derive(Component)]
+///
+/// Cloning an [`ActionSelector`] shares the selector between entities. A
+/// [`Selector`] is locked while it selects an action, so entities sharing one
+/// contend for the lock when selectors are run in parallel by
+/// [`run_all_action_selectors`]. Give each entity its own selector, or share a
+/// [`StatelessSelector`] created with [`ActionSelector::stateless`], which is
+/// run without locking.
+#[derive(Component, Clone)]
 #[component(on_insert = Self::on_insert)]
 #[require(ComputedScores)]
-pub struct ActionSelector(pub Arc<Mutex<dyn Selector>>);
+pub struct ActionSelector(SharedSelector);
+
+/// The selector of an [`ActionSelector`].
+#[derive(Clone)]
+enum SharedSelector {
+    /// A selector that needs exclusive access to select an action.
+    Locked(Arc<Mutex<dyn Selector>>),
+    /// A selector that can be run concurrently without locking.
+    Stateless(Arc<dyn StatelessSelector>),
+}
 
 impl ActionSelector {
     /// Create a new [`ActionSelector`] with the given [`Selector`].
     pub fn new<M>(selector: impl IntoSelector<M>) -> Self {
-        Self(Arc::new(Mutex::new(selector.into_selector())))
+        Self(SharedSelector::Locked(Arc::new(Mutex::new(
+            selector.into_selector(),
+        ))))
+    }
+
+    /// Create a new [`ActionSelector`] with the given [`StatelessSelector`],
+    /// which can be cloned onto any number of entities and is run for all of
+    /// them in parallel without locking.
+    pub fn stateless(selector: impl StatelessSelector) -> Self {
+        Self(SharedSelector::Stateless(Arc::new(selector)))
+    }
+
+    /// Returns the [`Selector`] if it was created with [`ActionSelector::new`],
+    /// or [`None`] if it is stateless.
+    pub fn locked(&self) -> Option<&Arc<Mutex<dyn Selector>>> {
+        match &self.0 {
+            SharedSelector::Locked(selector) => Some(selector),
+            SharedSelector::Stateless(_) => None,
+        }
+    }
+
+    /// Returns the [`StatelessSelector`] if it was created with
+    /// [`ActionSelector::stateless`], or [`None`] otherwise.
+    pub fn as_stateless(&self) -> Option<&Arc<dyn StatelessSelector>> {
+        match &self.0 {
+            SharedSelector::Stateless(selector) => Some(selector),
+            SharedSelector::Locked(_) => None,
+        }
+    }
+
+    /// Selects an action for the given selection context, locking the
+    /// selector if it is not stateless.
+    pub fn select(&self, ctx: SelectionCtx) -> Option<InternedActionLabel> {
+        match &self.0 {
+            SharedSelector::Locked(selector) => selector.lock().select(ctx),
+            SharedSelector::Stateless(selector) => selector.select(ctx),
+        }
+    }
+
+    /// Checks the change ticks stored by the selector. See
+    /// [`Selector::check_change_tick`].
+    fn check_change_tick(&self, change_tick: Tick) {
+        if let SharedSelector::Locked(selector) = &self.0 {
+            selector.lock().check_change_tick(change_tick);
+        }
+    }
+
+    fn on_insert(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+        let SharedSelector::Locked(selector) = &world.get::<ActionSelector>(entity).unwrap().0
+        else {
+            return;
+        };
+        let selector = Arc::clone(selector);
+        world.commands().queue(move |world: &mut World| {
+            selector.lock().initialize(world);
+        });
+    }
+}
+
+/// A [`Component`] that associates an entity with a [`MultiSelector`], for
+/// entities that can perform several actions concurrently. The selected
+/// actions are stored in [`SelectedActions`] by
+/// [`run_multi_action_selectors`].
+#[derive(Component)]
+#[component(on_insert = Self::on_insert)]
+#[require(ComputedScores, SelectedActions)]
+pub struct MultiActionSelector(pub Arc<Mutex<dyn MultiSelector>>);
+
+impl MultiActionSelector {
+    /// Create a new [`MultiActionSelector`] with the given [`MultiSelector`].
+    pub fn new(selector: impl MultiSelector) -> Self {
+        Self(Arc::new(Mutex::new(selector)))
     }
 
     fn on_insert(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
-        let selector = Arc::clone(&world.get::<ActionSelector>(entity).unwrap().0);
+        let selector = Arc::clone(&world.get::<MultiActionSelector>(entity).unwrap().0);
         world.commands().queue(move |world: &mut World| {
             selector.lock().initialize(world);
         });
     }
 }
 
+/// A [`Component`] that stores the actions most recently selected for an
+/// entity by its [`MultiActionSelector`].
+#[derive(Component, Default, Clone, PartialEq, Debug)]
+pub struct SelectedActions(SmallVec<[InternedActionLabel; 4]>);
+
+impl SelectedActions {
+    /// Returns the selected actions, in the order the selector returned them.
+    pub fn as_slice(&self) -> &[InternedActionLabel] {
+        &self.0
+    }
+
+    /// Returns `true` if the given action is selected.
+    pub fn contains(&self, action: impl ActionLabel) -> bool {
+        self.0.contains(&action.intern())
+    }
+}
+
 /// A [`Component`] that stores an entity's computed scores from their most
 /// recent [`Flow`] evaluation.
 ///
 /// [`Flow`]: crate::flow::Flow
 #[derive(Component, Default)]
-pub struct ComputedScores(HashMap<InternedScoreLabel, Score>);
+pub struct ComputedScores {
+    scores: HashMap<InternedScoreLabel, Score>,
+    /// The archetype of the entity when the scores were computed, used to
+    /// detect added or removed components. See [`Flow::skip_unchanged`].
+    archetype: Option<ArchetypeId>,
+    /// The [`Flow::generation`] of the flow the scores were computed with.
+    generation: u64,
+}
 
 impl ComputedScores {
     /// Get the [`Score`] associated with the given [`ScoreLabel`].
     pub fn get(&self, label: impl ScoreLabel) -> Option<Score> {
-        self.0.get(&label.intern()).copied()
+        self.scores.get(&label.intern()).copied()
     }
 
     /// Insert a [`Score`] associated with the given [`ScoreLabel`].
     pub fn insert(&mut self, label: impl ScoreLabel, score: Score) -> Option<Score> {
-        self.0.insert(label.intern(), score)
+        self.scores.insert(label.intern(), score)
+    }
+
+    /// Returns an iterator over all [`ScoreLabel`] and [`Score`] pairs, in
+    /// arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (InternedScoreLabel, Score)> + '_ {
+        self.scores.iter().map(|(&label, &score)| (label, score))
+    }
+
+    /// Returns all [`ScoreLabel`] and [`Score`] pairs, sorted from highest to
+    /// lowest score.
+    pub fn ranked(&self) -> Vec<(InternedScoreLabel, Score)> {
+        let mut ranked = self.iter().collect::<Vec<_>>();
+        ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+        ranked
+    }
+
+    /// Returns the [`ScoreLabel`] and [`Score`] pair with the highest score, if
+    /// any.
+    pub fn best(&self) -> Option<(InternedScoreLabel, Score)> {
+        self.iter().max_by_key(|&(_, score)| score)
+    }
+
+    /// Replaces all scores with the given ones, computed while the entity was
+    /// in the given archetype by a flow of the given generation.
+    pub(crate) fn replace(
+        &mut self,
+        scores: impl IntoIterator<Item = (InternedScoreLabel, Score)>,
+        archetype: Option<ArchetypeId>,
+        generation: u64,
+    ) {
+        self.scores.clear();
+        self.scores.extend(scores);
+        self.archetype = archetype;
+        self.generation = generation;
+    }
+}
+
+/// Returns the given entities paired with their [`ComputedScores`] for the
+/// given [`ScoreLabel`], sorted from highest to lowest score, such as to pick
+/// the ally that most needs healing. Entities without the component or a
+/// score for the label are ranked with [`Score::MIN`]. Entities with equal
+/// scores keep their relative order.
+pub fn rank_entities_by(
+    world: &World,
+    label: impl ScoreLabel,
+    entities: impl IntoIterator<Item = Entity>,
+) -> Vec<(Entity, Score)> {
+    let label = label.intern();
+    let mut ranked = entities
+        .into_iter()
+        .map(|entity| {
+            let score = world
+                .get::<ComputedScores>(entity)
+                .and_then(|scores| scores.get(label))
+                .unwrap_or(Score::MIN);
+            (entity, score)
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+    ranked
+}
+
+/// A [`Component`] that retains the most recent scores computed for an entity
+/// by [`run_all_entity_flows`], up to a fixed number per [`ScoreLabel`]. This
+/// is opt-in and useful for debugging, such as graphing scores over time to
+/// spot oscillation.
+///
+/// Scores are only recorded when the entity is scored, so an entity skipped by
+/// [`Flow::skip_unchanged`] records nothing for that run.
+///
+/// [`Flow::skip_unchanged`]: crate::flow::Flow::skip_unchanged
+#[derive(Component)]
+pub struct ScoreHistory {
+    /// The maximum number of scores retained per label.
+    capacity: usize,
+    /// The retained scores of each label, from oldest to newest.
+    scores: HashMap<InternedScoreLabel, VecDeque<Score>>,
+}
+
+impl ScoreHistory {
+    /// Creates an empty history retaining up to `capacity` scores per
+    /// [`ScoreLabel`].
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            scores: HashMap::default(),
+        }
+    }
+
+    /// Returns the maximum number of scores retained per [`ScoreLabel`].
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns an iterator over the retained scores of the given
+    /// [`ScoreLabel`], from oldest to newest.
+    pub fn recent(&self, label: impl ScoreLabel) -> impl Iterator<Item = Score> + '_ {
+        self.scores
+            .get(&label.intern())
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Appends a score for the given [`ScoreLabel`], discarding its oldest
+    /// score if the capacity is exceeded.
+    pub fn record(&mut self, label: impl ScoreLabel, score: Score) {
+        if self.capacity == 0 {
+            return;
+        }
+        let scores = self.scores.entry(label.intern()).or_default();
+        if scores.len() == self.capacity {
+            scores.pop_front();
+        }
+        scores.push_back(score);
+    }
+
+    /// Removes all retained scores.
+    pub fn clear(&mut self) {
+        self.scores.clear();
     }
 }
 
@@ -131,8 +725,9 @@ impl ComputedScores {
 /// labeled scores.
 #[derive(Component)]
 pub struct Actions {
-    /// The actions to pick when the associated score is selected.
-    actions: HashMap<InternedScoreLabel, InternedActionLabel>,
+    /// The actions to pick when the associated score is selected, in insertion
+    /// order.
+    actions: Vec<(InternedScoreLabel, InternedActionLabel)>,
     /// The default action when no other actions are available.
     default: InternedActionLabel,
     /// The current action.
@@ -144,21 +739,47 @@ impl Actions {
     pub fn new(default: impl ActionLabel) -> Self {
         let default = default.intern();
         Self {
-            actions: HashMap::new(),
+            actions: Vec::new(),
             default,
             current: default,
         }
     }
 
-    /// Adds an [`ActionLabel`] associated with the given [`ScoreLabel`].
+    /// Adds an [`ActionLabel`] associated with the given [`ScoreLabel`]. If the
+    /// score label already has an action, it is replaced in place.
     pub fn with(mut self, score: impl ScoreLabel, action: impl ActionLabel) -> Self {
-        self.actions.insert(score.intern(), action.intern());
+        let (score, action) = (score.intern(), action.intern());
+        match self.actions.iter_mut().find(|(s, _)| *s == score) {
+            Some((_, existing)) => *existing = action,
+            None => self.actions.push((score, action)),
+        }
         self
     }
 
     /// Gets the [`ActionLabel`] associated with the given [`ScoreLabel`], if any.
     pub fn action(&self, score: impl ScoreLabel) -> Option<impl ActionLabel> {
-        self.actions.get(&score.intern()).copied()
+        let score = score.intern();
+        self.actions
+            .iter()
+            .find(|(s, _)| *s == score)
+            .map(|&(_, action)| action)
+    }
+
+    /// Returns an iterator over the [`ScoreLabel`] and [`ActionLabel`] pairs,
+    /// in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (InternedScoreLabel, InternedActionLabel)> + '_ {
+        self.actions.iter().copied()
+    }
+
+    /// Returns the [`ScoreLabel`]s mapped to an action that the given [`Flow`]
+    /// has no labeled node for. Actions mapped to these labels can never be
+    /// selected when scoring with that flow.
+    pub fn validate_against(&self, flow: &Flow) -> Vec<InternedScoreLabel> {
+        self.actions
+            .iter()
+            .map(|&(score, _)| score)
+            .filter(|&score| !flow.has_label(score))
+            .collect()
     }
 
     /// Gets the current [`ActionLabel`].
@@ -166,8 +787,469 @@ impl Actions {
         self.current
     }
 
+    /// Sets the current [`ActionLabel`].
+    pub fn set_current(&mut self, action: impl ActionLabel) {
+        self.current = action.intern();
+    }
+
     /// Gets the default [`ActionLabel`].
     pub fn default(&self) -> impl ActionLabel {
         self.default
     }
+
+    /// Returns a copy of these actions without the score and action pairs for
+    /// which `keep` returns `false`. The default and current actions are kept.
+    #[cfg(feature = "bevy_time")]
+    pub(crate) fn filtered(&self, mut keep: impl FnMut(InternedActionLabel) -> bool) -> Self {
+        Self {
+            actions: self
+                .actions
+                .iter()
+                .copied()
+                .filter(|&(_, action)| keep(action))
+                .collect(),
+            default: self.default,
+            current: self.current,
+        }
+    }
+}
+
+/// A [`Component`] that stores per-action cooldowns for an entity, along with
+/// when each action was last selected.
+///
+/// Actions are marked as selected by [`run_action_selectors`] using the
+/// elapsed time of the [`Time`] resource. Wrap a selector with [`cooldown`] to
+/// skip actions that are still cooling down.
+///
+/// [`Time`]: bevy_time::Time
+/// [`cooldown`]: crate::selector::cooldown
+#[cfg(feature = "bevy_time")]
+#[derive(Component, Default)]
+pub struct ActionCooldowns {
+    /// The cooldown of each action.
+    cooldowns: HashMap<InternedActionLabel, Duration>,
+    /// The elapsed time at which each action was last selected.
+    last_selected: HashMap<InternedActionLabel, Duration>,
+}
+
+#[cfg(feature = "bevy_time")]
+impl ActionCooldowns {
+    /// Sets the cooldown of the given [`ActionLabel`].
+    pub fn with(mut self, action: impl ActionLabel, cooldown: Duration) -> Self {
+        self.cooldowns.insert(action.intern(), cooldown);
+        self
+    }
+
+    /// Returns the cooldown of the given [`ActionLabel`], if any.
+    pub fn cooldown(&self, action: impl ActionLabel) -> Option<Duration> {
+        self.cooldowns.get(&action.intern()).copied()
+    }
+
+    /// Returns how long the given [`ActionLabel`] still has to cool down at
+    /// elapsed time `now`, or [`Duration::ZERO`] if it is ready.
+    pub fn remaining(&self, action: impl ActionLabel, now: Duration) -> Duration {
+        let action = action.intern();
+        match (self.cooldowns.get(&action), self.last_selected.get(&action)) {
+            (Some(&cooldown), Some(&selected)) => (selected + cooldown).saturating_sub(now),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Returns `true` if the given [`ActionLabel`] is not cooling down at
+    /// elapsed time `now`.
+    pub fn is_ready(&self, action: impl ActionLabel, now: Duration) -> bool {
+        self.remaining(action, now).is_zero()
+    }
+
+    /// Records that the given [`ActionLabel`] was selected at elapsed time
+    /// `now`. Does nothing if the action has no cooldown.
+    pub fn mark_selected(&mut self, action: impl ActionLabel, now: Duration) {
+        let action = action.intern();
+        if self.cooldowns.contains_key(&action) {
+            self.last_selected.insert(action, now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::Cow, sync::Arc, vec::Vec};
+
+    use bevy_ecs::{
+        component::Component, entity::Entity, event::Events, query::With, world::World,
+    };
+    use bevy_utils::HashMap;
+    use parking_lot::Mutex;
+
+    use crate::{
+        self as evergreen_utility_ai,
+        component::{
+            rank_entities_by, run_action_selectors, run_all_action_selectors, run_all_entity_flows,
+            run_entity_flows_filtered, run_entity_flows_staggered, ActionChanged, ActionSelector,
+            Actions, ComputedScores, EntityFlow, ScoreHistory,
+        },
+        evaluator::{constant, target, EvaluationCtx, Evaluator, IntoEvaluator},
+        flow::{Flow, WorldFlowExt},
+        label::{ActionLabel, ScoreLabel},
+        macros::{ActionLabel, ScoreLabel},
+        score::Score,
+        selector::highest_score,
+        tests::{Health, TestFlow},
+    };
+
+    #[derive(ScoreLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    struct TestScore(u8);
+
+    #[derive(ActionLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    enum TestAction {
+        Idle,
+        Eat,
+        Flee,
+    }
+
+    #[test]
+    fn validate_actions_against_flow() {
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes(constant(0.5).label(TestScore(0)));
+
+        let actions = Actions::new(TestAction::Idle)
+            .with(TestScore(0), TestAction::Eat)
+            .with(TestScore(1), TestAction::Flee);
+
+        assert_eq!(actions.validate_against(&flow), [TestScore(1).intern()]);
+    }
+
+    #[test]
+    fn computed_scores_ranked() {
+        let mut scores = ComputedScores::default();
+        scores.insert(TestScore(0), Score::new(0.4));
+        scores.insert(TestScore(1), Score::new(0.9));
+        scores.insert(TestScore(2), Score::new(0.1));
+
+        assert_eq!(
+            scores.ranked(),
+            [
+                (TestScore(1).intern(), Score::new(0.9)),
+                (TestScore(0).intern(), Score::new(0.4)),
+                (TestScore(2).intern(), Score::new(0.1)),
+            ]
+        );
+        assert_eq!(
+            scores.best(),
+            Some((TestScore(1).intern(), Score::new(0.9)))
+        );
+        assert_eq!(scores.iter().count(), 3);
+
+        assert_eq!(ComputedScores::default().best(), None);
+    }
+
+    #[test]
+    fn rank_entities() {
+        let mut world = World::new();
+        let mut spawn = |scores: &[(TestScore, f32)]| {
+            let mut computed = ComputedScores::default();
+            for &(label, score) in scores {
+                computed.insert(label, Score::new(score));
+            }
+            world.spawn(computed).id()
+        };
+        let a = spawn(&[(TestScore(0), 0.4), (TestScore(1), 0.9)]);
+        let b = spawn(&[(TestScore(0), 0.7)]);
+        let c = spawn(&[(TestScore(1), 0.2)]);
+        let d = spawn(&[(TestScore(0), 0.4)]);
+        let unscored = world.spawn_empty().id();
+
+        assert_eq!(
+            rank_entities_by(&world, TestScore(0), [a, b, c, d, unscored]),
+            [
+                (b, Score::new(0.7)),
+                (a, Score::new(0.4)),
+                (d, Score::new(0.4)),
+                (c, Score::MIN),
+                (unscored, Score::MIN),
+            ]
+        );
+        assert_eq!(
+            rank_entities_by(&world, TestScore(1), [c, a]),
+            [(a, Score::new(0.9)), (c, Score::new(0.2))]
+        );
+        assert!(rank_entities_by(&world, TestScore(0), []).is_empty());
+    }
+
+    #[test]
+    fn run_all_entity_flows_overwrites_scores() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.5).label(TestScore(0)),
+                target::<Health>().label(TestScore(1)),
+            ),
+        );
+        let npc = world.spawn((EntityFlow::new(TestFlow), Health(50))).id();
+        let idle = world.spawn(EntityFlow::new(TestFlow)).id();
+
+        world.run_system_cached(run_all_entity_flows).unwrap();
+        let scores = world.get::<ComputedScores>(npc).unwrap();
+        assert_eq!(scores.get(TestScore(0)), Some(Score::new(0.5)));
+        assert_eq!(scores.get(TestScore(1)), Some(Score::new(0.5)));
+        let scores = world.get::<ComputedScores>(idle).unwrap();
+        assert_eq!(scores.get(TestScore(0)), Some(Score::new(0.5)));
+        assert_eq!(scores.get(TestScore(1)), None);
+
+        // Scores from earlier runs are replaced, not merged.
+        world.entity_mut(npc).remove::<Health>();
+        world
+            .get_mut::<ComputedScores>(npc)
+            .unwrap()
+            .insert(TestScore(2), Score::MAX);
+        world.run_system_cached(run_all_entity_flows).unwrap();
+        let scores = world.get::<ComputedScores>(npc).unwrap();
+        assert_eq!(scores.iter().count(), 1);
+        assert_eq!(scores.get(TestScore(1)), None);
+        assert_eq!(scores.get(TestScore(2)), None);
+    }
+
+    #[test]
+    fn run_all_entity_flows_skips_unchanged() {
+        let mut world = World::new();
+        world.add_nodes(TestFlow, target::<Health>().label(TestScore(0)));
+        world.flow_scope(TestFlow, |_, flow| {
+            flow.skip_unchanged(true);
+        });
+        let npc = world.spawn((EntityFlow::new(TestFlow), Health(50))).id();
+
+        let run = |world: &mut World| {
+            world
+                .get_mut::<ComputedScores>(npc)
+                .unwrap()
+                .insert(TestScore(1), Score::MAX);
+            world.run_system_cached(run_all_entity_flows).unwrap();
+            // The extra score is only cleared if the entity was re-scored.
+            let scores = world.get::<ComputedScores>(npc).unwrap();
+            (scores.get(TestScore(0)), scores.get(TestScore(1)).is_none())
+        };
+
+        assert_eq!(run(&mut world), (Some(Score::new(0.5)), true));
+        assert_eq!(run(&mut world), (Some(Score::new(0.5)), false));
+
+        // Running the flow elsewhere does not count as modifying it.
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.score(TestScore(0)), Some(Score::new(0.5)));
+        assert_eq!(run(&mut world), (Some(Score::new(0.5)), false));
+
+        // Scores still update when the inputs change.
+        world.get_mut::<Health>(npc).unwrap().0 = 80;
+        assert_eq!(run(&mut world), (Some(Score::new(0.8)), true));
+        assert_eq!(run(&mut world), (Some(Score::new(0.8)), false));
+
+        world.entity_mut(npc).remove::<Health>();
+        assert_eq!(run(&mut world), (None, true));
+        world.entity_mut(npc).insert(Health(20));
+        assert_eq!(run(&mut world), (Some(Score::new(0.2)), true));
+
+        // Modifying the flow re-scores all of its entities.
+        world.flow_scope(TestFlow, |_, flow| {
+            flow.add_nodes(constant(0.5).label(TestScore(2)));
+        });
+        assert_eq!(run(&mut world), (Some(Score::new(0.2)), true));
+    }
+
+    #[test]
+    fn run_entity_flows_filtered_skips_untagged() {
+        #[derive(Component)]
+        struct ActiveAi;
+
+        let mut world = World::new();
+        world.add_nodes(TestFlow, constant(0.5).label(TestScore(0)));
+        let active = world.spawn((EntityFlow::new(TestFlow), ActiveAi)).id();
+        let inactive = world.spawn(EntityFlow::new(TestFlow)).id();
+
+        world
+            .run_system_cached(run_entity_flows_filtered::<With<ActiveAi>>)
+            .unwrap();
+        let scores = world.get::<ComputedScores>(active).unwrap();
+        assert_eq!(scores.get(TestScore(0)), Some(Score::new(0.5)));
+        let scores = world.get::<ComputedScores>(inactive).unwrap();
+        assert_eq!(scores.iter().count(), 0);
+    }
+
+    #[test]
+    fn run_entity_flows_staggered_cycles_buckets() {
+        #[derive(Default)]
+        struct CountingEvaluator(Arc<Mutex<HashMap<Entity, usize>>>);
+
+        impl Evaluator for CountingEvaluator {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Borrowed("counting")
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                *self.0.lock().entry(ctx.evaluation.target).or_default() += 1;
+                Score::MAX
+            }
+        }
+
+        let mut world = World::new();
+        let evaluator = CountingEvaluator::default();
+        let counts = evaluator.0.clone();
+        world.add_nodes(TestFlow, evaluator.label(TestScore(0)));
+        let npcs = (0..7)
+            .map(|_| world.spawn(EntityFlow::new(TestFlow)).id())
+            .collect::<Vec<_>>();
+        let system = world.register_system(run_entity_flows_staggered(3));
+
+        for cycle in 1..=2 {
+            for _ in 0..3 {
+                world.run_system(system).unwrap();
+            }
+            // Every entity is scored exactly once per cycle.
+            let counts = counts.lock();
+            assert!(npcs.iter().all(|npc| counts.get(npc) == Some(&cycle)));
+        }
+
+        // Only one bucket is scored per run.
+        world.run_system(system).unwrap();
+        let counts = counts.lock();
+        assert_eq!(counts.values().filter(|&&count| count == 3).count(), 3);
+    }
+
+    #[test]
+    fn run_all_action_selectors_matches_serial() {
+        let mut world = World::new();
+        world.init_resource::<Events<ActionChanged>>();
+        let npcs = (0..1000)
+            .map(|i| {
+                let mut scores = ComputedScores::default();
+                scores.insert(TestScore(0), Score::new(if i % 2 == 0 { 0.9 } else { 0.1 }));
+                scores.insert(TestScore(1), Score::new(0.5));
+                let actions = Actions::new(TestAction::Idle)
+                    .with(TestScore(0), TestAction::Eat)
+                    .with(TestScore(1), TestAction::Flee);
+                world
+                    .spawn((ActionSelector::new(highest_score()), scores, actions))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+        world.flush();
+
+        let run = |world: &mut World| {
+            world.run_system_cached(run_all_action_selectors).unwrap();
+            let mut changed = world
+                .resource_mut::<Events<ActionChanged>>()
+                .drain()
+                .map(|event| event.entity)
+                .collect::<Vec<_>>();
+            changed.sort();
+            changed
+        };
+
+        let current = |world: &World| {
+            npcs.iter()
+                .map(|&npc| world.get::<Actions>(npc).unwrap().current)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(&mut world), npcs);
+        let parallel = current(&world);
+        for (i, action) in parallel.iter().enumerate() {
+            let expected = if i % 2 == 0 {
+                TestAction::Eat
+            } else {
+                TestAction::Flee
+            };
+            assert_eq!(*action, expected.intern());
+        }
+        // Unchanged actions send no events.
+        assert!(run(&mut world).is_empty());
+
+        // The serial system selects the same actions.
+        for &npc in &npcs {
+            world
+                .get_mut::<Actions>(npc)
+                .unwrap()
+                .set_current(TestAction::Idle);
+        }
+        world.run_system_cached(run_action_selectors).unwrap();
+        assert_eq!(current(&world), parallel);
+    }
+
+    #[test]
+    fn shared_action_selectors() {
+        let mut world = World::new();
+        let stateless = ActionSelector::stateless(highest_score());
+        let locked = ActionSelector::new(highest_score());
+        assert!(stateless.locked().is_none() && stateless.as_stateless().is_some());
+        assert!(locked.locked().is_some() && locked.as_stateless().is_none());
+        let npcs = (0..1000)
+            .map(|i| {
+                let mut scores = ComputedScores::default();
+                scores.insert(TestScore(0), Score::new(if i % 3 == 0 { 0.9 } else { 0.1 }));
+                scores.insert(TestScore(1), Score::new(0.5));
+                let actions = Actions::new(TestAction::Idle)
+                    .with(TestScore(0), TestAction::Eat)
+                    .with(TestScore(1), TestAction::Flee);
+                let selector = if i % 2 == 0 { &stateless } else { &locked };
+                world.spawn((selector.clone(), scores, actions)).id()
+            })
+            .collect::<Vec<_>>();
+        world.flush();
+
+        world.run_system_cached(run_all_action_selectors).unwrap();
+        for (i, &npc) in npcs.iter().enumerate() {
+            let expected = if i % 3 == 0 {
+                TestAction::Eat
+            } else {
+                TestAction::Flee
+            };
+            let actions = world.get::<Actions>(npc).unwrap();
+            assert_eq!(actions.current, expected.intern());
+        }
+    }
+
+    #[test]
+    fn score_history() {
+        let mut world = World::new();
+        world.add_nodes(TestFlow, target::<Health>().label(TestScore(0)));
+        let npc = world
+            .spawn((EntityFlow::new(TestFlow), ScoreHistory::new(3), Health(10)))
+            .id();
+
+        for health in [20, 30, 40, 50] {
+            world.run_system_cached(run_all_entity_flows).unwrap();
+            world.get_mut::<Health>(npc).unwrap().0 = health;
+        }
+
+        let history = world.get::<ScoreHistory>(npc).unwrap();
+        assert_eq!(
+            history.recent(TestScore(0)).collect::<Vec<_>>(),
+            [Score::new(0.2), Score::new(0.3), Score::new(0.4)]
+        );
+        assert_eq!(history.recent(TestScore(1)).count(), 0);
+    }
+
+    #[test]
+    fn score_entity() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.5).label(TestScore(0)),
+                target::<Health>().label(TestScore(1)),
+            ),
+        );
+        let npc = world.spawn(Health(30)).id();
+
+        world.score_entity(TestFlow, npc);
+        let scores = world.get::<ComputedScores>(npc).unwrap();
+        assert_eq!(scores.get(TestScore(0)), Some(Score::new(0.5)));
+        assert_eq!(scores.get(TestScore(1)), Some(Score::new(0.3)));
+
+        // Existing scores are replaced.
+        world.entity_mut(npc).remove::<Health>();
+        world.score_entity(TestFlow, npc);
+        let scores = world.get::<ComputedScores>(npc).unwrap();
+        assert_eq!(scores.iter().count(), 1);
+        assert_eq!(scores.get(TestScore(1)), None);
+    }
 }
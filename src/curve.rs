@@ -0,0 +1,165 @@
+//! Provides helpers for building [`Curve`]s over [`Score`]s, for use with the
+//! `curve` combinators of evaluators and aggregators, along with presets for
+//! common response curves.
+
+use alloc::vec::Vec;
+
+use bevy_math::{
+    curve::{FunctionCurve, Interval},
+    ops, Curve,
+};
+
+use crate::score::Score;
+
+/// Creates a piecewise-linear [`Curve`] over [`Score::INTERVAL`] from
+/// `(input, output)` keyframes, linearly interpolating between adjacent
+/// keyframes.
+///
+/// Both inputs and outputs are clamped to `[0, 1]`, and the keyframes are
+/// sorted by input. Inputs before the first keyframe or after the last one
+/// take the output of that keyframe. With no keyframes, the curve is
+/// [`Score::MIN`] everywhere.
+///
+/// # Panics
+///
+/// If any input or output is NaN.
+pub fn score_curve(points: &[(f32, f32)]) -> impl Curve<Score> + Clone + Send + Sync + 'static {
+    let mut points = points
+        .iter()
+        .map(|&(input, output)| (Score::new(input).get(), Score::new(output)))
+        .collect::<Vec<_>>();
+    points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    KeyframeCurve { points }
+}
+
+/// Creates a [`Curve`] that returns its input unchanged.
+pub fn linear() -> impl Curve<Score> + Send + Sync + 'static {
+    FunctionCurve::new(Score::INTERVAL, Score::new)
+}
+
+/// Creates a [`Curve`] that squares its input, staying low until the input is
+/// high.
+pub fn quadratic() -> impl Curve<Score> + Send + Sync + 'static {
+    exponential(2.)
+}
+
+/// Creates a [`Curve`] that raises its input to the given power. Exponents
+/// above one bend the curve down and exponents below one bend it up.
+pub fn exponential(exponent: f32) -> impl Curve<Score> + Send + Sync + 'static {
+    FunctionCurve::new(Score::INTERVAL, move |x| Score::new(ops::powf(x, exponent)))
+}
+
+/// Creates an S-shaped [`Curve`] that rises around `midpoint`, more sharply the
+/// higher `steepness` is.
+pub fn logistic(midpoint: f32, steepness: f32) -> impl Curve<Score> + Send + Sync + 'static {
+    FunctionCurve::new(Score::INTERVAL, move |x| {
+        Score::new(1. / (1. + ops::exp(-steepness * (x - midpoint))))
+    })
+}
+
+/// Creates the inverse of the [`logistic`] curve with the same parameters,
+/// which rises sharply near both ends and flattens out around `midpoint`.
+///
+/// # Panics
+///
+/// If `steepness` is zero.
+pub fn logit(midpoint: f32, steepness: f32) -> impl Curve<Score> + Send + Sync + 'static {
+    assert!(steepness != 0., "steepness must be non-zero");
+    FunctionCurve::new(Score::INTERVAL, move |x| {
+        Score::new(midpoint + ops::ln(x / (1. - x)) / steepness)
+    })
+}
+
+#[derive(Clone)]
+struct KeyframeCurve {
+    /// The `(input, output)` keyframes, sorted by input.
+    points: Vec<(f32, Score)>,
+}
+
+impl Curve<Score> for KeyframeCurve {
+    fn domain(&self) -> Interval {
+        Score::INTERVAL
+    }
+
+    fn sample_unchecked(&self, t: f32) -> Score {
+        let next = self.points.partition_point(|&(input, _)| input <= t);
+        match (
+            next.checked_sub(1).map(|i| self.points[i]),
+            self.points.get(next),
+        ) {
+            (None, None) => Score::MIN,
+            (Some((_, output)), None) | (None, Some(&(_, output))) => output,
+            (Some((x0, y0)), Some(&(x1, y1))) => {
+                let s = (t - x0) / (x1 - x0);
+                Score::new(y0.get() + (y1.get() - y0.get()) * s)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::Curve;
+
+    use crate::{
+        curve::{exponential, linear, logistic, logit, quadratic, score_curve},
+        score::Score,
+    };
+
+    #[test]
+    fn score_curve_keyframes() {
+        let curve = score_curve(&[(0.25, 0.0), (0.5, 1.0), (0.75, 0.5)]);
+
+        // Between keyframes.
+        assert_eq!(curve.sample(0.375), Some(Score::new(0.5)));
+        assert_eq!(curve.sample(0.625), Some(Score::new(0.75)));
+        // On keyframes.
+        assert_eq!(curve.sample(0.5), Some(Score::MAX));
+        assert_eq!(curve.sample(0.75), Some(Score::new(0.5)));
+        // Outside of the keyframes.
+        assert_eq!(curve.sample(0.0), Some(Score::MIN));
+        assert_eq!(curve.sample(1.0), Some(Score::new(0.5)));
+        // Outside of the domain.
+        assert_eq!(curve.sample(1.5), None);
+    }
+
+    #[test]
+    fn score_curve_clamps_and_sorts() {
+        let curve = score_curve(&[(1.5, 2.0), (-1.0, -1.0)]);
+        assert_eq!(curve.sample(0.0), Some(Score::MIN));
+        assert_eq!(curve.sample(0.25), Some(Score::new(0.25)));
+        assert_eq!(curve.sample(1.0), Some(Score::MAX));
+
+        let curve = score_curve(&[]);
+        assert_eq!(curve.sample(0.5), Some(Score::MIN));
+    }
+
+    #[test]
+    fn curve_presets() {
+        let linear = linear();
+        assert_eq!(linear.sample(0.0), Some(Score::MIN));
+        assert_eq!(linear.sample(0.25), Some(Score::new(0.25)));
+        assert_eq!(linear.sample(1.0), Some(Score::MAX));
+
+        let quadratic = quadratic();
+        assert_eq!(quadratic.sample(0.5), Some(Score::new(0.25)));
+        assert_eq!(quadratic.sample(1.0), Some(Score::MAX));
+
+        let exponential = exponential(0.5);
+        assert_eq!(exponential.sample(0.0), Some(Score::MIN));
+        assert_eq!(exponential.sample(0.25), Some(Score::new(0.5)));
+        assert_eq!(exponential.sample(1.0), Some(Score::MAX));
+
+        let logistic = logistic(0.5, 10.);
+        assert_eq!(logistic.sample(0.5), Some(Score::new(0.5)));
+        assert!(logistic.sample(0.0).unwrap() < 0.01);
+        assert!(logistic.sample(1.0).unwrap() > 0.99);
+
+        let logit = logit(0.5, 10.);
+        assert_eq!(logit.sample(0.5), Some(Score::new(0.5)));
+        assert_eq!(logit.sample(0.0), Some(Score::MIN));
+        assert_eq!(logit.sample(1.0), Some(Score::MAX));
+        assert!(logit.sample(0.25).unwrap() < 0.5);
+        assert!(logit.sample(0.75).unwrap() > 0.5);
+    }
+}
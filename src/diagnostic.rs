@@ -0,0 +1,176 @@
+//! Provides the [`UtilityAiDiagnosticsPlugin`] for measuring how long scoring
+//! takes with bevy's diagnostics.
+
+use core::time::Duration;
+
+use bevy_app::{App, Last, Plugin};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::{
+    system::{ResMut, Resource},
+    world::World,
+};
+
+/// [`Plugin`] that registers [`Diagnostic`]s measuring the runs of
+/// [`run_all_entity_flows`], [`run_entity_flows_filtered`] and
+/// [`run_entity_flows_staggered`] in each frame: the total time they took in
+/// milliseconds, and the total number of entities whose flows were run,
+/// excluding those skipped by [`Flow::skip_unchanged`]. Frames in which none of
+/// them ran are not measured.
+///
+/// Add `LogDiagnosticsPlugin` to print them, or read them from the
+/// [`DiagnosticsStore`].
+///
+/// [`run_all_entity_flows`]: crate::component::run_all_entity_flows
+/// [`run_entity_flows_filtered`]: crate::component::run_entity_flows_filtered
+/// [`run_entity_flows_staggered`]: crate::component::run_entity_flows_staggered
+/// [`Flow::skip_unchanged`]: crate::flow::Flow::skip_unchanged
+/// [`DiagnosticsStore`]: bevy_diagnostic::DiagnosticsStore
+#[derive(Default)]
+pub struct UtilityAiDiagnosticsPlugin;
+
+impl UtilityAiDiagnosticsPlugin {
+    /// The time it took to score all entities in a frame, in milliseconds.
+    pub const SCORING_TIME: DiagnosticPath = DiagnosticPath::const_new("utility_ai/scoring_time");
+    /// The number of entities whose flows were run in a frame.
+    pub const SCORED_ENTITIES: DiagnosticPath =
+        DiagnosticPath::const_new("utility_ai/scored_entities");
+}
+
+impl Plugin for UtilityAiDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::SCORING_TIME).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(Self::SCORED_ENTITIES))
+            .init_resource::<FrameScoring>()
+            .add_systems(Last, record_frame_scoring);
+    }
+}
+
+/// The runs of the entity flows in the current frame, recorded as diagnostics
+/// once the frame ends.
+#[derive(Resource, Default)]
+struct FrameScoring {
+    /// The number of runs.
+    runs: usize,
+    /// The total time the runs took.
+    elapsed: Duration,
+    /// The total number of entities whose flows were run.
+    scored: usize,
+}
+
+/// Adds a run of the entity flows to the frame totals, if the diagnostics are
+/// registered.
+pub(crate) fn record_scoring(world: &mut World, elapsed: Duration, scored: usize) {
+    let Some(mut frame) = world.get_resource_mut::<FrameScoring>() else {
+        return;
+    };
+    frame.runs += 1;
+    frame.elapsed += elapsed;
+    frame.scored += scored;
+}
+
+/// Records the totals of the frame's runs of the entity flows, and resets them.
+fn record_frame_scoring(mut frame: ResMut<FrameScoring>, mut diagnostics: Diagnostics) {
+    if frame.runs == 0 {
+        return;
+    }
+    let FrameScoring {
+        elapsed, scored, ..
+    } = core::mem::take(&mut *frame);
+    diagnostics.add_measurement(&UtilityAiDiagnosticsPlugin::SCORING_TIME, || {
+        elapsed.as_secs_f64() * 1000.0
+    });
+    diagnostics.add_measurement(&UtilityAiDiagnosticsPlugin::SCORED_ENTITIES, || {
+        scored as f64
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_diagnostic::DiagnosticsStore;
+    use bevy_ecs::{query::With, schedule::IntoSystemConfigs, world::World};
+
+    use crate::{
+        component::{run_all_entity_flows, run_entity_flows_filtered, EntityFlow},
+        diagnostic::UtilityAiDiagnosticsPlugin,
+        evaluator::{target, IntoEvaluator},
+        flow::WorldFlowExt,
+        plugin::UtilityAiPlugin,
+        tests::{Health, HealthScore, TestFlow},
+    };
+
+    fn spawn_npcs(world: &mut World) {
+        world.add_nodes(TestFlow, target::<Health>().label(HealthScore));
+        world.flow_scope(TestFlow, |_, flow| {
+            flow.skip_unchanged(true);
+        });
+        for health in [25, 50, 75] {
+            world.spawn((EntityFlow::new(TestFlow), Health(health)));
+        }
+    }
+
+    #[test]
+    fn measures_scoring() {
+        let mut app = App::new();
+        app.add_plugins((UtilityAiPlugin::default(), UtilityAiDiagnosticsPlugin));
+        spawn_npcs(app.world_mut());
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        for path in [
+            UtilityAiDiagnosticsPlugin::SCORING_TIME,
+            UtilityAiDiagnosticsPlugin::SCORED_ENTITIES,
+        ] {
+            assert!(store.get(&path).unwrap().measurement().is_none());
+        }
+
+        app.update();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let time = store
+            .get(&UtilityAiDiagnosticsPlugin::SCORING_TIME)
+            .unwrap()
+            .value()
+            .unwrap();
+        assert!(time >= 0.0);
+        let scored = store
+            .get(&UtilityAiDiagnosticsPlugin::SCORED_ENTITIES)
+            .unwrap();
+        assert_eq!(scored.value(), Some(3.0));
+
+        // Nothing changed, so every entity is skipped.
+        app.update();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let scored = store
+            .get(&UtilityAiDiagnosticsPlugin::SCORED_ENTITIES)
+            .unwrap();
+        assert_eq!(scored.value(), Some(0.0));
+        assert_eq!(scored.history_len(), 2);
+    }
+
+    #[test]
+    fn sums_runs_per_frame() {
+        let mut app = App::new();
+        app.add_plugins((
+            UtilityAiPlugin::default().selection(false),
+            UtilityAiDiagnosticsPlugin,
+        ))
+        .add_systems(
+            Update,
+            run_entity_flows_filtered::<With<Health>>.after(run_all_entity_flows),
+        );
+        spawn_npcs(app.world_mut());
+        app.world_mut().flow_scope(TestFlow, |_, flow| {
+            flow.skip_unchanged(false);
+        });
+
+        app.update();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let scored = store
+            .get(&UtilityAiDiagnosticsPlugin::SCORED_ENTITIES)
+            .unwrap();
+        assert_eq!(scored.value(), Some(6.0));
+        assert_eq!(scored.history_len(), 1);
+    }
+}
@@ -1,29 +1,49 @@
 //! Provides the [`Evaluator`] trait for evaluating target [`Entity`]s in a
 //! [`World`].
 
-use alloc::{borrow::Cow, boxed::Box, format};
+use alloc::{borrow::Cow, boxed::Box, format, vec::Vec};
 
-use bevy_ecs::{entity::Entity, system::SystemInput, world::World};
+use bevy_ecs::{component::Tick, entity::Entity, system::SystemInput, world::World};
 use bevy_math::Curve;
 
 use crate::{
-    flow::FlowNodeConfig,
-    label::ScoreLabel,
+    flow::{FlowAccess, FlowNodeConfig, LabeledScores},
+    label::{InternedFlowLabel, InternedScoreLabel, ScoreLabel},
     mapper::{IntoMapper, Mapper, Mapping, MappingCtx},
     score::Score,
 };
 
 mod constant;
+mod global_count;
+mod has_component;
 mod parent;
 mod resource;
+mod score_ref;
+mod sibling;
+#[cfg(feature = "bevy_time")]
+mod since;
+#[cfg(feature = "bevy_transform")]
+mod spatial;
+mod subflow;
 mod system;
 mod target;
+mod target_enum;
 
 pub use constant::*;
+pub use global_count::*;
+pub use has_component::*;
 pub use parent::*;
 pub use resource::*;
+pub use score_ref::*;
+pub use sibling::*;
+#[cfg(feature = "bevy_time")]
+pub use since::*;
+#[cfg(feature = "bevy_transform")]
+pub use spatial::*;
+pub use subflow::*;
 pub use system::*;
 pub use target::*;
+pub use target_enum::*;
 
 /// Trait for types that view the target [`Entity`] in a [`World`] and return a
 /// [`Score`].
@@ -36,8 +56,57 @@ pub trait Evaluator: Send + Sync + 'static {
         let _ = world;
     }
 
+    /// Checks the change ticks stored by the evaluator, such as the last run
+    /// tick of a system, and clamps them to prevent change detection false
+    /// positives when the world's change tick overflows.
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        let _ = change_tick;
+    }
+
     /// Evaluates the evaluator with the given context.
     fn evaluate(&mut self, ctx: EvaluationCtx) -> Score;
+
+    /// Evaluates the evaluator with the given context, returning [`None`] if
+    /// the score is absent (not applicable to the target) rather than a
+    /// genuine [`Score::MIN`]. [`Flow`]s skip absent scores, so that they are
+    /// not aggregated as zeros and their labels are left out of the results.
+    ///
+    /// Defaults to always returning the result of [`Evaluator::evaluate`].
+    ///
+    /// [`Flow`]: crate::flow::Flow
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        Some(self.evaluate(ctx))
+    }
+
+    /// Returns the parts of the world read by the evaluator. Called after the
+    /// evaluator has been initialized.
+    ///
+    /// Defaults to [`FlowAccess::all`].
+    fn access(&self) -> FlowAccess {
+        FlowAccess::all()
+    }
+
+    /// Returns the [`ScoreLabel`]s of the nodes whose scores the evaluator
+    /// reads from [`EvaluationCtx::scores`]. When the evaluator is added to a
+    /// [`Flow`], it is ordered to run after those nodes.
+    ///
+    /// Defaults to no labels.
+    ///
+    /// [`Flow`]: crate::flow::Flow
+    fn score_refs(&self) -> Vec<InternedScoreLabel> {
+        Vec::new()
+    }
+
+    /// Returns the labels of the [`Flow`]s the evaluator runs, such as the
+    /// flow embedded by [`subflow`]. Used to detect flows that embed
+    /// themselves, directly or through other flows.
+    ///
+    /// Defaults to no labels.
+    ///
+    /// [`Flow`]: crate::flow::Flow
+    fn subflows(&self) -> Vec<InternedFlowLabel> {
+        Vec::new()
+    }
 }
 
 /// Verifies that [`Evaluator`] is dyn-compatible.
@@ -72,9 +141,15 @@ pub trait IntoEvaluator<Marker>: Sized {
             }
 
             fn initialize(&mut self, world: &mut World) {
+                self.mapper.initialize(world);
                 self.evaluator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.mapper.check_change_tick(change_tick);
+                self.evaluator.check_change_tick(change_tick);
+            }
+
             fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
                 let world = ctx.world;
                 let target = ctx.evaluation.target;
@@ -85,6 +160,25 @@ pub trait IntoEvaluator<Marker>: Sized {
                     mapping: Mapping { target, value },
                 })
             }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                let world = ctx.world;
+                let target = ctx.evaluation.target;
+
+                let value = self.evaluator.try_evaluate(ctx)?;
+                Some(self.mapper.map(MappingCtx {
+                    world,
+                    mapping: Mapping { target, value },
+                }))
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
         }
 
         MapEvaluator {
@@ -93,6 +187,31 @@ pub trait IntoEvaluator<Marker>: Sized {
         }
     }
 
+    /// Maps the output score of this evaluator with the given function,
+    /// which is passed the world, the target entity and the score. This is a
+    /// shorthand for [`IntoEvaluator::map`] with a [`Mapper`] that reads the world.
+    fn map_world(
+        self,
+        f: impl Fn(&World, Entity, Score) -> Score + Send + Sync + 'static,
+    ) -> impl Evaluator {
+        struct WorldMapper<F>(F);
+
+        impl<F> Mapper<Score> for WorldMapper<F>
+        where
+            F: Fn(&World, Entity, Score) -> Score + Send + Sync + 'static,
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Borrowed("map_world")
+            }
+
+            fn map(&mut self, ctx: MappingCtx<Score>) -> Score {
+                (self.0)(ctx.world, ctx.mapping.target, ctx.mapping.value)
+            }
+        }
+
+        self.map(WorldMapper(f))
+    }
+
     /// Inverts the output score of this evaluator.
     fn invert(self) -> impl Evaluator {
         struct InvertEvaluator<E> {
@@ -111,10 +230,31 @@ pub trait IntoEvaluator<Marker>: Sized {
                 self.evaluator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
             fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
                 let score = self.evaluator.evaluate(ctx);
                 Score::new(1. - score.get())
             }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                let score = self.evaluator.try_evaluate(ctx)?;
+                Some(Score::new(1. - score.get()))
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
         }
 
         InvertEvaluator {
@@ -138,9 +278,29 @@ pub trait IntoEvaluator<Marker>: Sized {
                 self.evaluator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
             fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
                 self.evaluator.evaluate(ctx) * self.weight
             }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                Some(self.evaluator.try_evaluate(ctx)? * self.weight)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
         }
 
         WeightEvaluator {
@@ -171,10 +331,31 @@ pub trait IntoEvaluator<Marker>: Sized {
                 self.evaluator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
             fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
                 let score = self.evaluator.evaluate(ctx);
                 self.curve.sample(score.get()).unwrap_or(Score::MIN)
             }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                let score = self.evaluator.try_evaluate(ctx)?;
+                Some(self.curve.sample(score.get()).unwrap_or(Score::MIN))
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
         }
 
         CurveEvaluator {
@@ -183,6 +364,63 @@ pub trait IntoEvaluator<Marker>: Sized {
         }
     }
 
+    /// Applies the given [`Curve`] to this evaluator's output score. Unlike
+    /// [`IntoEvaluator::curve`], output scores outside of the curve's domain
+    /// are clamped into it, so they map to the nearest valid output.
+    fn curve_clamped(self, curve: impl Curve<Score> + Send + Sync + 'static) -> impl Evaluator {
+        struct CurveClampedEvaluator<C: Curve<Score> + Send + Sync + 'static, E: Evaluator> {
+            curve: C,
+            evaluator: E,
+        }
+
+        impl<C: Curve<Score> + Send + Sync + 'static, E: Evaluator> Evaluator
+            for CurveClampedEvaluator<C, E>
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.curve_clamped({})",
+                    self.evaluator.name(),
+                    core::any::type_name::<C>(),
+                ))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                let score = self.evaluator.evaluate(ctx);
+                self.curve.sample_clamped(score.get())
+            }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                let score = self.evaluator.try_evaluate(ctx)?;
+                Some(self.curve.sample_clamped(score.get()))
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+        }
+
+        CurveClampedEvaluator {
+            curve,
+            evaluator: self.into_evaluator(),
+        }
+    }
+
     /// Applies the given threshold to this evaluator's output score. If the
     /// output score is less than the threshold, the evaluator returns
     /// [`Score::MIN`].
@@ -205,6 +443,10 @@ pub trait IntoEvaluator<Marker>: Sized {
                 self.evaluator.initialize(world);
             }
 
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
             fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
                 let score = self.evaluator.evaluate(ctx);
                 if score < self.threshold {
@@ -213,6 +455,27 @@ pub trait IntoEvaluator<Marker>: Sized {
                     score
                 }
             }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                let score = self.evaluator.try_evaluate(ctx)?;
+                if score < self.threshold {
+                    Some(Score::MIN)
+                } else {
+                    Some(score)
+                }
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
         }
 
         OutputThresholdEvaluator {
@@ -221,138 +484,1066 @@ pub trait IntoEvaluator<Marker>: Sized {
         }
     }
 
-    /// Labels this evaluator with the given [`ScoreLabel`].
-    fn label(self, label: impl ScoreLabel) -> FlowNodeConfig {
-        FlowNodeConfig::evaluator(self).label(label)
-    }
-}
-
-/// All [`Evaluator`]s can be converted into themselves.
-impl<E: Evaluator> IntoEvaluator<()> for E {
-    type Evaluator = E;
+    /// Clamps this evaluator's output score to be at least the given minimum.
+    /// Unlike [`IntoEvaluator::threshold`], scores below the minimum are
+    /// raised to it rather than set to [`Score::MIN`].
+    fn clamp_min(self, min: impl Into<Score>) -> impl Evaluator {
+        struct ClampMinEvaluator<E: Evaluator> {
+            min: Score,
+            evaluator: E,
+        }
 
-    fn into_evaluator(self) -> Self::Evaluator {
-        self
-    }
-}
+        impl<E: Evaluator> Evaluator for ClampMinEvaluator<E> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!("{}.clamp_min({})", self.evaluator.name(), self.min))
+            }
 
-/// [`World`] and [`Evaluation`] pair passed to [`Evaluator`]s.
-#[derive(Clone, Debug)]
-pub struct EvaluationCtx<'w> {
-    /// The world in which the [`Evaluator`] is being evaluated.
-    pub world: &'w World,
-    /// The evaluation being performed.
-    pub evaluation: Evaluation,
-}
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
 
-/// [`SystemInput`] type for [`Evaluator`] systems.
-#[derive(Clone, PartialEq, Debug)]
-pub struct Evaluation {
-    /// The entity that is being scored.
-    pub target: Entity,
-}
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
 
-impl SystemInput for Evaluation {
-    type Param<'i> = Evaluation;
-    type Inner<'i> = Evaluation;
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                self.evaluator.evaluate(ctx).max(self.min)
+            }
 
-    fn wrap(this: Self::Inner<'_>) -> Self::Param<'_> {
-        this
-    }
-}
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                Some(self.evaluator.try_evaluate(ctx)?.max(self.min))
+            }
 
-#[cfg(test)]
-mod tests {
-    use bevy_ecs::{component::Component, entity::Entity, system::Resource, world::World};
-    use bevy_hierarchy::BuildChildren;
-    use bevy_math::curve::FunctionCurve;
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
 
-    use crate::{
-        aggregator::{sum, IntoAggregator},
-        evaluator::{
-            constant, parent, resource, target, Evaluation, EvaluationCtx, Evaluator, IntoEvaluator,
-        },
-        score::{Score, Scoreable},
-    };
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
 
-    #[derive(Resource)]
-    struct TestResource(i32);
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+        }
 
-    impl Scoreable for TestResource {
-        fn score(&self) -> Score {
-            Score::new(self.0 as f32 / 100.)
+        ClampMinEvaluator {
+            min: min.into(),
+            evaluator: self.into_evaluator(),
         }
     }
 
-    #[derive(Component)]
-    struct TestComponent(i32);
-
-    impl Scoreable for TestComponent {
-        fn score(&self) -> Score {
-            Score::new(self.0 as f32 / 100.)
+    /// Clamps this evaluator's output score to be at most the given maximum.
+    fn clamp_max(self, max: impl Into<Score>) -> impl Evaluator {
+        struct ClampMaxEvaluator<E: Evaluator> {
+            max: Score,
+            evaluator: E,
         }
-    }
 
-    #[test]
-    fn children_evaluator() {
-        let mut world = World::new();
+        impl<E: Evaluator> Evaluator for ClampMaxEvaluator<E> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!("{}.clamp_max({})", self.evaluator.name(), self.max))
+            }
 
-        let e1 = world.spawn(TestComponent(20)).id();
-        let e2 = world.spawn(TestComponent(20)).id();
-        let e3 = world.spawn(TestComponent(20)).id();
-        let pent = world.spawn_empty().add_children(&[e1, e2, e3]).id();
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
 
-        let mut evaluator = sum().score_children::<TestComponent>();
-        evaluator.initialize(&mut world);
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
 
-        let output = evaluator.evaluate(EvaluationCtx {
-            world: &world,
-            evaluation: Evaluation { target: pent },
-        });
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                self.evaluator.evaluate(ctx).min(self.max)
+            }
 
-        assert_eq!(output, Score::new(0.6));
-    }
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                Some(self.evaluator.try_evaluate(ctx)?.min(self.max))
+            }
 
-    #[test]
-    fn constant_evaluator() {
-        let mut world = World::new();
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
 
-        let mut evaluator = constant(0.8);
-        evaluator.initialize(&mut world);
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
 
-        let output = evaluator.evaluate(EvaluationCtx {
-            world: &world,
-            evaluation: Evaluation {
-                target: Entity::PLACEHOLDER,
-            },
-        });
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+        }
 
-        assert_eq!(output, Score::new(0.8));
+        ClampMaxEvaluator {
+            max: max.into(),
+            evaluator: self.into_evaluator(),
+        }
     }
 
-    #[test]
-    fn curve_evaluator() {
-        let mut world = World::new();
+    /// Rounds this evaluator's output score to the nearest of `levels` evenly
+    /// spaced values in `[0, 1]`. For example, 3 levels yields scores of 0.0,
+    /// 0.5 or 1.0.
+    ///
+    /// # Panics
+    ///
+    /// If `levels` is less than 2.
+    fn quantize(self, levels: usize) -> impl Evaluator {
+        assert!(
+            levels >= 2,
+            "cannot quantize scores into fewer than 2 levels"
+        );
+
+        struct QuantizeEvaluator<E: Evaluator> {
+            levels: usize,
+            evaluator: E,
+        }
 
-        let mut evaluator =
-            constant(0.5).curve(FunctionCurve::new(Score::INTERVAL, |x| Score::new(x * x)));
-        evaluator.initialize(&mut world);
+        impl<E: Evaluator> Evaluator for QuantizeEvaluator<E> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.quantize({})",
+                    self.evaluator.name(),
+                    self.levels
+                ))
+            }
 
-        let output = evaluator.evaluate(EvaluationCtx {
-            world: &world,
-            evaluation: Evaluation {
-                target: Entity::PLACEHOLDER,
-            },
-        });
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
 
-        assert_eq!(output, Score::new(0.25));
-    }
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
 
-    #[test]
-    fn parent_evaluator() {
-        let mut world = World::new();
-        let ent = world.spawn_empty().id();
-        world.spawn(TestComponent(25)).add_child(ent);
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                self.evaluator.evaluate(ctx).quantize(self.levels)
+            }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                Some(self.evaluator.try_evaluate(ctx)?.quantize(self.levels))
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+        }
+
+        QuantizeEvaluator {
+            levels,
+            evaluator: self.into_evaluator(),
+        }
+    }
+
+    /// Replaces an absent score of this evaluator (see
+    /// [`Evaluator::try_evaluate`]) with the given score, so that it is always
+    /// aggregated.
+    fn absent_as(self, score: impl Into<Score>) -> impl Evaluator {
+        struct AbsentAsEvaluator<E: Evaluator> {
+            score: Score,
+            evaluator: E,
+        }
+
+        impl<E: Evaluator> Evaluator for AbsentAsEvaluator<E> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.absent_as({})",
+                    self.evaluator.name(),
+                    self.score
+                ))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                self.evaluator.try_evaluate(ctx).unwrap_or(self.score)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+        }
+
+        AbsentAsEvaluator {
+            score: score.into(),
+            evaluator: self.into_evaluator(),
+        }
+    }
+
+    /// Treats an output score of [`Score::MIN`] from this evaluator as absent
+    /// (see [`Evaluator::try_evaluate`]). Useful for adapting evaluators that
+    /// return [`Score::MIN`] when they are not applicable.
+    fn min_as_absent(self) -> impl Evaluator {
+        struct MinAsAbsentEvaluator<E: Evaluator> {
+            evaluator: E,
+        }
+
+        impl<E: Evaluator> Evaluator for MinAsAbsentEvaluator<E> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!("{}.min_as_absent()", self.evaluator.name()))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                self.evaluator.evaluate(ctx)
+            }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                self.evaluator
+                    .try_evaluate(ctx)
+                    .filter(|&score| score > Score::MIN)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+        }
+
+        MinAsAbsentEvaluator {
+            evaluator: self.into_evaluator(),
+        }
+    }
+
+    /// Falls back to the given evaluator when this evaluator returns
+    /// [`Score::MIN`] or its score is absent (see [`Evaluator::try_evaluate`]).
+    /// The fallback evaluator is only evaluated when needed.
+    fn fallback<M>(self, other: impl IntoEvaluator<M>) -> impl Evaluator {
+        struct FallbackEvaluator<E: Evaluator, F: Evaluator> {
+            evaluator: E,
+            fallback: F,
+        }
+
+        impl<E: Evaluator, F: Evaluator> Evaluator for FallbackEvaluator<E, F> {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.fallback({})",
+                    self.evaluator.name(),
+                    self.fallback.name()
+                ))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+                self.fallback.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+                self.fallback.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                let score = self.evaluator.evaluate(ctx.clone());
+                if score > Score::MIN {
+                    score
+                } else {
+                    self.fallback.evaluate(ctx)
+                }
+            }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                match self.evaluator.try_evaluate(ctx.clone()) {
+                    Some(score) if score > Score::MIN => Some(score),
+                    _ => self.fallback.try_evaluate(ctx),
+                }
+            }
+
+            fn access(&self) -> FlowAccess {
+                let mut access = self.evaluator.access();
+                access.extend(&self.fallback.access());
+                access
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                let mut score_refs = self.evaluator.score_refs();
+                score_refs.extend(self.fallback.score_refs());
+                score_refs
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                let mut subflows = self.evaluator.subflows();
+                subflows.extend(self.fallback.subflows());
+                subflows
+            }
+        }
+
+        FallbackEvaluator {
+            evaluator: self.into_evaluator(),
+            fallback: other.into_evaluator(),
+        }
+    }
+
+    /// Evaluates this evaluator against the entity returned by `f` for the
+    /// target entity instead, such as an entity referenced by one of the
+    /// target's components. If `f` returns `None`, the evaluator returns
+    /// [`Score::MIN`], and the score is absent from [`Evaluator::try_evaluate`].
+    fn retarget(
+        self,
+        f: impl Fn(&World, Entity) -> Option<Entity> + Send + Sync + 'static,
+    ) -> impl Evaluator {
+        struct RetargetEvaluator<E: Evaluator, F> {
+            evaluator: E,
+            f: F,
+        }
+
+        impl<E, F> Evaluator for RetargetEvaluator<E, F>
+        where
+            E: Evaluator,
+            F: Fn(&World, Entity) -> Option<Entity> + Send + Sync + 'static,
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!("{}.retarget()", self.evaluator.name()))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                self.try_evaluate(ctx).unwrap_or(Score::MIN)
+            }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                let target = (self.f)(ctx.world, ctx.evaluation.target)?;
+                // The scores of the flow run are those of the original target,
+                // so they are not passed on.
+                self.evaluator
+                    .try_evaluate(EvaluationCtx::new(ctx.world, target))
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+
+            // The access of `f` is unknown, so the default access is kept.
+        }
+
+        RetargetEvaluator {
+            evaluator: self.into_evaluator(),
+            f,
+        }
+    }
+
+    /// Gates this evaluator behind the given predicate over the world and the
+    /// target entity. If the predicate holds, the evaluator returns the score
+    /// of this evaluator, otherwise it returns [`Score::MIN`] without
+    /// evaluating it. Unlike [`IntoEvaluator::threshold`], the condition can
+    /// depend on any world state rather than on the score.
+    fn gate(self, pred: impl Fn(&World, Entity) -> bool + Send + Sync + 'static) -> impl Evaluator {
+        struct GateEvaluator<E: Evaluator, P> {
+            evaluator: E,
+            pred: P,
+        }
+
+        impl<E, P> Evaluator for GateEvaluator<E, P>
+        where
+            E: Evaluator,
+            P: Fn(&World, Entity) -> bool + Send + Sync + 'static,
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!("{}.gate()", self.evaluator.name()))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                if (self.pred)(ctx.world, ctx.evaluation.target) {
+                    self.evaluator.evaluate(ctx)
+                } else {
+                    Score::MIN
+                }
+            }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                if (self.pred)(ctx.world, ctx.evaluation.target) {
+                    self.evaluator.try_evaluate(ctx)
+                } else {
+                    Some(Score::MIN)
+                }
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+
+            // The access of `pred` is unknown, so the default access is kept.
+        }
+
+        GateEvaluator {
+            evaluator: self.into_evaluator(),
+            pred,
+        }
+    }
+
+    /// Overrides the name of this evaluator, as returned by
+    /// [`Evaluator::name`], with the given name. Useful for giving nodes a
+    /// stable name in logs and traces.
+    fn named(self, name: impl Into<Cow<'static, str>>) -> impl Evaluator {
+        struct NamedEvaluator<E: Evaluator> {
+            name: Cow<'static, str>,
+            evaluator: E,
+        }
+
+        impl<E: Evaluator> Evaluator for NamedEvaluator<E> {
+            fn name(&self) -> Cow<'static, str> {
+                self.name.clone()
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.evaluator.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.evaluator.check_change_tick(change_tick);
+            }
+
+            fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+                self.evaluator.evaluate(ctx)
+            }
+
+            fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+                self.evaluator.try_evaluate(ctx)
+            }
+
+            fn access(&self) -> FlowAccess {
+                self.evaluator.access()
+            }
+
+            fn score_refs(&self) -> Vec<InternedScoreLabel> {
+                self.evaluator.score_refs()
+            }
+
+            fn subflows(&self) -> Vec<InternedFlowLabel> {
+                self.evaluator.subflows()
+            }
+        }
+
+        NamedEvaluator {
+            name: name.into(),
+            evaluator: self.into_evaluator(),
+        }
+    }
+
+    /// Labels this evaluator with the given [`ScoreLabel`].
+    fn label(self, label: impl ScoreLabel) -> FlowNodeConfig {
+        FlowNodeConfig::evaluator(self).label(label)
+    }
+}
+
+/// All [`Evaluator`]s can be converted into themselves.
+impl<E: Evaluator> IntoEvaluator<()> for E {
+    type Evaluator = E;
+
+    fn into_evaluator(self) -> Self::Evaluator {
+        self
+    }
+}
+
+/// [`World`] and [`Evaluation`] pair passed to [`Evaluator`]s.
+#[derive(Clone, Debug)]
+pub struct EvaluationCtx<'w> {
+    /// The world in which the [`Evaluator`] is being evaluated.
+    pub world: &'w World,
+    /// The evaluation being performed.
+    pub evaluation: Evaluation,
+    /// The scores of the labeled nodes that have already run in the current
+    /// run of the [`Flow`], as read by [`score_ref`].
+    ///
+    /// [`Flow`]: crate::flow::Flow
+    pub scores: LabeledScores<'w>,
+}
+
+impl<'w> EvaluationCtx<'w> {
+    /// Creates a context for evaluating the given target entity outside of a
+    /// [`Flow`] run, with no labeled scores.
+    ///
+    /// [`Flow`]: crate::flow::Flow
+    pub fn new(world: &'w World, target: Entity) -> Self {
+        Self {
+            world,
+            evaluation: Evaluation { target },
+            scores: LabeledScores::default(),
+        }
+    }
+}
+
+/// [`SystemInput`] type for [`Evaluator`] systems.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Evaluation {
+    /// The entity that is being scored.
+    pub target: Entity,
+}
+
+impl SystemInput for Evaluation {
+    type Param<'i> = Evaluation;
+    type Inner<'i> = Evaluation;
+
+    fn wrap(this: Self::Inner<'_>) -> Self::Param<'_> {
+        this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{
+        change_detection::DetectChangesMut, component::Component, entity::Entity, query::With,
+        system::Resource, world::World,
+    };
+    use bevy_hierarchy::BuildChildren;
+    use bevy_math::curve::{FunctionCurve, Interval};
+
+    use crate::{
+        aggregator::{sum, IntoAggregator},
+        evaluator::{
+            constant, from_resource, global_count, has_component, memoized_resource,
+            missing_component, parent, resource, resource_or, resource_present, sibling_aggregate,
+            target, target_enum, target_inverse, target_inverse_or, target_ranged, target_with,
+            variant_scores, Evaluation, EvaluationCtx, Evaluator, IntoEvaluator,
+        },
+        flow::{FlowAccess, LabeledScores},
+        mapper::Mapping,
+        score::{InvertScore, Score, Scoreable, ScoreableWith},
+        tests::Health,
+    };
+
+    #[derive(Resource)]
+    struct TestResource(i32);
+
+    impl Scoreable for TestResource {
+        fn score(&self) -> Score {
+            Score::new(self.0 as f32 / 100.)
+        }
+    }
+
+    #[derive(Component)]
+    struct TestComponent(i32);
+
+    impl Scoreable for TestComponent {
+        fn score(&self) -> Score {
+            Score::new(self.0 as f32 / 100.)
+        }
+    }
+
+    #[test]
+    fn absent_evaluators() {
+        let mut world = World::new();
+        let ent = world.spawn_empty().id();
+
+        let mut evaluate = |evaluator: &mut dyn Evaluator| {
+            evaluator.initialize(&mut world);
+            evaluator.try_evaluate(EvaluationCtx::new(&world, ent))
+        };
+
+        assert_eq!(evaluate(&mut target::<TestComponent>()), None);
+        assert_eq!(evaluate(&mut parent::<TestComponent>()), None);
+        assert_eq!(evaluate(&mut resource::<TestResource>()), None);
+        assert_eq!(evaluate(&mut target::<TestComponent>().weight(0.5)), None);
+        assert_eq!(
+            evaluate(&mut target::<TestComponent>().absent_as(0.25)),
+            Some(Score::new(0.25))
+        );
+        assert_eq!(evaluate(&mut constant(0.).min_as_absent()), None);
+        assert_eq!(
+            evaluate(&mut constant(0.5).min_as_absent()),
+            Some(Score::new(0.5))
+        );
+    }
+
+    #[test]
+    fn evaluator_access() {
+        let mut world = World::new();
+
+        let mut evaluator = target::<Health>();
+        assert!(evaluator.access().reads_all());
+        evaluator.initialize(&mut world);
+        let health = world.component_id::<Health>().unwrap();
+        let access = evaluator.access();
+        assert!(!access.reads_all());
+        assert!(access.has_component_read(health));
+        assert_eq!(access.component_reads().count(), 1);
+
+        // Wrappers report the access of the evaluator they wrap.
+        let mut evaluator = target::<Health>().weight(0.5);
+        evaluator.initialize(&mut world);
+        assert_eq!(evaluator.access(), access);
+
+        let mut evaluator = parent::<TestComponent>();
+        evaluator.initialize(&mut world);
+        let component = world.component_id::<TestComponent>().unwrap();
+        assert!(evaluator.access().has_related_component_read(component));
+        assert!(!evaluator.access().has_component_read(component));
+
+        let mut evaluator = resource::<TestResource>();
+        evaluator.initialize(&mut world);
+        let resource = world.resource_id::<TestResource>().unwrap();
+        assert!(evaluator.access().has_resource_read(resource));
+
+        assert_eq!(constant(0.5).access(), FlowAccess::none());
+    }
+
+    #[test]
+    fn children_evaluator() {
+        let mut world = World::new();
+
+        let e1 = world.spawn(TestComponent(20)).id();
+        let e2 = world.spawn(TestComponent(20)).id();
+        let e3 = world.spawn(TestComponent(20)).id();
+        let pent = world.spawn_empty().add_children(&[e1, e2, e3]).id();
+
+        let mut evaluator = sum().score_children::<TestComponent>();
+        evaluator.initialize(&mut world);
+
+        let output = evaluator.evaluate(EvaluationCtx {
+            world: &world,
+            evaluation: Evaluation { target: pent },
+            scores: LabeledScores::default(),
+        });
+
+        assert_eq!(output, Score::new(0.6));
+    }
+
+    #[test]
+    fn children_evaluator_when_empty() {
+        let mut world = World::new();
+        let child = world.spawn_empty().id();
+        let pent = world.spawn_empty().add_children(&[child]).id();
+
+        let mut evaluator = sum().when_empty(0.5).score_children::<TestComponent>();
+        evaluator.initialize(&mut world);
+
+        let output = evaluator.evaluate(EvaluationCtx::new(&world, pent));
+        assert_eq!(output, Score::new(0.5));
+    }
+
+    #[test]
+    fn clamp_evaluator() {
+        let mut world = World::new();
+
+        let evaluate = |evaluator: &mut dyn Evaluator, world: &mut World| {
+            evaluator.initialize(world);
+            evaluator.evaluate(EvaluationCtx::new(world, Entity::PLACEHOLDER))
+        };
+
+        for (score, clamped) in [(0.2, 0.4), (0.4, 0.4), (0.6, 0.6)] {
+            let mut evaluator = constant(score).clamp_min(0.4);
+            assert_eq!(evaluate(&mut evaluator, &mut world), Score::new(clamped));
+        }
+        for (score, clamped) in [(0.2, 0.2), (0.4, 0.4), (0.6, 0.4)] {
+            let mut evaluator = constant(score).clamp_max(0.4);
+            assert_eq!(evaluate(&mut evaluator, &mut world), Score::new(clamped));
+        }
+    }
+
+    #[test]
+    fn constant_evaluator() {
+        let mut world = World::new();
+
+        let mut evaluator = constant(0.8);
+        evaluator.initialize(&mut world);
+
+        let output = evaluator.evaluate(EvaluationCtx {
+            world: &world,
+            evaluation: Evaluation {
+                target: Entity::PLACEHOLDER,
+            },
+            scores: LabeledScores::default(),
+        });
+
+        assert_eq!(output, Score::new(0.8));
+    }
+
+    #[test]
+    fn curve_evaluator() {
+        let mut world = World::new();
+
+        let mut evaluator =
+            constant(0.5).curve(FunctionCurve::new(Score::INTERVAL, |x| Score::new(x * x)));
+        evaluator.initialize(&mut world);
+
+        let output = evaluator.evaluate(EvaluationCtx {
+            world: &world,
+            evaluation: Evaluation {
+                target: Entity::PLACEHOLDER,
+            },
+            scores: LabeledScores::default(),
+        });
+
+        assert_eq!(output, Score::new(0.25));
+    }
+
+    #[test]
+    fn curve_clamped_evaluator() {
+        let mut world = World::new();
+        let curve = || FunctionCurve::new(Interval::new(0.2, 0.8).unwrap(), Score::new);
+
+        let mut clamped = constant(0.9).curve_clamped(curve());
+        clamped.initialize(&mut world);
+        let mut unclamped = constant(0.9).curve(curve());
+        unclamped.initialize(&mut world);
+
+        let ctx = || EvaluationCtx::new(&world, Entity::PLACEHOLDER);
+        assert_eq!(clamped.evaluate(ctx()), Score::new(0.8));
+        assert_eq!(unclamped.evaluate(ctx()), Score::MIN);
+    }
+
+    #[test]
+    fn target_enum_evaluator() {
+        #[derive(Component)]
+        enum Mood {
+            Calm,
+            Anxious,
+            Panicked,
+        }
+
+        let mut world = World::new();
+        let calm = world.spawn(Mood::Calm).id();
+        let anxious = world.spawn(Mood::Anxious).id();
+        let panicked = world.spawn(Mood::Panicked).id();
+        let missing = world.spawn_empty().id();
+
+        let mut matched = target_enum(|mood: &Mood| match mood {
+            Mood::Calm => Score::new(0.1),
+            Mood::Anxious => Score::new(0.5),
+            Mood::Panicked => Score::new(0.9),
+        });
+        let mut tabled = target_enum(variant_scores(&[
+            (|mood| matches!(mood, Mood::Calm), Score::new(0.1)),
+            (|mood| matches!(mood, Mood::Anxious), Score::new(0.5)),
+            (|mood| matches!(mood, Mood::Panicked), Score::new(0.9)),
+        ]));
+        matched.initialize(&mut world);
+        tabled.initialize(&mut world);
+        assert!(matched.name().starts_with("target_enum("));
+
+        for evaluator in [&mut matched as &mut dyn Evaluator, &mut tabled] {
+            let mut evaluate = |target| evaluator.evaluate(EvaluationCtx::new(&world, target));
+
+            assert_eq!(evaluate(calm), Score::new(0.1));
+            assert_eq!(evaluate(anxious), Score::new(0.5));
+            assert_eq!(evaluate(panicked), Score::new(0.9));
+            assert_eq!(evaluate(missing), Score::MIN);
+        }
+
+        let partial = variant_scores(&[(|mood| matches!(mood, Mood::Calm), Score::MAX)]);
+        assert_eq!(partial(&Mood::Calm), Score::MAX);
+        assert_eq!(partial(&Mood::Panicked), Score::MIN);
+    }
+
+    #[test]
+    fn fallback_evaluator() {
+        let mut world = World::new();
+        let with = world.spawn(TestComponent(25)).id();
+        let without = world.spawn_empty().id();
+
+        let mut evaluator = target::<TestComponent>().fallback(constant(0.5));
+        evaluator.initialize(&mut world);
+        let mut evaluate = |target| {
+            let ctx = EvaluationCtx::new(&world, target);
+            (evaluator.evaluate(ctx.clone()), evaluator.try_evaluate(ctx))
+        };
+
+        assert_eq!(evaluate(with), (Score::new(0.25), Some(Score::new(0.25))));
+        assert_eq!(evaluate(without), (Score::new(0.5), Some(Score::new(0.5))));
+
+        // A zero score also falls back, and absent fallbacks stay absent.
+        let mut evaluator = constant(0.).fallback(target::<TestComponent>());
+        evaluator.initialize(&mut world);
+        let ctx = EvaluationCtx::new(&world, without);
+        assert_eq!(evaluator.evaluate(ctx.clone()), Score::MIN);
+        assert_eq!(evaluator.try_evaluate(ctx), None);
+    }
+
+    #[test]
+    fn gate_evaluator() {
+        let mut world = World::new();
+        world.insert_resource(TestResource(0));
+        let npc = world.spawn(TestComponent(75)).id();
+
+        let mut evaluator =
+            target::<TestComponent>().gate(|world, _| world.resource::<TestResource>().0 == 0);
+        evaluator.initialize(&mut world);
+        assert_eq!(evaluator.access(), FlowAccess::all());
+        let mut evaluate = |world: &World| {
+            let ctx = EvaluationCtx::new(world, npc);
+            (evaluator.evaluate(ctx.clone()), evaluator.try_evaluate(ctx))
+        };
+
+        assert_eq!(evaluate(&world), (Score::new(0.75), Some(Score::new(0.75))));
+        world.resource_mut::<TestResource>().0 = 1;
+        assert_eq!(evaluate(&world), (Score::MIN, Some(Score::MIN)));
+        world.resource_mut::<TestResource>().0 = 0;
+        assert_eq!(evaluate(&world), (Score::new(0.75), Some(Score::new(0.75))));
+    }
+
+    #[test]
+    fn retarget_evaluator() {
+        #[derive(Component)]
+        struct TargetedBy(Entity);
+
+        let mut world = World::new();
+        let hunter = world.spawn(TestComponent(75)).id();
+        let prey = world.spawn((TestComponent(25), TargetedBy(hunter))).id();
+        let alone = world.spawn(TestComponent(25)).id();
+
+        let mut evaluator = target::<TestComponent>()
+            .retarget(|world, target| world.get::<TargetedBy>(target).map(|by| by.0));
+        evaluator.initialize(&mut world);
+        assert_eq!(evaluator.access(), FlowAccess::all());
+        let mut evaluate = |target| {
+            let ctx = EvaluationCtx::new(&world, target);
+            (evaluator.evaluate(ctx.clone()), evaluator.try_evaluate(ctx))
+        };
+
+        assert_eq!(evaluate(prey), (Score::new(0.75), Some(Score::new(0.75))));
+        assert_eq!(evaluate(alone), (Score::MIN, None));
+    }
+
+    #[test]
+    fn target_with_evaluator() {
+        #[derive(Component)]
+        struct Hunger {
+            value: f32,
+        }
+
+        let mut world = World::new();
+        let hungry = world.spawn(Hunger { value: 80. }).id();
+        let other = world.spawn_empty().id();
+
+        let mut evaluator = target_with(|hunger: &Hunger| Score::new(hunger.value / 100.));
+        evaluator.initialize(&mut world);
+        let mut evaluate = |target| {
+            let ctx = EvaluationCtx::new(&world, target);
+            (evaluator.evaluate(ctx.clone()), evaluator.try_evaluate(ctx))
+        };
+
+        assert_eq!(evaluate(hungry), (Score::new(0.8), Some(Score::new(0.8))));
+        assert_eq!(evaluate(other), (Score::MIN, None));
+    }
+
+    #[test]
+    fn from_resource_evaluator() {
+        #[derive(Resource)]
+        struct Difficulty {
+            aggression: f32,
+        }
+
+        let mut world = World::new();
+
+        let mut evaluator =
+            from_resource(|difficulty: &Difficulty| Score::new(difficulty.aggression));
+        evaluator.initialize(&mut world);
+        let mut evaluate =
+            |world: &World| evaluator.try_evaluate(EvaluationCtx::new(world, Entity::PLACEHOLDER));
+
+        assert_eq!(evaluate(&world), None);
+        world.insert_resource(Difficulty { aggression: 0.25 });
+        assert_eq!(evaluate(&world), Some(Score::new(0.25)));
+        world.resource_mut::<Difficulty>().aggression = 0.75;
+        assert_eq!(evaluate(&world), Some(Score::new(0.75)));
+    }
+
+    #[cfg(feature = "bevy_time")]
+    #[test]
+    fn since_evaluator() {
+        use core::time::Duration;
+
+        use bevy::time::Time;
+
+        use crate::evaluator::{since, since_with, Timestamp};
+
+        #[derive(Component)]
+        struct LastAte(Duration);
+
+        impl Timestamp for LastAte {
+            fn timestamp(&self) -> Duration {
+                self.0
+            }
+        }
+
+        let mut world = World::new();
+        let npc = world.spawn(LastAte(Duration::from_secs(5))).id();
+        let other = world.spawn_empty().id();
+
+        let mut decay = since::<LastAte>(Duration::from_secs(10));
+        let mut linear = since_with::<LastAte>(|elapsed| Score::new(elapsed.as_secs_f32() / 40.));
+        decay.initialize(&mut world);
+        linear.initialize(&mut world);
+        let evaluate = |evaluator: &mut dyn Evaluator, world: &World, target| {
+            evaluator.try_evaluate(EvaluationCtx::new(world, target))
+        };
+
+        // Without a time resource the score is absent.
+        assert_eq!(evaluate(&mut decay, &world, npc), None);
+
+        world.insert_resource(Time::<()>::default());
+        assert_eq!(evaluate(&mut decay, &world, npc), Some(Score::MAX));
+        assert_eq!(evaluate(&mut decay, &world, other), None);
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(15));
+        assert_eq!(evaluate(&mut decay, &world, npc), Some(Score::new(0.5)));
+        assert_eq!(evaluate(&mut linear, &world, npc), Some(Score::new(0.25)));
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(10));
+        assert_eq!(evaluate(&mut decay, &world, npc), Some(Score::new(0.25)));
+        assert_eq!(evaluate(&mut linear, &world, npc), Some(Score::new(0.5)));
+    }
+
+    #[test]
+    fn global_count_evaluator() {
+        #[derive(Component)]
+        struct Enemy;
+
+        let mut world = World::new();
+        let npc = world.spawn(TestComponent(10)).id();
+        for _ in 0..3 {
+            world.spawn(Enemy);
+        }
+        world.spawn((Enemy, TestComponent(50)));
+        world.spawn(TestComponent(20));
+
+        let mut enemies = global_count::<&Enemy, ()>(8);
+        let mut armed = global_count::<&Enemy, With<TestComponent>>(8);
+        let mut zero = global_count::<&Enemy, ()>(0);
+        enemies.initialize(&mut world);
+        armed.initialize(&mut world);
+        zero.initialize(&mut world);
+
+        let evaluate = |evaluator: &mut dyn Evaluator, world: &World| {
+            evaluator.evaluate(EvaluationCtx::new(world, npc))
+        };
+
+        assert_eq!(evaluate(&mut enemies, &world), Score::new(0.5));
+        assert_eq!(evaluate(&mut armed, &world), Score::new(0.125));
+        assert_eq!(evaluate(&mut zero, &world), Score::MIN);
+
+        // Entities spawned after initialization are counted, up to the max.
+        for _ in 0..10 {
+            world.spawn(Enemy);
+        }
+        assert_eq!(evaluate(&mut enemies, &world), Score::MAX);
+
+        // Components inserted in a later tick are counted too.
+        assert_eq!(evaluate(&mut armed, &world), Score::new(0.125));
+        world.increment_change_tick();
+        world.entity_mut(npc).insert(Enemy);
+        assert_eq!(evaluate(&mut armed, &world), Score::new(0.25));
+    }
+
+    #[test]
+    fn has_component_evaluator() {
+        #[derive(Component)]
+        struct Tag;
+
+        let mut world = World::new();
+        let tagged = world.spawn(Tag).id();
+        let untagged = world.spawn_empty().id();
+
+        let mut has = has_component::<Tag>();
+        let mut missing = missing_component::<Tag>();
+        has.initialize(&mut world);
+        missing.initialize(&mut world);
+
+        let evaluate = |evaluator: &mut dyn Evaluator, target| {
+            evaluator.evaluate(EvaluationCtx::new(&world, target))
+        };
+
+        assert_eq!(evaluate(&mut has, tagged), Score::MAX);
+        assert_eq!(evaluate(&mut has, untagged), Score::MIN);
+        assert_eq!(evaluate(&mut missing, tagged), Score::MIN);
+        assert_eq!(evaluate(&mut missing, untagged), Score::MAX);
+    }
+
+    #[test]
+    fn map_world_evaluator() {
+        let mut world = World::new();
+        world.insert_resource(TestResource(50));
+        let npc = world.spawn(TestComponent(80)).id();
+
+        let mut evaluator = target::<TestComponent>().map_world(|world, target, score| {
+            assert_eq!(world.get::<TestComponent>(target).unwrap().0, 80);
+            score * world.resource::<TestResource>().score()
+        });
+        evaluator.initialize(&mut world);
+
+        let output = evaluator.evaluate(EvaluationCtx::new(&world, npc));
+
+        assert_eq!(output, Score::new(0.4));
+    }
+
+    #[test]
+    fn named_evaluator() {
+        let mut world = World::new();
+        let ent = world.spawn(TestComponent(25)).id();
+
+        let mut evaluator = target::<TestComponent>()
+            .named("health")
+            .map(|mapping: Mapping<Score>| mapping.value * 2.);
+        evaluator.initialize(&mut world);
+        assert!(evaluator.name().starts_with("health.map("));
+
+        let output = evaluator.evaluate(EvaluationCtx::new(&world, ent));
+        assert_eq!(output, Score::new(0.5));
+    }
+
+    #[test]
+    fn parent_evaluator() {
+        let mut world = World::new();
+        let ent = world.spawn_empty().id();
+        world.spawn(TestComponent(25)).add_child(ent);
 
         let mut evaluator = parent::<TestComponent>();
         evaluator.initialize(&mut world);
@@ -360,11 +1551,24 @@ mod tests {
         let output = evaluator.evaluate(EvaluationCtx {
             world: &world,
             evaluation: Evaluation { target: ent },
+            scores: LabeledScores::default(),
         });
 
         assert_eq!(output, Score::new(0.25));
     }
 
+    #[test]
+    fn quantize_evaluator() {
+        let mut world = World::new();
+
+        for (score, quantized) in [(0.0, 0.0), (0.3, 0.25), (0.6, 0.5), (0.9, 1.0)] {
+            let mut evaluator = constant(score).quantize(5);
+            evaluator.initialize(&mut world);
+            let output = evaluator.evaluate(EvaluationCtx::new(&world, Entity::PLACEHOLDER));
+            assert_eq!(output, Score::new(quantized));
+        }
+    }
+
     #[test]
     fn resource_evaluator() {
         let mut world = World::new();
@@ -378,11 +1582,169 @@ mod tests {
             evaluation: Evaluation {
                 target: Entity::PLACEHOLDER,
             },
+            scores: LabeledScores::default(),
         });
 
         assert_eq!(output, Score::new(0.5));
     }
 
+    #[test]
+    fn memoized_resource_evaluator() {
+        let mut world = World::new();
+
+        let mut evaluator = memoized_resource::<TestResource>();
+        evaluator.initialize(&mut world);
+        let mut evaluate =
+            |world: &World| evaluator.try_evaluate(EvaluationCtx::new(world, Entity::PLACEHOLDER));
+
+        assert_eq!(evaluate(&world), None);
+        world.insert_resource(TestResource(50));
+        assert_eq!(evaluate(&world), Some(Score::new(0.5)));
+
+        // The cached score is returned until the resource changes.
+        world.increment_change_tick();
+        world
+            .resource_mut::<TestResource>()
+            .bypass_change_detection()
+            .0 = 20;
+        assert_eq!(evaluate(&world), Some(Score::new(0.5)));
+        world.resource_mut::<TestResource>().0 = 30;
+        assert_eq!(evaluate(&world), Some(Score::new(0.3)));
+
+        world.increment_change_tick();
+        world.remove_resource::<TestResource>();
+        assert_eq!(evaluate(&world), None);
+        world.insert_resource(TestResource(30));
+        assert_eq!(evaluate(&world), Some(Score::new(0.3)));
+    }
+
+    #[test]
+    fn resource_or_evaluator() {
+        let mut world = World::new();
+
+        let mut present = resource_present::<TestResource>();
+        let mut fallback = resource_or::<TestResource>(0.3);
+        present.initialize(&mut world);
+        fallback.initialize(&mut world);
+
+        let evaluate = |evaluator: &mut dyn Evaluator, world: &World| {
+            evaluator.evaluate(EvaluationCtx::new(world, Entity::PLACEHOLDER))
+        };
+
+        assert_eq!(evaluate(&mut present, &world), Score::MIN);
+        assert_eq!(evaluate(&mut fallback, &world), Score::new(0.3));
+
+        // A resource scoring zero is distinct from a missing one.
+        world.insert_resource(TestResource(0));
+        assert_eq!(evaluate(&mut present, &world), Score::MAX);
+        assert_eq!(evaluate(&mut fallback, &world), Score::MIN);
+
+        world.insert_resource(TestResource(80));
+        assert_eq!(evaluate(&mut fallback, &world), Score::new(0.8));
+    }
+
+    #[test]
+    fn sibling_evaluator() {
+        let mut world = World::new();
+
+        let e1 = world.spawn(TestComponent(10)).id();
+        let e2 = world.spawn(TestComponent(20)).id();
+        let e3 = world.spawn(TestComponent(30)).id();
+        world.spawn_empty().add_children(&[e1, e2, e3]);
+        let orphan = world.spawn(TestComponent(40)).id();
+
+        let mut evaluator = sibling_aggregate::<TestComponent, _>(sum());
+        evaluator.initialize(&mut world);
+
+        let mut evaluate = |target| evaluator.evaluate(EvaluationCtx::new(&world, target));
+
+        assert_eq!(evaluate(e1), Score::new(0.5));
+        assert_eq!(evaluate(e2), Score::new(0.4));
+        assert_eq!(evaluate(e3), Score::new(0.3));
+        assert_eq!(evaluate(orphan), Score::MIN);
+    }
+
+    #[cfg(feature = "bevy_transform")]
+    #[test]
+    fn distance_evaluator() {
+        use bevy_math::curve::Interval;
+        use bevy_transform::components::GlobalTransform;
+
+        use crate::evaluator::{distance, DistanceFalloff};
+
+        #[derive(Component)]
+        struct Enemy(Entity);
+
+        let mut world = World::new();
+        let enemy = world.spawn(GlobalTransform::from_xyz(3., 4., 0.)).id();
+        let npc = world
+            .spawn((GlobalTransform::from_xyz(0., 0., 0.), Enemy(enemy)))
+            .id();
+        let far = world
+            .spawn((GlobalTransform::from_xyz(0., 20., 0.), Enemy(enemy)))
+            .id();
+        let unpositioned = world.spawn(Enemy(enemy)).id();
+        let alone = world.spawn(GlobalTransform::default()).id();
+
+        let enemy_of = |world: &World, entity| world.get::<Enemy>(entity).map(|e| e.0);
+        let mut linear = distance(enemy_of, 10.);
+        let mut curved = distance(
+            enemy_of,
+            DistanceFalloff::curve(FunctionCurve::new(Interval::new(0., 8.).unwrap(), |d| {
+                Score::new(1. - d / 8.)
+            })),
+        );
+        linear.initialize(&mut world);
+        curved.initialize(&mut world);
+
+        let evaluate = |evaluator: &mut dyn Evaluator, target| {
+            evaluator.evaluate(EvaluationCtx::new(&world, target))
+        };
+
+        assert_eq!(evaluate(&mut linear, npc), Score::new(0.5));
+        assert_eq!(evaluate(&mut linear, far), Score::MIN);
+        assert_eq!(evaluate(&mut linear, unpositioned), Score::MIN);
+        assert_eq!(evaluate(&mut linear, alone), Score::MIN);
+        assert_eq!(evaluate(&mut curved, npc), Score::new(0.375));
+        assert_eq!(evaluate(&mut curved, far), Score::MIN);
+    }
+
+    #[cfg(feature = "bevy_transform")]
+    #[test]
+    fn proximity_count_evaluator() {
+        use bevy_ecs::query::With;
+        use bevy_transform::components::GlobalTransform;
+
+        use crate::evaluator::proximity_count;
+
+        #[derive(Component)]
+        struct Ally;
+
+        let mut world = World::new();
+        let npc = world.spawn((GlobalTransform::default(), Ally)).id();
+        // Inside the radius.
+        world.spawn((GlobalTransform::from_xyz(1., 0., 0.), Ally));
+        world.spawn((GlobalTransform::from_xyz(0., -2., 0.), Ally));
+        world.spawn(GlobalTransform::from_xyz(0., 0., 3.));
+        // Outside the radius.
+        world.spawn((GlobalTransform::from_xyz(5., 0., 0.), Ally));
+        world.spawn(GlobalTransform::from_xyz(0., 10., 0.));
+
+        let mut everyone = proximity_count::<()>(3., 4);
+        let mut allies = proximity_count::<With<Ally>>(3., 4);
+        let mut crowded = proximity_count::<()>(3., 2);
+        everyone.initialize(&mut world);
+        allies.initialize(&mut world);
+        crowded.initialize(&mut world);
+
+        let evaluate =
+            |evaluator: &mut dyn Evaluator| evaluator.evaluate(EvaluationCtx::new(&world, npc));
+
+        assert_eq!(evaluate(&mut everyone), Score::new(0.75));
+        assert_eq!(evaluate(&mut allies), Score::new(0.5));
+        assert_eq!(evaluate(&mut crowded), Score::MAX);
+    }
+
     #[test]
     fn target_evaluator() {
         let mut world = World::new();
@@ -394,11 +1756,85 @@ mod tests {
         let output = evaluator.evaluate(EvaluationCtx {
             world: &world,
             evaluation: Evaluation { target: entity },
+            scores: LabeledScores::default(),
         });
 
         assert_eq!(output, Score::new(0.25));
     }
 
+    #[test]
+    fn target_inverse_evaluator() {
+        let mut world = World::new();
+        let high = world.spawn(TestComponent(90)).id();
+        let low = world.spawn(TestComponent(10)).id();
+        let absent = world.spawn_empty().id();
+
+        let mut evaluator = target_inverse::<TestComponent>();
+        evaluator.initialize(&mut world);
+
+        let mut evaluate = |target| evaluator.evaluate(EvaluationCtx::new(&world, target));
+
+        assert_eq!(evaluate(high), Score::new(1. - 0.9));
+        assert_eq!(evaluate(low), Score::new(1. - 0.1));
+        assert_eq!(evaluate(absent), Score::MIN);
+
+        let mut evaluator = target_inverse_or::<TestComponent>(0.5);
+        evaluator.initialize(&mut world);
+
+        let output = evaluator.evaluate(EvaluationCtx::new(&world, absent));
+
+        assert_eq!(output, Score::new(0.5));
+    }
+
+    #[test]
+    fn invert_score_evaluator() {
+        let mut world = World::new();
+        let entities = [90, 25, 0].map(|value| world.spawn(TestComponent(value)).id());
+        let missing = world.spawn_empty().id();
+
+        let mut expected = target::<TestComponent>().invert();
+        let mut evaluator = target::<InvertScore<TestComponent>>();
+        expected.initialize(&mut world);
+        evaluator.initialize(&mut world);
+        let ctx = |target| EvaluationCtx::new(&world, target);
+
+        for entity in entities {
+            assert_eq!(
+                evaluator.evaluate(ctx(entity)),
+                expected.evaluate(ctx(entity))
+            );
+        }
+        assert_eq!(evaluator.evaluate(ctx(entities[1])), Score::new(0.75));
+        // Unlike inverting the evaluator, a missing component stays absent.
+        assert_eq!(evaluator.try_evaluate(ctx(missing)), None);
+    }
+
+    #[test]
+    fn target_ranged_evaluator() {
+        #[derive(Component)]
+        struct MaxHealth(i32);
+
+        impl ScoreableWith<MaxHealth> for TestComponent {
+            fn score_with(&self, max: &MaxHealth) -> Score {
+                Score::new(self.0 as f32 / max.0 as f32)
+            }
+        }
+
+        let mut world = World::new();
+        let weak = world.spawn((TestComponent(50), MaxHealth(100))).id();
+        let strong = world.spawn((TestComponent(50), MaxHealth(200))).id();
+        let unbounded = world.spawn(TestComponent(50)).id();
+
+        let mut evaluator = target_ranged::<TestComponent, MaxHealth>();
+        evaluator.initialize(&mut world);
+
+        let mut evaluate = |target| evaluator.evaluate(EvaluationCtx::new(&world, target));
+
+        assert_eq!(evaluate(weak), Score::new(0.5));
+        assert_eq!(evaluate(strong), Score::new(0.25));
+        assert_eq!(evaluate(unbounded), Score::MIN);
+    }
+
     #[test]
     fn weight_evaluator() {
         let mut world = World::new();
@@ -411,6 +1847,7 @@ mod tests {
             evaluation: Evaluation {
                 target: Entity::PLACEHOLDER,
             },
+            scores: LabeledScores::default(),
         });
 
         assert_eq!(output, Score::new(0.4));
@@ -2,6 +2,7 @@ use alloc::{borrow::Cow, format};
 
 use crate::{
     evaluator::{EvaluationCtx, Evaluator},
+    flow::FlowAccess,
     score::Score,
 };
 
@@ -20,4 +21,8 @@ impl Evaluator for ConstantScoreEvaluator {
     fn evaluate(&mut self, _ctx: EvaluationCtx) -> Score {
         self.0
     }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
 }
@@ -0,0 +1,92 @@
+use alloc::{borrow::Cow, format};
+
+use bevy_ecs::{
+    component::Tick,
+    query::{QueryFilter, QueryState, ReadOnlyQueryData},
+    world::World,
+};
+
+use crate::{
+    evaluator::{EvaluationCtx, Evaluator},
+    score::Score,
+};
+
+/// Creates an [`Evaluator`] that counts the entities in the whole world
+/// matching the query data `D` and filter `F`, regardless of the target
+/// entity, such as the number of enemies alive. The score is `count / max`,
+/// clamped to [`Score::MAX`]. If `max` is zero, the evaluator returns
+/// [`Score::MIN`].
+///
+/// As the count does not depend on the target, it is cached and only
+/// recounted once the world's change tick, entity count or archetypes change,
+/// so scoring many entities in the same run only counts once.
+///
+/// Use `proximity_count` to only count entities near the target.
+pub fn global_count<D, F>(max: usize) -> impl Evaluator
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    GlobalCountEvaluator::<D, F> {
+        max,
+        query: None,
+        cached: None,
+    }
+}
+
+/// The state of the world a count was computed in: its change tick, entity
+/// count and archetype count.
+type CountKey = (Tick, u32, usize);
+
+struct GlobalCountEvaluator<D: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> {
+    max: usize,
+    query: Option<QueryState<D, F>>,
+    /// The last count and the state of the world it was computed in.
+    cached: Option<(CountKey, usize)>,
+}
+
+impl<D, F> Evaluator for GlobalCountEvaluator<D, F>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "global_count({}, {}, {})",
+            core::any::type_name::<D>(),
+            core::any::type_name::<F>(),
+            self.max,
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.query = Some(QueryState::new(world));
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        let query = self
+            .query
+            .as_mut()
+            .expect("global_count evaluator was not initialized");
+        if self.max == 0 {
+            return Score::MIN;
+        }
+
+        let world = ctx.world;
+        let key = (
+            world.read_change_tick(),
+            world.entities().len(),
+            world.archetypes().len(),
+        );
+        let count = match self.cached {
+            Some((cached, count)) if cached == key => count,
+            _ => {
+                query.update_archetypes(world);
+                let count = query.iter_manual(world).count();
+                self.cached = Some((key, count));
+                count
+            }
+        };
+        Score::new(count as f32 / self.max as f32)
+    }
+}
@@ -0,0 +1,68 @@
+use alloc::{borrow::Cow, format};
+use core::marker::PhantomData;
+
+use bevy_ecs::{component::Component, world::World};
+
+use crate::{
+    evaluator::{EvaluationCtx, Evaluator},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Creates a [`Evaluator`] that returns [`Score::MAX`] if the target entity has
+/// the given [`Component`], and [`Score::MIN`] otherwise. Useful for gating
+/// behaviors on tag components.
+pub fn has_component<C: Component>() -> impl Evaluator {
+    HasComponentEvaluator::<C> {
+        present: true,
+        access: FlowAccess::all(),
+        _component: PhantomData,
+    }
+}
+
+/// Creates a [`Evaluator`] that returns [`Score::MAX`] if the target entity does
+/// not have the given [`Component`], and [`Score::MIN`] otherwise. This is the
+/// inverse of [`has_component`].
+pub fn missing_component<C: Component>() -> impl Evaluator {
+    HasComponentEvaluator::<C> {
+        present: false,
+        access: FlowAccess::all(),
+        _component: PhantomData,
+    }
+}
+
+struct HasComponentEvaluator<C: Component> {
+    /// Whether the component must be present to score [`Score::MAX`].
+    present: bool,
+    access: FlowAccess,
+    _component: PhantomData<C>,
+}
+
+impl<C: Component> Evaluator for HasComponentEvaluator<C> {
+    fn name(&self) -> Cow<'static, str> {
+        let name = if self.present {
+            "has_component"
+        } else {
+            "missing_component"
+        };
+        Cow::Owned(format!("{name}({})", core::any::type_name::<C>()))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = FlowAccess::none();
+        self.access
+            .add_component_read(world.register_component::<C>());
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        if ctx.world.get::<C>(ctx.evaluation.target).is_some() == self.present {
+            Score::MAX
+        } else {
+            Score::MIN
+        }
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
@@ -1,37 +1,55 @@
 use alloc::{borrow::Cow, format};
 use core::marker::PhantomData;
 
-use bevy_ecs::component::Component;
+use bevy_ecs::{component::Component, world::World};
 use bevy_hierarchy::Parent;
 
 use crate::{
     evaluator::{EvaluationCtx, Evaluator},
+    flow::FlowAccess,
     score::{Score, Scoreable},
 };
 
 /// Creates a [`Evaluator`] that scores the given [`Component`] on the parent
 /// entity of the target entity. If the target entity does not have a parent, or
 /// if the parent entity does not have the given component, the evaluator
-/// returns [`Score::MIN`].
+/// returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
 pub fn parent<C: Component + Scoreable>() -> impl Evaluator {
-    ParentEvaluator(PhantomData::<C>)
+    ParentEvaluator::<C> {
+        access: FlowAccess::all(),
+        _component: PhantomData,
+    }
 }
 
-struct ParentEvaluator<C: Component + Scoreable>(PhantomData<C>);
+struct ParentEvaluator<C: Component + Scoreable> {
+    access: FlowAccess,
+    _component: PhantomData<C>,
+}
 
 impl<C: Component + Scoreable> Evaluator for ParentEvaluator<C> {
     fn name(&self) -> Cow<'static, str> {
         Cow::Owned(format!("parent({})", core::any::type_name::<C>()))
     }
 
+    fn initialize(&mut self, world: &mut World) {
+        self.access = FlowAccess::none();
+        self.access
+            .add_component_read(world.register_component::<Parent>());
+        self.access
+            .add_related_component_read(world.register_component::<C>());
+    }
+
     fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
-        let Some(parent) = ctx.world.get::<Parent>(ctx.evaluation.target) else {
-            return Score::MIN;
-        };
-
-        ctx.world
-            .get::<C>(parent.get())
-            .map(|c| c.score())
-            .unwrap_or(Score::MIN)
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        let parent = ctx.world.get::<Parent>(ctx.evaluation.target)?;
+        ctx.world.get::<C>(parent.get()).map(|c| c.score())
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
     }
 }
@@ -1,31 +1,251 @@
 use alloc::{borrow::Cow, format};
 use core::marker::PhantomData;
 
-use bevy_ecs::system::Resource;
+use bevy_ecs::{component::Tick, system::Resource, world::World};
 
 use crate::{
     evaluator::{EvaluationCtx, Evaluator},
+    flow::FlowAccess,
     score::{Score, Scoreable},
 };
 
 /// Creates a [`Evaluator`] that scores the current value of the given
 /// [`Resource`]. If the resource is not present in the world, the evaluator
-/// returns [`Score::MIN`].
+/// returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
 pub fn resource<R: Resource + Scoreable>() -> impl Evaluator {
-    ResourceEvaluator::<R>(PhantomData)
+    ResourceEvaluator::<R> {
+        access: FlowAccess::all(),
+        _resource: PhantomData,
+    }
 }
 
-struct ResourceEvaluator<R: Resource + Scoreable>(PhantomData<R>);
+struct ResourceEvaluator<R: Resource + Scoreable> {
+    access: FlowAccess,
+    _resource: PhantomData<R>,
+}
 
 impl<R: Resource + Scoreable> Evaluator for ResourceEvaluator<R> {
     fn name(&self) -> Cow<'static, str> {
         Cow::Owned(format!("resource({})", core::any::type_name::<R>()))
     }
 
+    fn initialize(&mut self, world: &mut World) {
+        self.access = resource_access::<R>(world);
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        ctx.world.get_resource::<R>().map(|r| r.score())
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Creates a [`Evaluator`] that scores the current value of the given
+/// [`Resource`] like [`resource`], but caches the score and only re-scores the
+/// resource when it was changed. This avoids scoring the same resource again
+/// for every entity a flow is run on. If the resource is not present in the
+/// world, the evaluator returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
+///
+/// Changes are detected using the resource's change tick, so a change made
+/// during the same world tick as the previous evaluation is missed. This is
+/// never the case when the flow is run from a system, such as
+/// [`run_all_entity_flows`].
+///
+/// [`run_all_entity_flows`]: crate::component::run_all_entity_flows
+pub fn memoized_resource<R: Resource + Scoreable>() -> impl Evaluator {
+    MemoizedResourceEvaluator::<R> {
+        cached: None,
+        access: FlowAccess::all(),
+        _resource: PhantomData,
+    }
+}
+
+struct MemoizedResourceEvaluator<R: Resource + Scoreable> {
+    /// The tick at which the resource was last changed when it was scored, and
+    /// the resulting score.
+    cached: Option<(Tick, Score)>,
+    access: FlowAccess,
+    _resource: PhantomData<R>,
+}
+
+impl<R: Resource + Scoreable> Evaluator for MemoizedResourceEvaluator<R> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "memoized_resource({})",
+            core::any::type_name::<R>()
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = resource_access::<R>(world);
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        let Some(changed) = ctx
+            .world
+            .get_resource_change_ticks::<R>()
+            .map(|ticks| ticks.changed)
+        else {
+            self.cached = None;
+            return None;
+        };
+        match self.cached {
+            Some((tick, score)) if tick == changed => Some(score),
+            _ => {
+                let score = ctx.world.resource::<R>().score();
+                self.cached = Some((changed, score));
+                Some(score)
+            }
+        }
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Creates a [`Evaluator`] that scores the current value of the given
+/// [`Resource`]. If the resource is not present in the world, the evaluator
+/// returns the given `default` score, so that a missing resource can be told
+/// apart from one that scores zero.
+pub fn resource_or<R: Resource + Scoreable>(default: impl Into<Score>) -> impl Evaluator {
+    ResourceOrEvaluator::<R> {
+        default: default.into(),
+        access: FlowAccess::all(),
+        _resource: PhantomData,
+    }
+}
+
+struct ResourceOrEvaluator<R: Resource + Scoreable> {
+    default: Score,
+    access: FlowAccess,
+    _resource: PhantomData<R>,
+}
+
+impl<R: Resource + Scoreable> Evaluator for ResourceOrEvaluator<R> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "resource_or({}, {})",
+            core::any::type_name::<R>(),
+            self.default
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = resource_access::<R>(world);
+    }
+
     fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
         ctx.world
             .get_resource::<R>()
             .map(|r| r.score())
-            .unwrap_or(Score::MIN)
+            .unwrap_or(self.default)
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Creates a [`Evaluator`] that scores the current value of the given
+/// [`Resource`] using the given function, such as reading a tuning value
+/// from one of its fields. Unlike [`resource`], this does not require the
+/// resource to implement [`Scoreable`]. If the resource is not present in the
+/// world, the evaluator returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
+pub fn from_resource<R: Resource>(
+    map: impl Fn(&R) -> Score + Send + Sync + 'static,
+) -> impl Evaluator {
+    FromResourceEvaluator {
+        map,
+        access: FlowAccess::all(),
+        _resource: PhantomData::<R>,
+    }
+}
+
+struct FromResourceEvaluator<R: Resource, F> {
+    map: F,
+    access: FlowAccess,
+    _resource: PhantomData<R>,
+}
+
+impl<R, F> Evaluator for FromResourceEvaluator<R, F>
+where
+    R: Resource,
+    F: Fn(&R) -> Score + Send + Sync + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("from_resource({})", core::any::type_name::<R>()))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = resource_access::<R>(world);
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        ctx.world.get_resource::<R>().map(&self.map)
     }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Creates a [`Evaluator`] that returns [`Score::MAX`] if the given
+/// [`Resource`] is present in the world, and [`Score::MIN`] otherwise.
+pub fn resource_present<R: Resource>() -> impl Evaluator {
+    ResourcePresentEvaluator::<R> {
+        access: FlowAccess::all(),
+        _resource: PhantomData,
+    }
+}
+
+struct ResourcePresentEvaluator<R: Resource> {
+    access: FlowAccess,
+    _resource: PhantomData<R>,
+}
+
+impl<R: Resource> Evaluator for ResourcePresentEvaluator<R> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("resource_present({})", core::any::type_name::<R>()))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = resource_access::<R>(world);
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        if ctx.world.contains_resource::<R>() {
+            Score::MAX
+        } else {
+            Score::MIN
+        }
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Returns the access of an evaluator reading the given [`Resource`].
+fn resource_access<R: Resource>(world: &mut World) -> FlowAccess {
+    let mut access = FlowAccess::none();
+    access.add_resource_read(world.register_resource::<R>());
+    access
 }
@@ -0,0 +1,54 @@
+use alloc::{borrow::Cow, format, vec, vec::Vec};
+
+use crate::{
+    evaluator::{EvaluationCtx, Evaluator},
+    flow::FlowAccess,
+    label::{InternedScoreLabel, ScoreLabel},
+    score::Score,
+};
+
+/// Creates an [`Evaluator`] that returns the score of the node with the given
+/// [`ScoreLabel`] in the same run of the [`Flow`], read from
+/// [`EvaluationCtx::scores`]. This allows subtrees to share an intermediate
+/// score without computing it more than once.
+///
+/// When added to a flow, the evaluator is ordered to run after the labeled
+/// node, as with [`Flow::depends_on`]. The labeled node must be added before
+/// the evaluator and must not be one of its ancestors, otherwise the
+/// evaluator is not added and a [`FlowDependencyError`] is logged.
+///
+/// The score is absent if the labeled node's score is absent, such as when it
+/// is skipped by [`Flow::short_circuit`], or if the evaluator is run outside
+/// of a flow.
+///
+/// [`Flow`]: crate::flow::Flow
+/// [`Flow::depends_on`]: crate::flow::Flow::depends_on
+/// [`Flow::short_circuit`]: crate::flow::Flow::short_circuit
+/// [`FlowDependencyError`]: crate::flow::FlowDependencyError
+pub fn score_ref(label: impl ScoreLabel) -> impl Evaluator {
+    ScoreRefEvaluator(label.intern())
+}
+
+struct ScoreRefEvaluator(InternedScoreLabel);
+
+impl Evaluator for ScoreRefEvaluator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("score_ref({:?})", self.0))
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        ctx.scores.get(self.0)
+    }
+
+    fn access(&self) -> FlowAccess {
+        FlowAccess::none()
+    }
+
+    fn score_refs(&self) -> Vec<InternedScoreLabel> {
+        vec![self.0]
+    }
+}
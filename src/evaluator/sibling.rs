@@ -0,0 +1,76 @@
+use alloc::{borrow::Cow, format};
+use core::marker::PhantomData;
+
+use bevy_ecs::{
+    component::{Component, Tick},
+    world::World,
+};
+use bevy_hierarchy::{Children, Parent};
+use smallvec::SmallVec;
+
+use crate::{
+    aggregator::{Aggregation, AggregationCtx, Aggregator, IntoAggregator},
+    evaluator::{EvaluationCtx, Evaluator},
+    score::{Score, Scoreable},
+};
+
+/// Creates an [`Evaluator`] that scores the given [`Component`] on the siblings
+/// of the target entity (the other children of its parent), and then
+/// aggregates the scores using the given aggregator. The target entity itself
+/// is excluded. If the target entity does not have a parent, or none of its
+/// siblings have the component, the evaluator returns [`Score::MIN`].
+pub fn sibling_aggregate<C: Component + Scoreable, M>(
+    aggregator: impl IntoAggregator<M>,
+) -> impl Evaluator {
+    SiblingEvaluator {
+        aggregator: aggregator.into_aggregator(),
+        _component: PhantomData::<C>,
+    }
+}
+
+struct SiblingEvaluator<A: Aggregator, C: Component + Scoreable> {
+    aggregator: A,
+    _component: PhantomData<C>,
+}
+
+impl<A: Aggregator, C: Component + Scoreable> Evaluator for SiblingEvaluator<A, C> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "sibling_aggregate({}, {})",
+            core::any::type_name::<C>(),
+            self.aggregator.name(),
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.aggregator.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.aggregator.check_change_tick(change_tick);
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        let target = ctx.evaluation.target;
+        let Some(parent) = ctx.world.get::<Parent>(target) else {
+            return Score::MIN;
+        };
+        let Some(siblings) = ctx.world.get::<Children>(parent.get()) else {
+            return Score::MIN;
+        };
+        // Score the siblings' components and collect them.
+        let scores = siblings
+            .iter()
+            .filter(|&&entity| entity != target)
+            .flat_map(|&entity| ctx.world.get::<C>(entity).map(|c| c.score()))
+            .collect::<SmallVec<_>>();
+        // Return early if none of the siblings have the component.
+        if scores.is_empty() {
+            return Score::MIN;
+        }
+        self.aggregator.aggregate(AggregationCtx {
+            world: ctx.world,
+            aggregation: Aggregation { target, scores },
+        })
+    }
+}
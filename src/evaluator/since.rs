@@ -0,0 +1,103 @@
+use alloc::{borrow::Cow, format};
+use core::{marker::PhantomData, time::Duration};
+
+use bevy_ecs::{component::Component, world::World};
+use bevy_math::ops;
+use bevy_time::Time;
+
+use crate::{
+    evaluator::{EvaluationCtx, Evaluator},
+    flow::FlowAccess,
+    score::Score,
+};
+
+/// Trait for [`Component`]s that store when something last happened, such as
+/// when an entity last ate, as the elapsed time of the [`Time`] resource at
+/// that moment.
+pub trait Timestamp {
+    /// Returns the elapsed time of the [`Time`] resource at which the event
+    /// happened.
+    fn timestamp(&self) -> Duration;
+}
+
+/// Creates an [`Evaluator`] that scores the time elapsed since the
+/// [`Timestamp`] stored in the given [`Component`] on the target entity, using
+/// the elapsed time of the [`Time`] resource. The score decays exponentially
+/// from [`Score::MAX`], halving every `half_life`. Use [`since_with`] to map
+/// the elapsed time through another curve.
+///
+/// If the target entity does not have the component, or there is no [`Time`]
+/// resource, the evaluator returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
+///
+/// # Panics
+///
+/// If `half_life` is zero.
+pub fn since<C: Component + Timestamp>(half_life: Duration) -> impl Evaluator {
+    assert!(!half_life.is_zero(), "half_life must be non-zero");
+    SinceEvaluator::<C, _> {
+        name: Cow::Owned(format!(
+            "since({}, {half_life:?})",
+            core::any::type_name::<C>()
+        )),
+        curve: move |elapsed: Duration| {
+            Score::new(ops::exp2(-elapsed.as_secs_f32() / half_life.as_secs_f32()))
+        },
+        access: FlowAccess::all(),
+        _component: PhantomData,
+    }
+}
+
+/// Creates an [`Evaluator`] that scores the time elapsed since the
+/// [`Timestamp`] stored in the given [`Component`] on the target entity using
+/// the given function. Like [`since`], the score is absent if the target
+/// entity does not have the component or there is no [`Time`] resource.
+pub fn since_with<C: Component + Timestamp>(
+    curve: impl Fn(Duration) -> Score + Send + Sync + 'static,
+) -> impl Evaluator {
+    SinceEvaluator::<C, _> {
+        name: Cow::Owned(format!("since_with({})", core::any::type_name::<C>())),
+        curve,
+        access: FlowAccess::all(),
+        _component: PhantomData,
+    }
+}
+
+struct SinceEvaluator<C: Component + Timestamp, F> {
+    name: Cow<'static, str>,
+    curve: F,
+    access: FlowAccess,
+    _component: PhantomData<C>,
+}
+
+impl<C, F> Evaluator for SinceEvaluator<C, F>
+where
+    C: Component + Timestamp,
+    F: Fn(Duration) -> Score + Send + Sync + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = FlowAccess::none();
+        self.access
+            .add_component_read(world.register_component::<C>());
+        self.access
+            .add_resource_read(world.register_resource::<Time>());
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        let timestamp = ctx.world.get::<C>(ctx.evaluation.target)?.timestamp();
+        let now = ctx.world.get_resource::<Time>()?.elapsed();
+        Some((self.curve)(now.saturating_sub(timestamp)))
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::{borrow::Cow, boxed::Box, format};
+
+use bevy_ecs::{
+    entity::Entity,
+    query::{QueryFilter, QueryState},
+    world::World,
+};
+use bevy_math::Curve;
+use bevy_transform::components::GlobalTransform;
+
+use crate::{
+    evaluator::{EvaluationCtx, Evaluator},
+    score::Score,
+};
+
+/// Creates an [`Evaluator`] that scores the distance between the
+/// [`GlobalTransform`]s of the target entity and the entity returned by
+/// `other`, such as the target's current enemy. The distance is mapped to a
+/// score by the given [`DistanceFalloff`], so that nearer is higher. If `other`
+/// returns [`None`] or either entity has no [`GlobalTransform`], the evaluator
+/// returns [`Score::MIN`].
+///
+/// Passing an `f32` as the falloff uses [`DistanceFalloff::Linear`] with that
+/// maximum range.
+pub fn distance<F>(other: F, falloff: impl Into<DistanceFalloff>) -> impl Evaluator
+where
+    F: Fn(&World, Entity) -> Option<Entity> + Send + Sync + 'static,
+{
+    DistanceEvaluator {
+        other,
+        falloff: falloff.into(),
+    }
+}
+
+/// Maps a distance to a [`Score`] for the [`distance`] evaluator.
+pub enum DistanceFalloff {
+    /// Scores [`Score::MAX`] at a distance of zero, falling off linearly to
+    /// [`Score::MIN`] at `max_range` and beyond.
+    Linear {
+        /// The distance at and beyond which the score is [`Score::MIN`].
+        max_range: f32,
+    },
+    /// Samples the curve at the distance, clamped to the curve's domain.
+    Curve(Box<dyn Curve<Score> + Send + Sync>),
+}
+
+impl DistanceFalloff {
+    /// Creates a [`DistanceFalloff::Curve`] from the given curve.
+    pub fn curve(curve: impl Curve<Score> + Send + Sync + 'static) -> Self {
+        Self::Curve(Box::new(curve))
+    }
+
+    /// Maps the given distance to a [`Score`].
+    pub fn score(&self, distance: f32) -> Score {
+        match self {
+            Self::Linear { max_range } if *max_range > 0. => Score::new(1. - distance / max_range),
+            Self::Linear { .. } => Score::MIN,
+            Self::Curve(curve) => curve.sample_clamped(distance),
+        }
+    }
+}
+
+impl From<f32> for DistanceFalloff {
+    fn from(max_range: f32) -> Self {
+        Self::Linear { max_range }
+    }
+}
+
+struct DistanceEvaluator<F> {
+    other: F,
+    falloff: DistanceFalloff,
+}
+
+impl<F> Evaluator for DistanceEvaluator<F>
+where
+    F: Fn(&World, Entity) -> Option<Entity> + Send + Sync + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        match self.falloff {
+            DistanceFalloff::Linear { max_range } => {
+                Cow::Owned(format!("distance(linear({max_range}))"))
+            }
+            DistanceFalloff::Curve(_) => Cow::Borrowed("distance(curve)"),
+        }
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        let target = ctx.evaluation.target;
+        let Some(other) = (self.other)(ctx.world, target) else {
+            return Score::MIN;
+        };
+        let (Some(from), Some(to)) = (
+            ctx.world.get::<GlobalTransform>(target),
+            ctx.world.get::<GlobalTransform>(other),
+        ) else {
+            return Score::MIN;
+        };
+
+        self.falloff
+            .score(from.translation().distance(to.translation()))
+    }
+}
+
+/// Creates an [`Evaluator`] that counts the entities matching the query filter
+/// `F` whose [`GlobalTransform`] is within `radius` of the target entity's,
+/// excluding the target itself. The score is `count / max_count`, clamped to
+/// [`Score::MAX`]. If the target entity has no [`GlobalTransform`] or
+/// `max_count` is zero, the evaluator returns [`Score::MIN`].
+///
+/// Use `()` as the filter to count all entities with a [`GlobalTransform`].
+///
+/// # Performance
+///
+/// Each evaluation naively scans every matching entity, so scoring `n`
+/// entities against each other is `O(n²)`. Narrow the query with a filter for
+/// large worlds; a spatial index may replace the scan in the future.
+pub fn proximity_count<F: QueryFilter + 'static>(radius: f32, max_count: usize) -> impl Evaluator {
+    ProximityCountEvaluator::<F> {
+        radius,
+        max_count,
+        query: None,
+    }
+}
+
+struct ProximityCountEvaluator<F: QueryFilter + 'static> {
+    radius: f32,
+    max_count: usize,
+    query: Option<QueryState<(Entity, &'static GlobalTransform), F>>,
+}
+
+impl<F: QueryFilter + 'static> Evaluator for ProximityCountEvaluator<F> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "proximity_count({}, {}, {})",
+            core::any::type_name::<F>(),
+            self.radius,
+            self.max_count,
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.query = Some(QueryState::new(world));
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        let query = self
+            .query
+            .as_mut()
+            .expect("proximity_count evaluator was not initialized");
+        let target = ctx.evaluation.target;
+        let Some(origin) = ctx.world.get::<GlobalTransform>(target) else {
+            return Score::MIN;
+        };
+        if self.max_count == 0 {
+            return Score::MIN;
+        }
+
+        query.update_archetypes(ctx.world);
+        let origin = origin.translation();
+        let radius_squared = self.radius * self.radius;
+        let count = query
+            .iter_manual(ctx.world)
+            .filter(|&(entity, transform)| {
+                entity != target
+                    && transform.translation().distance_squared(origin) <= radius_squared
+            })
+            .count();
+
+        Score::new(count as f32 / self.max_count as f32)
+    }
+}
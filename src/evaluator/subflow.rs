@@ -0,0 +1,85 @@
+use alloc::{borrow::Cow, format, vec, vec::Vec};
+
+use bevy_ecs::world::World;
+
+use crate::{
+    evaluator::{EvaluationCtx, Evaluator},
+    flow::{Flows, WorldFlowExt},
+    label::{FlowLabel, InternedFlowLabel, InternedScoreLabel, ScoreLabel},
+    score::Score,
+};
+
+/// Creates an [`Evaluator`] that runs the [`Flow`] with the given label on the
+/// target entity and returns the score of its node with the given
+/// [`ScoreLabel`]. This allows a reusable flow to be embedded as a single node
+/// of other flows. The embedded flow is initialized along with this evaluator.
+///
+/// If the flow does not exist, is not initialized, or has no score for the
+/// given label, the evaluator returns [`Score::MIN`], and the score is absent
+/// from [`Evaluator::try_evaluate`].
+///
+/// Flows must not embed each other, directly or through other flows: a flow
+/// that reaches itself while running would lock its own nodes again and
+/// deadlock. When initialized, the evaluator checks the flows reachable
+/// through [`Evaluator::subflows`], and if the flow being initialized or any
+/// other flow would be run recursively, it logs an error and its score is
+/// always absent. Only flows stored in the [`Flows`] resource are checked,
+/// and the check is not repeated if the flows are modified afterwards.
+///
+/// [`Flow`]: crate::flow::Flow
+pub fn subflow(flow: impl FlowLabel, score: impl ScoreLabel) -> impl Evaluator {
+    SubflowEvaluator {
+        flow: flow.intern(),
+        score: score.intern(),
+        recursive: false,
+    }
+}
+
+struct SubflowEvaluator {
+    flow: InternedFlowLabel,
+    score: InternedScoreLabel,
+    /// Whether the flow would run recursively, in which case it is never run.
+    recursive: bool,
+}
+
+impl Evaluator for SubflowEvaluator {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("subflow({:?}, {:?})", self.flow, self.score))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.recursive = world
+            .get_resource::<Flows>()
+            .is_some_and(|flows| flows.recurses(self.flow));
+        if self.recursive {
+            tracing::error!(
+                "{} would run the {:?} flow recursively, so it will never be run.",
+                self.name(),
+                self.flow
+            );
+            return;
+        }
+        world
+            .try_flow_scope(self.flow, |world, flow| flow.initialize(world))
+            .ok();
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        if self.recursive {
+            return None;
+        }
+        ctx.world
+            .get_flow(self.flow)
+            .filter(|flow| flow.is_initialized())?
+            .run_readonly(ctx.world, ctx.evaluation.target)
+            .score(self.score)
+    }
+
+    fn subflows(&self) -> Vec<InternedFlowLabel> {
+        vec![self.flow]
+    }
+}
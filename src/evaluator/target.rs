@@ -1,31 +1,217 @@
 use alloc::{borrow::Cow, format};
 use core::marker::PhantomData;
 
-use bevy_ecs::component::Component;
+use bevy_ecs::{component::Component, world::World};
 
 use crate::{
     evaluator::{EvaluationCtx, Evaluator},
-    score::{Score, Scoreable},
+    flow::FlowAccess,
+    score::{Score, ScoreComponent, Scoreable, ScoreableWith},
 };
 
 /// Creates a [`Evaluator`] that scores the given [`Component`] on the target
 /// entity. If the target entity does not have the component, the evaluator
-/// returns [`Score::MIN`].
-pub fn target<C: Component + Scoreable>() -> impl Evaluator {
-    TargetEvaluator::<C>(PhantomData)
+/// returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
+///
+/// Besides [`Scoreable`] components, any [`ScoreComponent`] adapter can be
+/// used, such as `target::<InvertScore<C>>()` to score the inverse of `C`.
+///
+/// [`InvertScore`]: crate::score::InvertScore
+pub fn target<C: ScoreComponent>() -> impl Evaluator {
+    TargetEvaluator::<C> {
+        access: FlowAccess::all(),
+        _component: PhantomData,
+    }
 }
 
-struct TargetEvaluator<C: Component + Scoreable>(PhantomData<C>);
+struct TargetEvaluator<C: ScoreComponent> {
+    access: FlowAccess,
+    _component: PhantomData<C>,
+}
 
-impl<C: Component + Scoreable> Evaluator for TargetEvaluator<C> {
+impl<C: ScoreComponent> Evaluator for TargetEvaluator<C> {
     fn name(&self) -> Cow<'static, str> {
         Cow::Owned(format!("target({})", core::any::type_name::<C>()))
     }
 
+    fn initialize(&mut self, world: &mut World) {
+        self.access = FlowAccess::none();
+        self.access
+            .add_component_read(world.register_component::<C::Component>());
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        ctx.world
+            .get::<C::Component>(ctx.evaluation.target)
+            .map(C::score_component)
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Creates a [`Evaluator`] that scores the given [`Component`] on the target
+/// entity using the given function, such as reading one of its numeric
+/// fields. Unlike [`target`], this does not require the component to implement
+/// [`Scoreable`]. If the target entity does not have the component, the
+/// evaluator returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
+pub fn target_with<C: Component>(
+    map: impl Fn(&C) -> Score + Send + Sync + 'static,
+) -> impl Evaluator {
+    TargetWithEvaluator {
+        map,
+        access: FlowAccess::all(),
+        _component: PhantomData::<C>,
+    }
+}
+
+struct TargetWithEvaluator<C: Component, F> {
+    map: F,
+    access: FlowAccess,
+    _component: PhantomData<C>,
+}
+
+impl<C, F> Evaluator for TargetWithEvaluator<C, F>
+where
+    C: Component,
+    F: Fn(&C) -> Score + Send + Sync + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("target_with({})", core::any::type_name::<C>()))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = FlowAccess::none();
+        self.access
+            .add_component_read(world.register_component::<C>());
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        ctx.world.get::<C>(ctx.evaluation.target).map(&self.map)
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Creates a [`Evaluator`] that scores the inverse (`1 - score`) of the given
+/// [`Component`] on the target entity. Unlike `target::<C>().invert()`, a
+/// missing component is not treated as a zero score that inverts to
+/// [`Score::MAX`]: if the target entity does not have the component, the
+/// evaluator returns [`Score::MIN`].
+pub fn target_inverse<C: Component + Scoreable>() -> impl Evaluator {
+    target_inverse_or::<C>(Score::MIN)
+}
+
+/// Creates a [`Evaluator`] that scores the inverse (`1 - score`) of the given
+/// [`Component`] on the target entity. If the target entity does not have the
+/// component, the evaluator returns the given `absent` score.
+pub fn target_inverse_or<C: Component + Scoreable>(absent: impl Into<Score>) -> impl Evaluator {
+    TargetInverseEvaluator::<C> {
+        absent: absent.into(),
+        access: FlowAccess::all(),
+        _component: PhantomData,
+    }
+}
+
+struct TargetInverseEvaluator<C: Component + Scoreable> {
+    absent: Score,
+    access: FlowAccess,
+    _component: PhantomData<C>,
+}
+
+impl<C: Component + Scoreable> Evaluator for TargetInverseEvaluator<C> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "target_inverse_or({}, {})",
+            core::any::type_name::<C>(),
+            self.absent
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = FlowAccess::none();
+        self.access
+            .add_component_read(world.register_component::<C>());
+    }
+
     fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
         ctx.world
             .get::<C>(ctx.evaluation.target)
-            .map(|c| c.score())
-            .unwrap_or(Score::MIN)
+            .map(|c| Score::new(1. - c.score().get()))
+            .unwrap_or(self.absent)
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
+    }
+}
+
+/// Creates a [`Evaluator`] that scores the given [`Component`] on the target
+/// entity against the companion `Max` component on the same entity, using
+/// [`ScoreableWith`]. If the target entity is missing either component, the
+/// evaluator returns [`Score::MIN`], and the score is absent from
+/// [`Evaluator::try_evaluate`].
+pub fn target_ranged<C, Max>() -> impl Evaluator
+where
+    C: Component + ScoreableWith<Max>,
+    Max: Component,
+{
+    TargetRangedEvaluator::<C, Max> {
+        access: FlowAccess::all(),
+        _components: PhantomData,
+    }
+}
+
+struct TargetRangedEvaluator<C, Max> {
+    access: FlowAccess,
+    _components: PhantomData<(C, Max)>,
+}
+
+impl<C, Max> Evaluator for TargetRangedEvaluator<C, Max>
+where
+    C: Component + ScoreableWith<Max>,
+    Max: Component,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "target_ranged({}, {})",
+            core::any::type_name::<C>(),
+            core::any::type_name::<Max>()
+        ))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.access = FlowAccess::none();
+        self.access
+            .add_component_read(world.register_component::<C>());
+        self.access
+            .add_component_read(world.register_component::<Max>());
+    }
+
+    fn evaluate(&mut self, ctx: EvaluationCtx) -> Score {
+        self.try_evaluate(ctx).unwrap_or(Score::MIN)
+    }
+
+    fn try_evaluate(&mut self, ctx: EvaluationCtx) -> Option<Score> {
+        let target = ctx.evaluation.target;
+        let (c, max) = (ctx.world.get::<C>(target)?, ctx.world.get::<Max>(target)?);
+        Some(c.score_with(max))
+    }
+
+    fn access(&self) -> FlowAccess {
+        self.access.clone()
     }
 }
@@ -0,0 +1,42 @@
+use alloc::{format, vec::Vec};
+
+use bevy_ecs::component::Component;
+
+use crate::{
+    evaluator::{target_with, Evaluator, IntoEvaluator},
+    score::Score,
+};
+
+/// Creates a [`Evaluator`] that scores the given [`Component`] on the target
+/// entity using the given function, typically a `match` assigning a score to
+/// each variant of an enum, or a table built with [`variant_scores`]. This
+/// avoids implementing [`Scoreable`] on the component itself. If the target
+/// entity does not have the component, the evaluator returns [`Score::MIN`],
+/// and the score is absent from [`Evaluator::try_evaluate`].
+///
+/// [`Scoreable`]: crate::score::Scoreable
+pub fn target_enum<C: Component>(
+    map: impl Fn(&C) -> Score + Send + Sync + 'static,
+) -> impl Evaluator {
+    target_with(map).named(format!("target_enum({})", core::any::type_name::<C>()))
+}
+
+/// A predicate matching variants of `C`, paired with the score of those
+/// variants. See [`variant_scores`].
+pub type VariantScore<C> = (fn(&C) -> bool, Score);
+
+/// Builds a mapping for [`target_enum`] from pairs of a variant predicate and
+/// its score, such as `(|state| matches!(state, AiState::Idle), Score::MIN)`.
+/// The score of the first matching predicate is returned, or [`Score::MIN`] if
+/// none match.
+pub fn variant_scores<C: 'static>(
+    variants: &[VariantScore<C>],
+) -> impl Fn(&C) -> Score + Send + Sync + 'static {
+    let variants: Vec<_> = variants.to_vec();
+    move |value| {
+        variants
+            .iter()
+            .find(|(matches, _)| matches(value))
+            .map_or(Score::MIN, |&(_, score)| score)
+    }
+}
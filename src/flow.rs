@@ -1,33 +1,61 @@
 //! Provides the [`Flow`] type for defining a collection of [`Aggregator`] and
 //! [`Evaluator`] nodes and running them in topological order.
 
-use core::hash::Hash;
+use core::{
+    fmt::{self, Write},
+    hash::Hash,
+    ops::{Deref, Range, RangeInclusive},
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use alloc::{boxed::Box, vec::Vec};
-use bevy_ecs::{entity::Entity, system::Resource, world::World};
-use bevy_utils::HashMap;
+use alloc::{borrow::Cow, boxed::Box, format, string::String, vec, vec::Vec};
+use bevy_ecs::{
+    change_detection::DetectChangesMut, component::Tick, entity::Entity, system::Resource,
+    world::World,
+};
+use bevy_utils::{HashMap, HashSet};
 use parking_lot::Mutex;
-use petgraph::{algo::toposort, prelude::DiGraphMap};
+use petgraph::{
+    algo::{tarjan_scc, toposort},
+    prelude::DiGraphMap,
+    Direction,
+};
 use smallvec::SmallVec;
 use thiserror::Error;
 use tracing::warn;
 
 use crate::{
-    aggregator::{Aggregation, AggregationCtx, Aggregator},
+    aggregator::{Aggregation, AggregationCtx, Aggregator, ShortCircuit},
+    component::ComputedScores,
     evaluator::{Evaluation, EvaluationCtx, Evaluator},
     label::{FlowLabel, InternedFlowLabel, InternedScoreLabel, ScoreLabel},
     score::Score,
 };
 
+mod access;
+mod builder;
 mod config;
+mod inspect;
+#[cfg(feature = "profiling")]
+mod profile;
+mod trace;
 
+pub use access::*;
+pub use builder::*;
 pub use config::*;
+pub use inspect::*;
+#[cfg(feature = "profiling")]
+pub use profile::*;
+pub use trace::*;
 
 /// [`Resource`] that stores [`Flow`]s mapped to [`FlowLabel`]s, excluding the
 /// current running [`Flow`].
 #[derive(Resource, Default)]
 pub struct Flows {
     inner: HashMap<InternedFlowLabel, Flow>,
+    /// Labels of the flows currently pulled out by
+    /// [`WorldFlowExt::try_flow_scope`], innermost last.
+    scoped: Vec<InternedFlowLabel>,
 }
 
 impl Flows {
@@ -49,6 +77,64 @@ impl Flows {
         self.inner.remove(&label.intern())
     }
 
+    /// Returns a mutable reference to the [`Flow`] with the given label, if it
+    /// exists.
+    pub fn get_mut(&mut self, label: impl FlowLabel) -> Option<&mut Flow> {
+        self.inner.get_mut(&label.intern())
+    }
+
+    /// Returns `true` if a [`Flow`] with the given label exists.
+    pub fn contains(&self, label: impl FlowLabel) -> bool {
+        self.inner.contains_key(&label.intern())
+    }
+
+    /// Returns an iterator over all [`FlowLabel`] and [`Flow`] pairs, in
+    /// arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (InternedFlowLabel, &Flow)> + '_ {
+        self.inner.iter().map(|(&label, flow)| (label, flow))
+    }
+
+    /// Returns an iterator over the labels of all [`Flow`]s, in arbitrary
+    /// order.
+    pub fn labels(&self) -> impl Iterator<Item = InternedFlowLabel> + '_ {
+        self.inner.keys().copied()
+    }
+
+    /// Returns `true` if running the flow with the given label would run,
+    /// through its subflows, itself or a flow that is currently pulled out by
+    /// [`WorldFlowExt::try_flow_scope`], such as the flow being initialized.
+    pub(crate) fn recurses(&self, label: InternedFlowLabel) -> bool {
+        fn visit(
+            flows: &Flows,
+            label: InternedFlowLabel,
+            path: &mut Vec<InternedFlowLabel>,
+        ) -> bool {
+            if flows.scoped.contains(&label) || path.contains(&label) {
+                return true;
+            }
+            let Some(flow) = flows.inner.get(&label) else {
+                return false;
+            };
+            path.push(label);
+            let recurses = flow
+                .subflows()
+                .into_iter()
+                .any(|subflow| visit(flows, subflow, path));
+            path.pop();
+            recurses
+        }
+
+        visit(self, label, &mut Vec::new())
+    }
+
+    /// Checks the change ticks stored by the nodes of all [`Flow`]s. See
+    /// [`Flow::check_change_ticks`].
+    pub fn check_change_ticks(&mut self, change_tick: Tick) {
+        for flow in self.inner.values_mut() {
+            flow.check_change_ticks(change_tick);
+        }
+    }
+
     /// Adds one or more nodes to the [`Flow`] matching the given [`FlowLabel`].
     pub fn add_nodes<M>(
         &mut self,
@@ -65,6 +151,14 @@ impl Flows {
 pub struct Flow {
     label: InternedFlowLabel,
     graph: FlowGraph,
+    /// Whether [`run_all_entity_flows`] skips entities whose inputs have not
+    /// changed.
+    ///
+    /// [`run_all_entity_flows`]: crate::component::run_all_entity_flows
+    skip_unchanged: bool,
+    /// Whether aggregators skip their remaining children once their score is
+    /// determined. See [`Flow::short_circuit`].
+    short_circuit: bool,
 }
 
 impl Flow {
@@ -72,16 +166,73 @@ impl Flow {
     pub fn new(label: impl FlowLabel) -> Self {
         Self {
             label: label.intern(),
-            graph: FlowGraph::default(),
+            graph: FlowGraph {
+                generation: next_generation(),
+                ..FlowGraph::default()
+            },
+            skip_unchanged: false,
+            short_circuit: false,
         }
     }
 
+    /// Returns a [`FlowBuilder`] for a flow with the given label.
+    pub fn builder(label: impl FlowLabel) -> FlowBuilder {
+        FlowBuilder::new(label)
+    }
+
     /// Add a collection of nodes to the flow.
     pub fn add_nodes<M>(&mut self, nodes: impl IntoFlowNodeConfigs<M>) -> &mut Self {
         self.add_nodes_with_parent(None, nodes);
         self
     }
 
+    /// Like [`Flow::add_nodes`], but fails instead of logging an error if any
+    /// of the nodes has a [`ScoreLabel`] that is already used in the flow or
+    /// by another of the nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DuplicateLabelError`] if a label is used more than once. None
+    /// of the nodes are added in that case.
+    pub fn add_nodes_checked<M>(
+        &mut self,
+        nodes: impl IntoFlowNodeConfigs<M>,
+    ) -> Result<(), DuplicateLabelError> {
+        let configs = nodes.into_configs();
+        self.check_labels(&configs, &mut HashMap::default())?;
+        self.add_nodes_with_parent(None, configs);
+        Ok(())
+    }
+
+    /// Checks that none of the labels of the given nodes or their descendants
+    /// are already used by the flow or in `seen`.
+    fn check_labels(
+        &self,
+        configs: &FlowNodeConfigs,
+        seen: &mut HashMap<InternedScoreLabel, Cow<'static, str>>,
+    ) -> Result<(), DuplicateLabelError> {
+        for config in &configs.0 {
+            if let Some(label) = config.label {
+                let existing = match self.graph.labels.get(&label) {
+                    Some(&node) => Some(self.graph.describe(node).1),
+                    None => seen.get(&label).cloned(),
+                };
+                if let Some(existing) = existing {
+                    return Err(DuplicateLabelError {
+                        label,
+                        existing,
+                        duplicate: config.node.name(),
+                    });
+                }
+                seen.insert(label, config.node.name());
+            }
+            if let FlowNode::Aggregator { children, .. } = &config.node {
+                self.check_labels(children, seen)?;
+            }
+        }
+        Ok(())
+    }
+
     fn add_nodes_with_parent<M>(
         &mut self,
         parent: Option<NodeId>,
@@ -102,7 +253,13 @@ impl Flow {
                 continue;
             }
 
-            let (node, children) = self.graph.add_node(parent, config.node);
+            let (node, children) = match self.graph.add_node(parent, config.node) {
+                Ok(added) => added,
+                Err(e) => {
+                    tracing::error!("{e} The node was not added to the {:?} flow.", self.label);
+                    continue;
+                }
+            };
 
             if let Some(label) = config.label {
                 self.add_label(label, node);
@@ -119,10 +276,7 @@ impl Flow {
     fn add_label(&mut self, label: impl ScoreLabel, node: NodeId) {
         let label = label.intern();
         if let Some(&nid) = self.graph.labels.get(&label) {
-            let (kind, name) = match nid {
-                NodeId::Evaluator(e) => ("evaluator", self.graph.evaluators[e].lock().name()),
-                NodeId::Aggregator(a) => ("aggregator", self.graph.aggregators[a].lock().name()),
-            };
+            let (kind, name) = self.graph.describe(nid);
             tracing::error!(
                 "Label {label:?} is already associated with {kind} {name} in the {:?} flow. It was not overwritten.",
                 self.label
@@ -133,79 +287,600 @@ impl Flow {
         }
     }
 
+    /// Moves all nodes of `other` into this flow, keeping their labels and
+    /// parent-child relations, so that a shared base flow can be extended
+    /// with extra nodes. The label and settings of `other` are discarded in
+    /// favor of those of this flow. Nodes of `other` that were already
+    /// initialized are not initialized again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DuplicateLabelError`] if a label of `other` is already used
+    /// in this flow. Nothing is merged in that case, and `other` is dropped.
+    pub fn merge(&mut self, other: Flow) -> Result<(), DuplicateLabelError> {
+        for (&label, &node) in &other.graph.labels {
+            if let Some(&existing) = self.graph.labels.get(&label) {
+                return Err(DuplicateLabelError {
+                    label,
+                    existing: self.graph.describe(existing).1,
+                    duplicate: other.graph.describe(node).1,
+                });
+            }
+        }
+        self.graph.merge(other.graph);
+        Ok(())
+    }
+
+    /// Orders the node labeled with `node` to run after the node labeled with
+    /// `dependency`, without making it a child. This lets a node read the
+    /// score of a node in another subtree, as [`score_ref`] does, which adds
+    /// such an ordering automatically.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlowDependencyError`] if either label is not used in the flow
+    /// or the ordering would introduce a dependency cycle, such as when
+    /// `dependency` is a descendant of `node`.
+    ///
+    /// [`score_ref`]: crate::evaluator::score_ref
+    pub fn depends_on(
+        &mut self,
+        node: impl ScoreLabel,
+        dependency: impl ScoreLabel,
+    ) -> Result<(), FlowDependencyError> {
+        let lookup = |label: InternedScoreLabel| {
+            self.graph
+                .labels
+                .get(&label)
+                .copied()
+                .ok_or(FlowDependencyError::UnknownLabel(label))
+        };
+        let node = lookup(node.intern())?;
+        let dependency = lookup(dependency.intern())?;
+        self.graph.add_ordering(dependency, node)?;
+        Ok(())
+    }
+
+    /// Returns `true` if a node in the flow is labeled with the given
+    /// [`ScoreLabel`].
+    pub fn has_label(&self, label: impl ScoreLabel) -> bool {
+        self.graph.labels.contains_key(&label.intern())
+    }
+
+    /// Sets whether [`run_all_entity_flows`] skips re-scoring entities whose
+    /// inputs have not changed since it last ran, keeping their previous
+    /// [`ComputedScores`]. Disabled by default.
+    ///
+    /// An entity is re-scored if any component in the flow's [`FlowAccess`]
+    /// was added to or changed on it, if it gained or lost any component, if
+    /// any resource read by the flow changed or is missing, or if the
+    /// [`Flow::generation`] changed since the entity was last scored. Flows with a
+    /// node that reads all of the world, or components of related entities,
+    /// are always re-scored.
+    ///
+    /// [`run_all_entity_flows`]: crate::component::run_all_entity_flows
+    /// [`ComputedScores`]: crate::component::ComputedScores
+    pub fn skip_unchanged(&mut self, skip: bool) -> &mut Self {
+        self.skip_unchanged = skip;
+        self
+    }
+
+    /// Returns `true` if unchanged entities are skipped when running this flow
+    /// for all entities. See [`Flow::skip_unchanged`].
+    pub fn skips_unchanged(&self) -> bool {
+        self.skip_unchanged
+    }
+
+    /// Sets whether nodes are skipped once the score of an ancestor aggregator
+    /// is determined by one of its other children, as declared by
+    /// [`Aggregator::short_circuits`]. For example, the remaining children of
+    /// a [`product`] are skipped once any child scores [`Score::MIN`].
+    /// Disabled by default.
+    ///
+    /// The scores of skipped nodes are absent, so labeled nodes that are
+    /// skipped are missing from the results. Children are run in the order
+    /// they were added, each along with its descendants, unless
+    /// [`Flow::depends_on`] orders them otherwise, so put cheap children that
+    /// are likely to determine the score first.
+    ///
+    /// [`product`]: crate::aggregator::product
+    pub fn short_circuit(&mut self, enabled: bool) -> &mut Self {
+        if self.short_circuit != enabled {
+            self.short_circuit = enabled;
+            self.graph.generation = next_generation();
+        }
+        self
+    }
+
+    /// Returns `true` if nodes are skipped once the score of an ancestor
+    /// aggregator is determined. See [`Flow::short_circuit`].
+    pub fn short_circuits(&self) -> bool {
+        self.short_circuit
+    }
+
+    /// Returns the combined [`FlowAccess`] of all nodes in the flow. Only up to
+    /// date once the flow is initialized.
+    pub fn access(&self) -> &FlowAccess {
+        &self.graph.access
+    }
+
+    /// Returns a read-only view over the structure of the flow.
+    pub fn inspect(&self) -> FlowInspector<'_> {
+        FlowInspector { graph: &self.graph }
+    }
+
+    /// Removes the node labeled with the given [`ScoreLabel`], along with all
+    /// of its descendant nodes. Returns `false` if no node has the label.
+    pub fn remove_label(&mut self, label: impl ScoreLabel) -> bool {
+        let Some(&node) = self.graph.labels.get(&label.intern()) else {
+            return false;
+        };
+        self.graph.remove_subtree(node);
+        true
+    }
+
+    /// Removes all nodes from the flow, keeping its label. Nodes added
+    /// afterwards must be initialized again before running.
+    pub fn clear(&mut self) {
+        self.graph = FlowGraph {
+            generation: next_generation(),
+            ..FlowGraph::default()
+        };
+    }
+
+    /// Checks the change ticks stored by all nodes in the flow, such as the
+    /// last run ticks of system nodes, to prevent change detection false
+    /// positives when the world's change tick overflows. Bevy only checks the
+    /// systems in its schedules, so this is called by
+    /// [`check_flow_change_ticks`] instead.
+    ///
+    /// [`check_flow_change_ticks`]: crate::component::check_flow_change_ticks
+    pub fn check_change_ticks(&mut self, change_tick: Tick) {
+        self.graph.check_change_ticks(change_tick);
+    }
+
+    /// Returns a number that changes whenever the structure of the flow
+    /// changes in a way that can affect its scores, such as when nodes are
+    /// initialized, removed or merged in. Generations are unique across all
+    /// flows, so a flow replaced by another one under the same label also
+    /// has a different generation. Used by [`run_all_entity_flows`] to
+    /// re-score the entities of modified flows.
+    ///
+    /// [`run_all_entity_flows`]: crate::component::run_all_entity_flows
+    pub fn generation(&self) -> u64 {
+        self.graph.generation
+    }
+
+    /// Returns the labels of the flows run by the evaluators of this flow. See
+    /// [`Evaluator::subflows`].
+    pub(crate) fn subflows(&self) -> Vec<InternedFlowLabel> {
+        self.graph
+            .evaluators
+            .iter()
+            .flatten()
+            .flat_map(|evaluator| evaluator.lock().subflows())
+            .collect()
+    }
+
+    /// Returns `true` if all nodes in the flow have been initialized.
+    pub fn is_initialized(&self) -> bool {
+        self.graph.uninitialized.is_empty()
+    }
+
     /// Initializes the flow if necessary and runs it, returning the scores of
     /// all labeled nodes.
-    pub fn run(&mut self, world: &mut World, target: Entity) -> HashMap<InternedScoreLabel, Score> {
+    pub fn run(&mut self, world: &mut World, target: Entity) -> FlowScores {
         self.initialize(world);
         self.run_readonly(world, target)
     }
 
+    /// Initializes the flow if necessary and runs it for each of the given
+    /// targets, returning the scores of all labeled nodes for each target in
+    /// the same order. The scratch buffers for intermediate scores are reused
+    /// across targets.
+    pub fn run_batch(&mut self, world: &mut World, targets: &[Entity]) -> Vec<FlowScores> {
+        self.initialize(world);
+        let mut scratch = FlowScratch::default();
+        targets
+            .iter()
+            .map(|&target| {
+                let mut scores = HashMap::with_capacity(self.graph.labels.len());
+                self.run_observed(world, target, &mut scores, &mut scratch, &mut ());
+                FlowScores(scores)
+            })
+            .collect()
+    }
+
     /// Runs the flow, returning the scores of all labeled nodes.
     ///
     /// # Panics
     ///
     /// If the flow was not initialized before running.
-    pub fn run_readonly(
+    pub fn run_readonly(&self, world: &World, target: Entity) -> FlowScores {
+        let mut scores = HashMap::with_capacity(self.graph.labels.len());
+        self.run_observed(
+            world,
+            target,
+            &mut scores,
+            &mut FlowScratch::default(),
+            &mut (),
+        );
+        FlowScores(scores)
+    }
+
+    /// Runs the flow, clearing `out` and filling it with the scores of all
+    /// labeled nodes. Reusing the same map across runs avoids allocating a
+    /// new one each time.
+    ///
+    /// # Panics
+    ///
+    /// If the flow was not initialized before running.
+    pub fn run_readonly_into(
+        &self,
+        world: &World,
+        target: Entity,
+        out: &mut HashMap<InternedScoreLabel, Score>,
+    ) {
+        self.run_readonly_with_scratch(world, target, out, &mut FlowScratch::default());
+    }
+
+    /// Like [`Flow::run_readonly_into`], but also reuses the given
+    /// [`FlowScratch`] for the intermediate scores of aggregator nodes. The
+    /// same scratch buffer can be reused across runs of different flows.
+    ///
+    /// # Panics
+    ///
+    /// If the flow was not initialized before running.
+    pub fn run_readonly_with_scratch(
+        &self,
+        world: &World,
+        target: Entity,
+        out: &mut HashMap<InternedScoreLabel, Score>,
+        scratch: &mut FlowScratch,
+    ) {
+        out.clear();
+        self.run_observed(world, target, out, scratch, &mut ());
+    }
+
+    /// Runs the flow while timing each node, returning the scores of all
+    /// labeled nodes and a [`FlowProfile`] that mirrors the dependency graph.
+    ///
+    /// # Panics
+    ///
+    /// If the flow was not initialized before running.
+    #[cfg(feature = "profiling")]
+    pub fn run_profiled(&self, world: &World, target: Entity) -> (FlowScores, FlowProfile) {
+        let mut profiler = Profiler::default();
+        let mut scores = HashMap::with_capacity(self.graph.labels.len());
+        self.run_observed(
+            world,
+            target,
+            &mut scores,
+            &mut FlowScratch::default(),
+            &mut profiler,
+        );
+        let profile = profiler.finish(&self.graph);
+        (FlowScores(scores), profile)
+    }
+
+    /// Runs the flow, returning a [`FlowTrace`] holding the score of every
+    /// node, including unlabeled intermediate nodes.
+    ///
+    /// # Panics
+    ///
+    /// If the flow was not initialized before running.
+    pub fn run_traced(&self, world: &World, target: Entity) -> FlowTrace {
+        let mut tracer = Tracer::default();
+        self.run_observed(
+            world,
+            target,
+            &mut HashMap::new(),
+            &mut FlowScratch::default(),
+            &mut tracer,
+        );
+        tracer.finish(&self.graph)
+    }
+
+    /// Runs only the node labeled with the given [`ScoreLabel`], its
+    /// descendants and the nodes it depends on, returning its score. Returns
+    /// [`None`] if no node has the label or its score is absent.
+    ///
+    /// # Panics
+    ///
+    /// If the flow was not initialized before running.
+    pub fn run_label(
+        &self,
+        world: &World,
+        target: Entity,
+        label: impl ScoreLabel,
+    ) -> Option<Score> {
+        assert!(
+            self.is_initialized(),
+            "flow {:?} was not initialized before running",
+            self.label
+        );
+
+        let &root = self.graph.labels.get(&label.intern())?;
+        let dependencies = self.graph.dependencies(root);
+        let mut slots = vec![None; self.graph.plan.slot_count];
+        for step in &self.graph.plan.steps {
+            if !dependencies.contains(&step.node) {
+                continue;
+            }
+            let score = self.run_step(step, world, target, &slots, &mut ());
+            if step.node == root {
+                return score;
+            }
+            slots[step.slot] = score;
+        }
+        unreachable!("labeled node {root:?} is missing from the plan")
+    }
+
+    /// Runs the flow, passing each node through the given [`NodeObserver`] and
+    /// inserting the scores of all labeled nodes into `labeled_scores`.
+    fn run_observed(
         &self,
         world: &World,
         target: Entity,
-    ) -> HashMap<InternedScoreLabel, Score> {
+        labeled_scores: &mut HashMap<InternedScoreLabel, Score>,
+        scratch: &mut FlowScratch,
+        observer: &mut impl NodeObserver,
+    ) {
         assert!(
-            self.graph.uninitialized.is_empty(),
+            self.is_initialized(),
             "flow {:?} was not initialized before running",
             self.label
         );
 
-        let mut labeled_scores = HashMap::with_capacity(self.graph.labels.len());
-        // Holds the intermediate child scores for each aggregator node.
-        let mut aggregator_child_scores = HashMap::<NodeId, SmallVec<[Score; 4]>>::with_capacity(
-            self.graph.dependency.node_count(),
+        let slots = &mut scratch.slots;
+        slots.clear();
+        slots.resize(self.graph.plan.slot_count, None);
+
+        if !self.short_circuit {
+            for step in &self.graph.plan.steps {
+                slots[step.slot] = self.run_step(step, world, target, slots, observer);
+            }
+        } else {
+            let steps = &self.graph.plan.steps;
+            let determined = &mut scratch.determined;
+            determined.clear();
+            determined.resize(steps.len(), false);
+            for step in steps {
+                // Skip the node if the score of any ancestor is determined.
+                let mut ancestor = step.parent;
+                while let Some(parent) = ancestor {
+                    if determined[parent] {
+                        break;
+                    }
+                    ancestor = steps[parent].parent;
+                }
+                if ancestor.is_some() {
+                    continue;
+                }
+
+                let score = self.run_step(step, world, target, slots, observer);
+                slots[step.slot] = score;
+                if let (Some(score), Some(parent)) = (score, step.parent) {
+                    if steps[parent]
+                        .short_circuit
+                        .is_some_and(|condition| condition.is_met_by(score))
+                    {
+                        determined[parent] = true;
+                    }
+                }
+            }
+        }
+
+        labeled_scores.extend(
+            self.graph
+                .plan
+                .labeled
+                .iter()
+                .filter_map(|&(label, slot)| Some((label, slots[slot]?))),
         );
+    }
 
-        for &node in &self.graph.dependency_toposort {
-            let score = match node {
-                NodeId::Evaluator(eval_idx) => {
-                    let mut evaluator = self.graph.evaluators[eval_idx].lock();
+    /// Runs the node of the given step, reading the scores of its children
+    /// from `slots`, and returns its score.
+    fn run_step(
+        &self,
+        step: &PlanStep,
+        world: &World,
+        target: Entity,
+        slots: &[Option<Score>],
+        observer: &mut impl NodeObserver,
+    ) -> Option<Score> {
+        match step.node {
+            NodeId::Evaluator(eval_idx) => {
+                let mut evaluator = self.graph.evaluator(eval_idx).lock();
+                #[cfg(feature = "trace")]
+                let _span = tracing::info_span!("evaluator", name = %evaluator.name()).entered();
 
-                    evaluator.evaluate(EvaluationCtx {
+                observer.observe(step.node, || {
+                    evaluator.try_evaluate(EvaluationCtx {
                         world,
                         evaluation: Evaluation { target },
+                        scores: LabeledScores {
+                            labeled: &self.graph.plan.labeled,
+                            slots,
+                        },
                     })
-                }
-                NodeId::Aggregator(aggr_idx) => {
-                    let scores = aggregator_child_scores
-                        .remove(&node)
-                        .expect("aggregator node was not scored before its children");
-                    let mut aggregator = self.graph.aggregators[aggr_idx].lock();
+                })
+            }
+            NodeId::Aggregator(aggr_idx) => {
+                // Absent child scores are skipped.
+                let scores: SmallVec<[Score; 4]> = slots[step.children.clone()]
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .collect();
+                let mut aggregator = self.graph.aggregator(aggr_idx).lock();
+                #[cfg(feature = "trace")]
+                let _span = tracing::info_span!("aggregator", name = %aggregator.name()).entered();
 
-                    aggregator.aggregate(AggregationCtx {
+                // An aggregator whose children are all absent is not run unless
+                // it opts in, so that empty AND-style aggregations don't score it
+                // as satisfied.
+                let all_absent = !step.children.is_empty()
+                    && scores.is_empty()
+                    && !aggregator.aggregates_absent();
+                let score = observer.observe(step.node, || {
+                    if all_absent {
+                        return Some(Score::MIN);
+                    }
+                    Some(aggregator.aggregate(AggregationCtx {
                         world,
                         aggregation: Aggregation { target, scores },
-                    })
+                    }))
+                });
+                observer.explain(|| {
+                    let present = || {
+                        step.children
+                            .clone()
+                            .filter_map(|slot| Some((slot, slots[slot]?)))
+                    };
+                    let scores = present()
+                        .map(|(_, score)| score)
+                        .collect::<SmallVec<[Score; 4]>>();
+                    let index = aggregator.explain(&scores)?;
+                    present().nth(index).map(|(slot, _)| slot)
+                });
+                score
+            }
+        }
+    }
+
+    /// Initializes all evaluators and aggregators in the flow. Any problems
+    /// found by [`Flow::validate`] in newly added nodes are logged as warnings.
+    pub fn initialize(&mut self, world: &mut World) {
+        if self.is_initialized() {
+            return;
+        }
+        self.graph.initialize(world);
+        for warning in self.validate() {
+            warn!("{warning} in the {:?} flow.", self.label);
+        }
+    }
+
+    /// Checks the flow for likely construction mistakes, such as aggregators
+    /// without children or with a child count outside of their
+    /// [`Aggregator::required_arity`], returning a [`FlowWarning`] for each
+    /// one found.
+    pub fn validate(&self) -> Vec<FlowWarning> {
+        self.graph
+            .dependency_toposort
+            .iter()
+            .filter_map(|&node| {
+                let NodeId::Aggregator(a) = node else {
+                    return None;
+                };
+                let handle = FlowNodeHandle(node);
+                let children = self.graph.child_count(node);
+                if children == 0 {
+                    return Some(FlowWarning::ChildlessAggregator {
+                        handle,
+                        name: self.graph.describe(node).1,
+                    });
                 }
-            };
+                let required = self.graph.aggregator(a).lock().required_arity()?;
+                (!required.contains(&children)).then(|| FlowWarning::ArityMismatch {
+                    handle,
+                    name: self.graph.describe(node).1,
+                    children,
+                    required,
+                })
+            })
+            .collect()
+    }
 
-            if let Some(label) = self.graph.labeled.get(&node) {
-                labeled_scores.insert(*label, score);
+    /// Renders the flow as an indented ASCII tree, for quick debugging in logs.
+    ///
+    /// Each line shows the name of a node, followed by its [`ScoreLabel`] in
+    /// brackets if it has one. Children are listed below their parent in the
+    /// order they were added.
+    pub fn format_tree(&self) -> String {
+        fn write_node(
+            graph: &FlowGraph,
+            node: NodeId,
+            prefix: &str,
+            last: bool,
+            tree: &mut String,
+        ) {
+            let _ = write!(
+                tree,
+                "\n{prefix}{}{}",
+                if last { "`-- " } else { "|-- " },
+                graph.describe(node).1
+            );
+            if let Some(label) = graph.labeled.get(&node) {
+                let _ = write!(tree, " [{label:?}]");
+            }
+            let prefix = format!("{prefix}{}", if last { "    " } else { "|   " });
+            let children = graph.children(node);
+            for (i, &child) in children.iter().enumerate() {
+                write_node(graph, child, &prefix, i + 1 == children.len(), tree);
             }
+        }
+
+        let mut tree = format!("{:?}", self.label);
+        let roots = &self.graph.roots;
+        for (i, &root) in roots.iter().enumerate() {
+            write_node(&self.graph, root, "", i + 1 == roots.len(), &mut tree);
+        }
+        tree
+    }
 
-            let parent = self.graph.dependency.neighbors(node).next();
+    /// Renders the flow as a [Graphviz](https://graphviz.org/) DOT digraph.
+    ///
+    /// Each node is labeled with its name and kind, plus its [`ScoreLabel`] if
+    /// it has one. Edges point from child nodes to their parent, matching the
+    /// order in which nodes are run. Orderings between nodes that are not
+    /// parent and child, as added by [`Flow::depends_on`], are dashed.
+    pub fn to_dot(&self) -> String {
+        fn escape(s: &str) -> String {
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        }
 
-            if let Some(parent) = parent {
-                aggregator_child_scores
-                    .entry(parent)
-                    .or_default()
-                    .push(score);
+        fn id(node: NodeId) -> String {
+            match node {
+                NodeId::Evaluator(i) => format!("e{i}"),
+                NodeId::Aggregator(i) => format!("a{i}"),
             }
         }
 
-        labeled_scores
+        let mut dot = String::new();
+        let _ = writeln!(
+            dot,
+            "digraph \"{}\" {{",
+            escape(&format!("{:?}", self.label))
+        );
+        for node in self.graph.dependency.nodes() {
+            let (kind, name) = self.graph.describe(node);
+            let mut label = format!("{}\\n({kind})", escape(&name));
+            if let Some(score) = self.graph.labeled.get(&node) {
+                let _ = write!(label, "\\n[{}]", escape(&format!("{score:?}")));
+            }
+            let shape = match node {
+                NodeId::Evaluator(_) => "ellipse",
+                NodeId::Aggregator(_) => "box",
+            };
+            let _ = writeln!(dot, "    {} [label=\"{label}\", shape={shape}];", id(node));
+        }
+        for (child, parent, _) in self.graph.dependency.all_edges() {
+            let _ = writeln!(dot, "    {} -> {};", id(child), id(parent));
+        }
+        for (before, after, _) in self.graph.ordering.all_edges() {
+            let _ = writeln!(dot, "    {} -> {} [style=dashed];", id(before), id(after));
+        }
+        dot.push('}');
+        dot
     }
+}
 
-    /// Initializes all evaluators and aggregators in the flow.
-    pub fn initialize(&mut self, world: &mut World) {
-        self.graph.initialize(world);
-    }
+/// Returns a new, never before used [`Flow::generation`].
+fn next_generation() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    NEXT.fetch_add(1, Ordering::Relaxed)
 }
 
 /// Stores all nodes in a flow graph and their dependency metadata.
@@ -213,10 +888,25 @@ impl Flow {
 pub struct FlowGraph {
     /// All evaluator nodes in the [`Flow`]. Any [`NodeId::Evaluator`] value
     /// must be an index into this [`Vec`].
-    evaluators: Vec<Mutex<Box<dyn Evaluator>>>,
+    ///
+    /// Removed nodes are tombstoned as [`None`] rather than shifted out, so
+    /// that the [`NodeId`]s of the remaining nodes stay valid.
+    evaluators: Vec<Option<Mutex<Box<dyn Evaluator>>>>,
     /// All aggregator nodes in the [`Flow`]. Any [`NodeId::Aggregator`] value
     /// must be an index into this [`Vec`].
-    aggregators: Vec<Mutex<Box<dyn Aggregator>>>,
+    ///
+    /// Removed nodes are tombstoned as [`None`] rather than shifted out, so
+    /// that the [`NodeId`]s of the remaining nodes stay valid.
+    aggregators: Vec<Option<Mutex<Box<dyn Aggregator>>>>,
+    /// Children of each aggregator node in the order they were added, indexed
+    /// like [`FlowGraph::aggregators`]. The adjacency lists of
+    /// [`FlowGraph::dependency`] are reordered when nodes are removed, so this
+    /// is the source of truth for the order aggregators see their child
+    /// scores in.
+    children: Vec<Vec<NodeId>>,
+    /// Nodes without a parent, in the order they were added, for the same
+    /// reason as [`FlowGraph::children`].
+    roots: Vec<NodeId>,
     /// [`Evaluator`]s/[`Aggregator`]s that have not been initialized yet.
     uninitialized: Vec<NodeId>,
     /// All labeled nodes in the [`Flow`].
@@ -225,41 +915,139 @@ pub struct FlowGraph {
     labels: HashMap<InternedScoreLabel, NodeId>,
     /// Directed acyclic graph of node dependencies (which nodes have to run before which other nodes).
     dependency: DiGraphMap<NodeId, ()>,
+    /// Ordering-only dependencies between nodes that are not parent and
+    /// child, such as those added by [`Flow::depends_on`] and
+    /// [`Evaluator::score_refs`]. Edges point from the node that runs first.
+    ordering: DiGraphMap<NodeId, ()>,
     /// Topological sort of the dependency graph.
     dependency_toposort: Vec<NodeId>,
+    /// Precomputed order and score slots used to run the flow. Rebuilt by
+    /// [`FlowGraph::update_plan`] when the flow is initialized or nodes are
+    /// removed.
+    plan: FlowPlan,
+    /// Combined access of all nodes, rebuilt along with
+    /// [`FlowGraph::plan`].
+    access: FlowAccess,
+    /// See [`Flow::generation`]. Renewed along with [`FlowGraph::plan`].
+    generation: u64,
 }
 
 impl FlowGraph {
     /// Initializes all evaluators and aggregators in the flow.
     pub fn initialize(&mut self, world: &mut World) {
-        for id in self.uninitialized.drain(..) {
+        if self.uninitialized.is_empty() {
+            return;
+        }
+
+        for id in core::mem::take(&mut self.uninitialized) {
             match id {
-                NodeId::Evaluator(i) => self.evaluators[i].lock().initialize(world),
-                NodeId::Aggregator(i) => self.aggregators[i].lock().initialize(world),
+                NodeId::Evaluator(i) => self.evaluator(i).lock().initialize(world),
+                NodeId::Aggregator(i) => self.aggregator(i).lock().initialize(world),
             }
         }
+        self.update_plan();
+    }
+
+    /// Checks the change ticks of all evaluators and aggregators in the flow.
+    fn check_change_ticks(&mut self, change_tick: Tick) {
+        for evaluator in self.evaluators.iter_mut().flatten() {
+            evaluator.get_mut().check_change_tick(change_tick);
+        }
+        for aggregator in self.aggregators.iter_mut().flatten() {
+            aggregator.get_mut().check_change_tick(change_tick);
+        }
+    }
+
+    /// Returns the evaluator at the given index.
+    ///
+    /// # Panics
+    ///
+    /// If the evaluator was removed.
+    fn evaluator(&self, index: usize) -> &Mutex<Box<dyn Evaluator>> {
+        self.evaluators[index]
+            .as_ref()
+            .expect("evaluator node was removed from the flow")
+    }
+
+    /// Returns the aggregator at the given index.
+    ///
+    /// # Panics
+    ///
+    /// If the aggregator was removed.
+    fn aggregator(&self, index: usize) -> &Mutex<Box<dyn Aggregator>> {
+        self.aggregators[index]
+            .as_ref()
+            .expect("aggregator node was removed from the flow")
+    }
+
+    /// Returns the children of the given node, in the order they were added.
+    fn children(&self, node: NodeId) -> &[NodeId] {
+        match node {
+            NodeId::Evaluator(_) => &[],
+            NodeId::Aggregator(a) => &self.children[a],
+        }
+    }
+
+    /// Returns the number of children of the given node.
+    fn child_count(&self, node: NodeId) -> usize {
+        match node {
+            NodeId::Evaluator(_) => 0,
+            NodeId::Aggregator(a) => self.children[a].len(),
+        }
+    }
+
+    /// Returns the kind and name of the given node.
+    fn describe(&self, node: NodeId) -> (&'static str, Cow<'static, str>) {
+        match node {
+            NodeId::Evaluator(e) => ("evaluator", self.evaluator(e).lock().name()),
+            NodeId::Aggregator(a) => ("aggregator", self.aggregator(a).lock().name()),
+        }
     }
 
     /// Adds an individual node to the [`FlowGraph`] and returns its [`NodeId`]
     /// and children, if any.
+    ///
+    /// Evaluator nodes are ordered after the nodes labeled with their
+    /// [`Evaluator::score_refs`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlowDependencyError`] if a score referenced by the node is
+    /// not labeled yet or adding the node would introduce a dependency cycle,
+    /// in which case the graph is left unchanged.
     fn add_node(
         &mut self,
         parent: Option<NodeId>,
         node: FlowNode,
-    ) -> (NodeId, Option<FlowNodeConfigs>) {
+    ) -> Result<(NodeId, Option<FlowNodeConfigs>), FlowDependencyError> {
+        let score_refs = match &node {
+            FlowNode::Evaluator { evaluator } => evaluator.score_refs(),
+            FlowNode::Aggregator { .. } => Vec::new(),
+        };
+        let dependencies = score_refs
+            .into_iter()
+            .map(|label| {
+                self.labels
+                    .get(&label)
+                    .copied()
+                    .ok_or(FlowDependencyError::UnknownLabel(label))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
         let (node, children) = match node {
             FlowNode::Aggregator {
                 aggregator,
                 children,
             } => {
                 let node = NodeId::Aggregator(self.aggregators.len());
-                self.aggregators.push(Mutex::new(aggregator));
+                self.aggregators.push(Some(Mutex::new(aggregator)));
+                self.children.push(Vec::new());
                 self.uninitialized.push(node);
                 (node, Some(children))
             }
             FlowNode::Evaluator { evaluator } => {
                 let node = NodeId::Evaluator(self.evaluators.len());
-                self.evaluators.push(Mutex::new(evaluator));
+                self.evaluators.push(Some(Mutex::new(evaluator)));
                 self.uninitialized.push(node);
                 (node, None)
             }
@@ -267,33 +1055,489 @@ impl FlowGraph {
 
         if let Some(parent) = parent {
             self.dependency.add_edge(node, parent, ());
+            if let NodeId::Aggregator(p) = parent {
+                self.children[p].push(node);
+            }
         } else {
             self.dependency.add_node(node);
+            self.roots.push(node);
+        }
+        for dependency in dependencies {
+            self.ordering.add_edge(dependency, node, ());
         }
 
-        self.dependency_toposort =
-            toposort(&self.dependency, None).unwrap_or_else(|_| unreachable!());
+        if let Err(e) = self.update_toposort() {
+            // Roll back the node, which was pushed last.
+            self.dependency.remove_node(node);
+            self.ordering.remove_node(node);
+            self.uninitialized.pop();
+            match parent {
+                Some(NodeId::Aggregator(p)) => drop(self.children[p].pop()),
+                Some(NodeId::Evaluator(_)) => {}
+                None => drop(self.roots.pop()),
+            }
+            match node {
+                NodeId::Evaluator(_) => drop(self.evaluators.pop()),
+                NodeId::Aggregator(_) => {
+                    self.aggregators.pop();
+                    self.children.pop();
+                }
+            }
+            return Err(e.into());
+        }
 
-        (node, children)
+        Ok((node, children))
     }
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
-enum NodeId {
-    /// Index into [`FlowGraph::evaluators`].
-    Evaluator(usize),
-    /// Index into [`FlowGraph::aggregators`].
-    Aggregator(usize),
-}
 
-/// [`World`] extension trait for working with [`Flow`]s.
-pub trait WorldFlowExt {
-    /// Adds a collection of nodes to the flow with the given label.
+    /// Orders `before` to run before `after`.
     ///
-    /// If the flow does not exist, it will be created.
-    fn add_nodes<M>(
-        &mut self,
-        label: impl FlowLabel,
+    /// # Errors
+    ///
+    /// Returns [`FlowCycleError`] if the ordering would introduce a dependency
+    /// cycle, in which case the graph is left unchanged.
+    fn add_ordering(&mut self, before: NodeId, after: NodeId) -> Result<(), FlowCycleError> {
+        if self.ordering.add_edge(before, after, ()).is_some() {
+            return Ok(());
+        }
+        if let Err(e) = self.update_toposort() {
+            self.ordering.remove_edge(before, after);
+            return Err(e);
+        }
+        self.update_plan();
+        Ok(())
+    }
+
+    /// Moves all nodes of `other` into this graph, offsetting their
+    /// [`NodeId`]s past the nodes already in it. Labels must not collide.
+    fn merge(&mut self, other: FlowGraph) {
+        let evaluator_offset = self.evaluators.len();
+        let aggregator_offset = self.aggregators.len();
+        let remap = |node: NodeId| match node {
+            NodeId::Evaluator(i) => NodeId::Evaluator(i + evaluator_offset),
+            NodeId::Aggregator(i) => NodeId::Aggregator(i + aggregator_offset),
+        };
+
+        self.evaluators.extend(other.evaluators);
+        self.aggregators.extend(other.aggregators);
+        self.children.extend(
+            other
+                .children
+                .into_iter()
+                .map(|children| children.into_iter().map(remap).collect()),
+        );
+        self.roots.extend(other.roots.into_iter().map(remap));
+        self.uninitialized
+            .extend(other.uninitialized.into_iter().map(remap));
+        for (node, label) in other.labeled {
+            self.labeled.insert(remap(node), label);
+            self.labels.insert(label, remap(node));
+        }
+        for node in other.dependency.nodes() {
+            self.dependency.add_node(remap(node));
+        }
+        for (child, parent, _) in other.dependency.all_edges() {
+            self.dependency.add_edge(remap(child), remap(parent), ());
+        }
+        for (before, after, _) in other.ordering.all_edges() {
+            self.ordering.add_edge(remap(before), remap(after), ());
+        }
+
+        self.update_toposort()
+            .expect("merging disjoint graphs cannot introduce a dependency cycle");
+        self.update_plan();
+    }
+
+    /// Recomputes [`FlowGraph::dependency_toposort`], respecting both the
+    /// parent-child dependencies and the [`FlowGraph::ordering`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlowCycleError`] naming the nodes involved if the dependency
+    /// graph contains a cycle, in which case the previous topological sort is
+    /// kept.
+    fn update_toposort(&mut self) -> Result<(), FlowCycleError> {
+        let combined;
+        let graph = if self.ordering.edge_count() == 0 {
+            &self.dependency
+        } else {
+            let mut graph = self.dependency.clone();
+            for (before, after, _) in self.ordering.all_edges() {
+                graph.add_edge(before, after, ());
+            }
+            combined = graph;
+            &combined
+        };
+        match toposort(graph, None) {
+            Ok(sorted) => {
+                self.dependency_toposort = self.run_order(&sorted);
+                Ok(())
+            }
+            Err(cycle) => {
+                let start = cycle.node_id();
+                let nodes = tarjan_scc(graph)
+                    .into_iter()
+                    .find(|component| component.contains(&start))
+                    .unwrap_or_else(|| vec![start]);
+                Err(FlowCycleError {
+                    nodes: nodes
+                        .into_iter()
+                        .map(|node| self.describe(node).1)
+                        .collect(),
+                })
+            }
+        }
+    }
+
+    /// Orders the topologically `sorted` nodes so that the children of each
+    /// aggregator run in the order they were added, each with its descendants,
+    /// and every node still runs after the nodes it is ordered after.
+    fn run_order(&self, sorted: &[NodeId]) -> Vec<NodeId> {
+        fn visit(
+            graph: &FlowGraph,
+            node: NodeId,
+            visited: &mut HashSet<NodeId>,
+            order: &mut Vec<NodeId>,
+        ) {
+            if !visited.insert(node) {
+                return;
+            }
+            for before in graph.ordering.neighbors_directed(node, Direction::Incoming) {
+                visit(graph, before, visited, order);
+            }
+            for &child in graph.children(node) {
+                visit(graph, child, visited, order);
+            }
+            order.push(node);
+        }
+
+        let mut visited = HashSet::default();
+        let mut order = Vec::with_capacity(sorted.len());
+        for &root in &self.roots {
+            visit(self, root, &mut visited, &mut order);
+        }
+        debug_assert_eq!(order.len(), sorted.len());
+        order
+    }
+
+    /// Returns the given node and all nodes that have to run before it: its
+    /// descendants and the nodes it is ordered after, along with their own
+    /// dependencies.
+    fn dependencies(&self, node: NodeId) -> HashSet<NodeId> {
+        let mut dependencies = HashSet::default();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            if !dependencies.insert(node) {
+                continue;
+            }
+            stack.extend(
+                self.dependency
+                    .neighbors_directed(node, Direction::Incoming),
+            );
+            stack.extend(self.ordering.neighbors_directed(node, Direction::Incoming));
+        }
+        dependencies
+    }
+
+    /// Removes the given node and all of its descendant nodes from the
+    /// [`FlowGraph`].
+    fn remove_subtree(&mut self, node: NodeId) {
+        match self.dependency.neighbors(node).next() {
+            Some(NodeId::Aggregator(p)) => self.children[p].retain(|&child| child != node),
+            Some(NodeId::Evaluator(_)) => {}
+            None => self.roots.retain(|&root| root != node),
+        }
+
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            stack.extend(
+                self.dependency
+                    .neighbors_directed(node, Direction::Incoming),
+            );
+            self.dependency.remove_node(node);
+            self.ordering.remove_node(node);
+
+            match node {
+                NodeId::Evaluator(i) => self.evaluators[i] = None,
+                NodeId::Aggregator(i) => {
+                    self.aggregators[i] = None;
+                    self.children[i].clear();
+                }
+            }
+            if let Some(label) = self.labeled.remove(&node) {
+                self.labels.remove(&label);
+            }
+            self.uninitialized.retain(|&id| id != node);
+        }
+
+        self.update_toposort()
+            .expect("removing nodes cannot introduce a dependency cycle");
+        self.update_plan();
+    }
+
+    /// Recomputes [`FlowGraph::plan`] and [`FlowGraph::access`] from the
+    /// topological sort.
+    ///
+    /// Every node is assigned a slot for its score. The slots of an
+    /// aggregator's children are contiguous and ordered as the children are
+    /// run, so the child scores can be copied out as a single slice.
+    fn update_plan(&mut self) {
+        let mut slot_count = 0;
+        let mut children = HashMap::<NodeId, Range<usize>>::default();
+        for &node in &self.dependency_toposort {
+            let count = self.child_count(node);
+            if count > 0 {
+                children.insert(node, slot_count..slot_count + count);
+                slot_count += count;
+            }
+        }
+
+        // Children fill their parent's slots in the order they were added, so
+        // aggregators see their scores in that order regardless of run order.
+        let mut child_slots = HashMap::<NodeId, usize>::default();
+        for (&parent, range) in &children {
+            for (index, &child) in self.children(parent).iter().enumerate() {
+                child_slots.insert(child, range.start + index);
+            }
+        }
+        let indices = self
+            .dependency_toposort
+            .iter()
+            .enumerate()
+            .map(|(index, &node)| (node, index))
+            .collect::<HashMap<_, _>>();
+        let mut slots = HashMap::<NodeId, usize>::default();
+        let steps = self
+            .dependency_toposort
+            .iter()
+            .map(|&node| {
+                let parent = self.dependency.neighbors(node).next();
+                let slot = match parent {
+                    Some(_) => child_slots[&node],
+                    None => {
+                        slot_count += 1;
+                        slot_count - 1
+                    }
+                };
+                slots.insert(node, slot);
+                PlanStep {
+                    node,
+                    slot,
+                    children: children.get(&node).cloned().unwrap_or_default(),
+                    parent: parent.map(|parent| indices[&parent]),
+                    short_circuit: match node {
+                        NodeId::Aggregator(a) => self.aggregator(a).lock().short_circuits(),
+                        NodeId::Evaluator(_) => None,
+                    },
+                }
+            })
+            .collect();
+
+        let labeled = self
+            .labeled
+            .iter()
+            .map(|(node, &label)| (label, slots[node]))
+            .collect();
+
+        self.plan = FlowPlan {
+            steps,
+            labeled,
+            slot_count,
+        };
+        self.generation = next_generation();
+
+        let mut access = FlowAccess::none();
+        for &node in &self.dependency_toposort {
+            match node {
+                NodeId::Evaluator(e) => access.extend(&self.evaluator(e).lock().access()),
+                NodeId::Aggregator(a) => access.extend(&self.aggregator(a).lock().access()),
+            }
+        }
+        self.access = access;
+    }
+}
+
+/// The scores of the labeled nodes of a [`Flow`], as returned by
+/// [`Flow::run`] and [`WorldFlowExt::run_flow`]. Dereferences to the
+/// underlying map.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct FlowScores(HashMap<InternedScoreLabel, Score>);
+
+impl FlowScores {
+    /// Returns the [`Score`] associated with the given [`ScoreLabel`]. Unlike
+    /// [`HashMap::get`], reached through [`Deref`], this takes the label
+    /// itself rather than a reference to the interned label.
+    pub fn score(&self, label: impl ScoreLabel) -> Option<Score> {
+        self.0.get(&label.intern()).copied()
+    }
+
+    /// Returns the number of scores.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no scores.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the underlying map.
+    pub fn into_inner(self) -> HashMap<InternedScoreLabel, Score> {
+        self.0
+    }
+}
+
+impl Deref for FlowScores {
+    type Target = HashMap<InternedScoreLabel, Score>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PartialEq<HashMap<InternedScoreLabel, Score>> for FlowScores {
+    fn eq(&self, other: &HashMap<InternedScoreLabel, Score>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<FlowScores> for HashMap<InternedScoreLabel, Score> {
+    fn eq(&self, other: &FlowScores) -> bool {
+        *self == other.0
+    }
+}
+
+impl From<FlowScores> for HashMap<InternedScoreLabel, Score> {
+    fn from(scores: FlowScores) -> Self {
+        scores.0
+    }
+}
+
+impl IntoIterator for FlowScores {
+    type Item = (InternedScoreLabel, Score);
+    type IntoIter = <HashMap<InternedScoreLabel, Score> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FlowScores {
+    type Item = (&'a InternedScoreLabel, &'a Score);
+    type IntoIter = <&'a HashMap<InternedScoreLabel, Score> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// The scores of the labeled nodes that have run so far in the current run of
+/// a [`Flow`], as passed to [`Evaluator`]s in [`EvaluationCtx::scores`].
+/// Empty when an evaluator is run outside of a flow.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LabeledScores<'w> {
+    labeled: &'w [(InternedScoreLabel, usize)],
+    slots: &'w [Option<Score>],
+}
+
+impl LabeledScores<'_> {
+    /// Get the [`Score`] of the node with the given [`ScoreLabel`]. Returns
+    /// [`None`] if no node has the label, the node has not run yet, or its
+    /// score is absent.
+    pub fn get(&self, label: impl ScoreLabel) -> Option<Score> {
+        let label = label.intern();
+        self.labeled
+            .iter()
+            .find(|&&(l, _)| l == label)
+            .and_then(|&(_, slot)| *self.slots.get(slot)?)
+    }
+}
+
+/// Reusable buffer for the intermediate scores computed while running a
+/// [`Flow`]. See [`Flow::run_readonly_with_scratch`].
+#[derive(Default)]
+pub struct FlowScratch {
+    /// Holds the score of each node, indexed by [`PlanStep::slot`], or [`None`]
+    /// if the score is absent.
+    slots: Vec<Option<Score>>,
+    /// Whether the score of each aggregator step is already determined by one
+    /// of its children, indexed like [`FlowPlan::steps`]. Only used with
+    /// [`Flow::short_circuit`].
+    determined: Vec<bool>,
+}
+
+/// Precomputed steps for running a [`FlowGraph`] without any per-node
+/// hashing.
+#[derive(Default)]
+struct FlowPlan {
+    /// One step per node, in topological order.
+    steps: Vec<PlanStep>,
+    /// The labeled nodes and their score slots.
+    labeled: Vec<(InternedScoreLabel, usize)>,
+    /// The total number of score slots.
+    slot_count: usize,
+}
+
+/// A single node in a [`FlowPlan`].
+struct PlanStep {
+    node: NodeId,
+    /// The slot the node's score is written to.
+    slot: usize,
+    /// The slots of the node's child scores.
+    children: Range<usize>,
+    /// The index of the parent node's step, if any.
+    parent: Option<usize>,
+    /// The condition under which the node's score is determined by a single
+    /// child score, if it is an aggregator that declares one.
+    short_circuit: Option<ShortCircuit>,
+}
+
+/// Hook invoked around the evaluation of each node while a [`Flow`] runs.
+trait NodeObserver {
+    /// Observes the given node being scored by `score`, which is [`None`] if
+    /// the score is absent.
+    fn observe(&mut self, node: NodeId, score: impl FnOnce() -> Option<Score>) -> Option<Score>;
+
+    /// Observes the slot of the child score that dominated the aggregator node
+    /// that was just observed, as returned by `dominant`. See
+    /// [`Aggregator::explain`].
+    fn explain(&mut self, dominant: impl FnOnce() -> Option<usize>) {
+        let _ = dominant;
+    }
+}
+
+/// The default, no-op [`NodeObserver`].
+impl NodeObserver for () {
+    #[inline(always)]
+    fn observe(&mut self, _node: NodeId, score: impl FnOnce() -> Option<Score>) -> Option<Score> {
+        score()
+    }
+}
+
+/// Opaque, stable identifier of an evaluator or aggregator node within a
+/// [`Flow`]. Handles stay valid until their node is removed, so they can be
+/// used as map keys across runs of the same flow.
+///
+/// Handles are returned by [`FlowNodeView::handle`], [`FlowTrace`] and
+/// `FlowProfile`, and resolved back with [`FlowInspector::get`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct FlowNodeHandle(NodeId);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+enum NodeId {
+    /// Index into [`FlowGraph::evaluators`].
+    Evaluator(usize),
+    /// Index into [`FlowGraph::aggregators`].
+    Aggregator(usize),
+}
+
+/// [`World`] extension trait for working with [`Flow`]s.
+pub trait WorldFlowExt {
+    /// Adds a collection of nodes to the flow with the given label.
+    ///
+    /// If the flow does not exist, it will be created.
+    fn add_nodes<M>(
+        &mut self,
+        label: impl FlowLabel,
         nodes: impl IntoFlowNodeConfigs<M>,
     ) -> &mut Self;
 
@@ -308,7 +1552,7 @@ pub trait WorldFlowExt {
         &mut self,
         label: impl FlowLabel,
         target: Entity,
-    ) -> Result<HashMap<InternedScoreLabel, Score>, TryRunFlowError> {
+    ) -> Result<FlowScores, TryRunFlowError> {
         self.try_flow_scope(label, |world, flow| flow.run(world, target))
     }
 
@@ -319,14 +1563,45 @@ pub trait WorldFlowExt {
     ///
     /// If the flow does not exist.
     #[must_use]
-    fn run_flow(
-        &mut self,
-        label: impl FlowLabel,
-        target: Entity,
-    ) -> HashMap<InternedScoreLabel, Score> {
+    fn run_flow(&mut self, label: impl FlowLabel, target: Entity) -> FlowScores {
         self.flow_scope(label, |world, flow| flow.run(world, target))
     }
 
+    /// Runs the flow with the given label for each of the given targets,
+    /// returning the scores of all labeled nodes for each target in the same
+    /// order. See [`Flow::run_batch`].
+    ///
+    /// # Panics
+    ///
+    /// If the flow does not exist.
+    #[must_use]
+    fn run_flow_batch(&mut self, label: impl FlowLabel, targets: &[Entity]) -> Vec<FlowScores> {
+        self.flow_scope(label, |world, flow| flow.run_batch(world, targets))
+    }
+
+    /// Runs the flow with the given label for the given entity and writes the
+    /// resulting scores to its [`ComputedScores`], inserting the component if
+    /// it is missing. Unlike [`run_all_entity_flows`], this runs immediately
+    /// and regardless of whether the entity has an [`EntityFlow`].
+    ///
+    /// # Panics
+    ///
+    /// If the flow or the entity does not exist.
+    ///
+    /// [`ComputedScores`]: crate::component::ComputedScores
+    /// [`run_all_entity_flows`]: crate::component::run_all_entity_flows
+    /// [`EntityFlow`]: crate::component::EntityFlow
+    fn score_entity(&mut self, label: impl FlowLabel, entity: Entity) {
+        self.flow_scope(label, |world, flow| {
+            let scores = flow.run(world, entity);
+            world
+                .entity_mut(entity)
+                .entry::<ComputedScores>()
+                .or_default()
+                .replace(scores, None, flow.generation());
+        });
+    }
+
     /// Pulls the flow with the given label out of the [`Flows`] resource,
     /// provides it to the closure, and then re-inserts it into the resource.
     ///
@@ -357,6 +1632,10 @@ pub trait WorldFlowExt {
 
     /// Returns a reference to the flow with the given label.
     fn get_flow(&self, label: impl FlowLabel) -> Option<&Flow>;
+
+    /// Returns a mutable reference to the flow with the given label. The
+    /// [`Flows`] resource is only marked as changed if the flow exists.
+    fn get_flow_mut(&mut self, label: impl FlowLabel) -> Option<&mut Flow>;
 }
 
 impl WorldFlowExt for World {
@@ -375,16 +1654,24 @@ impl WorldFlowExt for World {
         label: impl FlowLabel,
         f: impl FnOnce(&mut World, &mut Flow) -> R,
     ) -> Result<R, TryRunFlowError> {
+        // Taking the flow out and putting it back does not change it, so the
+        // resource is not marked as changed. Changes to the flow itself are
+        // tracked by its generation.
         let label = label.intern();
-        let Some(mut flow) = self
-            .get_resource_mut::<Flows>()
-            .and_then(|mut flows| flows.remove(label))
-        else {
+        let Some(mut flow) = self.get_resource_mut::<Flows>().and_then(|mut flows| {
+            let flows = flows.bypass_change_detection();
+            let flow = flows.remove(label)?;
+            flows.scoped.push(label);
+            Some(flow)
+        }) else {
             return Err(TryRunFlowError(label));
         };
 
         let value = f(self, &mut flow);
-        let old = self.resource_mut::<Flows>().insert(flow);
+        let mut flows = self.resource_mut::<Flows>();
+        let flows = flows.bypass_change_detection();
+        flows.scoped.retain(|&scoped| scoped != label);
+        let old = flows.insert(flow);
         if old.is_some() {
             warn!("Flow `{label:?} was inserted during a call to `World::try_flow_scope`: its value has been overwritten");
         }
@@ -396,9 +1683,1384 @@ impl WorldFlowExt for World {
         self.get_resource::<Flows>()
             .and_then(|flows| flows.inner.get(&label.intern()))
     }
+
+    fn get_flow_mut(&mut self, label: impl FlowLabel) -> Option<&mut Flow> {
+        let label = label.intern();
+        let flows = self.get_resource_mut::<Flows>()?;
+        if !flows.contains(label) {
+            return None;
+        }
+        flows.into_inner().get_mut(label)
+    }
+}
+
+/// Error type returned when the dependencies between the nodes of a [`Flow`]
+/// form a cycle.
+#[derive(Error, Debug)]
+#[error("The flow contains a dependency cycle between the nodes: {}.", .nodes.join(", "))]
+pub struct FlowCycleError {
+    /// The names of the nodes involved in the cycle.
+    pub nodes: Vec<Cow<'static, str>>,
+}
+
+/// Error type returned when a dependency between the nodes of a [`Flow`]
+/// cannot be added.
+#[derive(Error, Debug)]
+pub enum FlowDependencyError {
+    /// No node in the flow has the given label. A node can only depend on
+    /// labeled nodes that were added before it.
+    #[error("The flow has no node labeled {0:?}.")]
+    UnknownLabel(InternedScoreLabel),
+    /// The dependency would introduce a cycle.
+    #[error(transparent)]
+    Cycle(#[from] FlowCycleError),
+}
+
+/// A likely construction mistake in a [`Flow`], as found by
+/// [`Flow::validate`].
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum FlowWarning {
+    /// An aggregator node has no children, so it always aggregates an empty
+    /// list of scores.
+    ChildlessAggregator {
+        /// The handle of the aggregator node.
+        handle: FlowNodeHandle,
+        /// The name of the aggregator.
+        name: Cow<'static, str>,
+    },
+    /// An aggregator node has a number of children outside of its
+    /// [`Aggregator::required_arity`].
+    ArityMismatch {
+        /// The handle of the aggregator node.
+        handle: FlowNodeHandle,
+        /// The name of the aggregator.
+        name: Cow<'static, str>,
+        /// The number of children of the aggregator node.
+        children: usize,
+        /// The range of child counts the aggregator requires.
+        required: RangeInclusive<usize>,
+    },
+}
+
+impl fmt::Display for FlowWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowWarning::ChildlessAggregator { name, .. } => {
+                write!(f, "Aggregator {name} has no children")
+            }
+            FlowWarning::ArityMismatch {
+                name,
+                children,
+                required,
+                ..
+            } if required.start() == required.end() => write!(
+                f,
+                "Aggregator {name} has {children} children but requires exactly {}",
+                required.start()
+            ),
+            FlowWarning::ArityMismatch {
+                name,
+                children,
+                required,
+                ..
+            } => write!(
+                f,
+                "Aggregator {name} has {children} children but requires between {} and {}",
+                required.start(),
+                required.end()
+            ),
+        }
+    }
+}
+
+/// Error type returned by [`Flow::add_nodes_checked`] when a [`ScoreLabel`] is
+/// used by more than one node.
+#[derive(Error, Debug)]
+#[error("The label {label:?} is already associated with {existing}, so it cannot be used for {duplicate}.")]
+pub struct DuplicateLabelError {
+    /// The label used more than once.
+    pub label: InternedScoreLabel,
+    /// The name of the node the label is already associated with.
+    pub existing: Cow<'static, str>,
+    /// The name of the node the label could not be associated with.
+    pub duplicate: Cow<'static, str>,
 }
 
 /// Error type returned when trying to run a flow that does not exist.
 #[derive(Error, Debug)]
 #[error("The flow with the label {0:?} was not found.")]
 pub struct TryRunFlowError(pub InternedFlowLabel);
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::Cow, format, sync::Arc, vec::Vec};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use bevy::time::Time;
+    use bevy_ecs::{
+        change_detection::{DetectChanges, MAX_CHANGE_AGE},
+        component::Tick,
+        system::{Res, Resource},
+        world::World,
+    };
+    use bevy_hierarchy::BuildChildren;
+    use bevy_utils::HashMap;
+
+    use crate::{
+        self as evergreen_utility_ai,
+        aggregator::{
+            average, difference, maximum, product, ratio, sum, Aggregation, IntoAggregator,
+        },
+        evaluator::{
+            constant, parent, resource, score_ref, subflow, target, EvaluationCtx, Evaluator,
+            IntoEvaluator,
+        },
+        flow::{
+            Flow, FlowDependencyError, FlowNodeConfig, FlowNodeConfigs, FlowNodeHandle,
+            FlowScratch, FlowWarning, Flows, IntoFlowNodeConfig, WorldFlowExt,
+        },
+        label::{FlowLabel, ScoreLabel},
+        macros::{FlowLabel, ScoreLabel},
+        score::Score,
+        tests::{
+            Fuel, FuelScore, GameTime, Health, HealthScore, TestFlow, TimeScore, TotalHealthScore,
+        },
+    };
+
+    #[test]
+    fn remove_label() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                sum()
+                    .with_children((constant(0.25), target::<Health>().label(HealthScore)))
+                    .label(TotalHealthScore),
+                parent::<Fuel>().label(FuelScore),
+            ),
+        );
+        let npc = world.spawn(Health(50)).id();
+        world.spawn(Fuel(50)).add_child(npc);
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 3);
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.75);
+
+        // Removing a child leaves its parent in place.
+        world.flow_scope(TestFlow, |_, flow| {
+            assert!(flow.remove_label(HealthScore));
+            assert!(!flow.remove_label(HealthScore));
+        });
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 2);
+        assert!(!scores.contains_key(&HealthScore.intern()));
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.25);
+
+        // Removing a parent removes its whole subtree.
+        world.flow_scope(TestFlow, |_, flow| {
+            assert!(flow.remove_label(TotalHealthScore));
+        });
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 1);
+        assert!(scores.contains_key(&FuelScore.intern()));
+    }
+
+    #[test]
+    fn clear_and_rebuild() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                target::<Health>().label(HealthScore),
+                parent::<Fuel>().label(FuelScore),
+            ),
+        );
+        let npc = world.spawn(Health(50)).id();
+        world.spawn(Fuel(50)).add_child(npc);
+        assert_eq!(world.run_flow(TestFlow, npc).len(), 2);
+
+        world.flow_scope(TestFlow, |world, flow| {
+            flow.clear();
+            assert!(flow.is_initialized());
+            assert!(flow.run_readonly(world, npc).is_empty());
+
+            flow.add_nodes(constant(0.25).label(TotalHealthScore));
+            assert!(!flow.is_initialized());
+            flow.initialize(world);
+            assert!(flow.is_initialized());
+        });
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.25);
+    }
+
+    #[test]
+    fn dependency_cycle_error() {
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes(
+            sum()
+                .with_children(target::<Health>())
+                .label(TotalHealthScore),
+        );
+        let toposort = flow.graph.dependency_toposort.clone();
+
+        // Flow configs cannot express back-edges, so add one by hand.
+        let [child, parent] = toposort[..] else {
+            panic!("expected two nodes, got {toposort:?}");
+        };
+        flow.graph.dependency.add_edge(parent, child, ());
+
+        let err = flow.graph.update_toposort().unwrap_err();
+        assert_eq!(err.nodes.len(), 2);
+        assert!(err.nodes.contains(&"sum".into()));
+        assert!(err.nodes.iter().any(|name| name.starts_with("target(")));
+        assert_eq!(flow.graph.dependency_toposort, toposort);
+    }
+
+    #[test]
+    fn run_readonly_into_matches_run_readonly() {
+        let mut world = World::new();
+        world.insert_resource(Time::<GameTime>::default());
+        world.add_nodes(
+            TestFlow,
+            (
+                sum()
+                    .with_children((constant(0.25), target::<Health>().label(HealthScore)))
+                    .label(TotalHealthScore),
+                resource::<Time<GameTime>>().label(TimeScore),
+            ),
+        );
+        let npc = world.spawn(Health(50)).id();
+        world.flow_scope(TestFlow, |world, flow| flow.initialize(world));
+
+        let flow = world.get_flow(TestFlow).unwrap();
+        let expected = flow.run_readonly(&world, npc);
+
+        // Stale entries from a previous run must be cleared.
+        let mut out = HashMap::default();
+        out.insert(FuelScore.intern(), 1.0.into());
+        flow.run_readonly_into(&world, npc, &mut out);
+        assert_eq!(out, expected);
+
+        flow.run_readonly_into(&world, npc, &mut out);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn scratch_reused_across_flows() {
+        let mut world = World::new();
+        let mut deep = Flow::new(TestFlow);
+        deep.add_nodes(
+            sum()
+                .with_children((
+                    constant(0.25),
+                    sum().with_children((constant(0.125), target::<Health>())),
+                ))
+                .label(TotalHealthScore),
+        );
+        deep.initialize(&mut world);
+        let mut flat = Flow::new(TestFlow);
+        flat.add_nodes(sum().with_children(constant(0.5)).label(HealthScore));
+        flat.initialize(&mut world);
+        let npc = world.spawn(Health(50)).id();
+
+        let mut out = HashMap::default();
+        let mut scratch = FlowScratch::default();
+        for _ in 0..2 {
+            deep.run_readonly_with_scratch(&world, npc, &mut out, &mut scratch);
+            assert_eq!(out, deep.run_readonly(&world, npc));
+            assert_eq!(out[&TotalHealthScore.intern()], 0.875);
+
+            flat.run_readonly_with_scratch(&world, npc, &mut out, &mut scratch);
+            assert_eq!(out, flat.run_readonly(&world, npc));
+            assert_eq!(out[&HealthScore.intern()], 0.5);
+        }
+    }
+
+    #[test]
+    fn flow_scores() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.25).label(HealthScore),
+                target::<Fuel>().label(FuelScore),
+            ),
+        );
+        let npc = world.spawn_empty().id();
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.score(HealthScore), Some(Score::new(0.25)));
+        // Absent scores are left out.
+        assert_eq!(scores.score(FuelScore), None);
+        assert_eq!(scores.len(), 1);
+        assert!(!scores.is_empty());
+        // The underlying map is still reachable.
+        assert_eq!(scores[&HealthScore.intern()], 0.25);
+        assert_eq!(scores.into_inner().len(), 1);
+    }
+
+    #[test]
+    fn flow_builder() {
+        fn health_flow() -> Flow {
+            Flow::builder(TestFlow)
+                .add(target::<Health>().label(HealthScore))
+                .add((
+                    constant(0.25).label(FuelScore),
+                    sum()
+                        .with_children((constant(0.125), target::<Health>()))
+                        .label(TotalHealthScore),
+                ))
+                .short_circuit(true)
+                .build()
+        }
+
+        let flow = health_flow();
+        assert!(flow.short_circuits());
+        assert!(!flow.skips_unchanged());
+
+        let mut world = World::new();
+        world.init_resource::<Flows>();
+        world.resource_mut::<Flows>().insert(flow);
+        let npc = world.spawn(Health(50)).id();
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.score(HealthScore), Some(Score::new(0.5)));
+        assert_eq!(scores.score(FuelScore), Some(Score::new(0.25)));
+        assert_eq!(scores.score(TotalHealthScore), Some(Score::new(0.625)));
+    }
+
+    #[test]
+    fn merge_flows() {
+        let mut base = Flow::new(TestFlow);
+        base.add_nodes(target::<Health>().label(HealthScore));
+
+        let mut extension = Flow::new(TestFlow);
+        extension.add_nodes((
+            target::<Fuel>().label(FuelScore),
+            product()
+                .with_children((constant(0.5), target::<Health>(), constant(1.0)))
+                .label(TotalHealthScore),
+        ));
+
+        let mut world = World::new();
+        let npc = world.spawn((Health(50), Fuel(30))).id();
+
+        base.run(&mut world, npc);
+        base.merge(extension).unwrap();
+        let scores = base.run(&mut world, npc);
+        assert_eq!(scores.score(HealthScore), Some(Score::new(0.5)));
+        assert_eq!(scores.score(FuelScore), Some(Score::new(0.3)));
+        assert_eq!(scores.score(TotalHealthScore), Some(Score::new(0.25)));
+
+        // Colliding labels are rejected without merging anything.
+        let mut extension = Flow::new(TestFlow);
+        extension.add_nodes((
+            constant(0.75).label(TimeScore),
+            constant(0.25).label(FuelScore),
+        ));
+        let error = base.merge(extension).unwrap_err();
+        assert_eq!(error.label, FuelScore.intern());
+        assert_eq!(error.duplicate, "constant(0.2500)");
+        assert!(!base.has_label(TimeScore));
+    }
+
+    #[test]
+    fn score_ref_shares_intermediate_score() {
+        #[derive(ScoreLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        struct SharedScore;
+
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            average()
+                .with_children((target::<Health>(), target::<Fuel>()))
+                .label(SharedScore),
+            product()
+                .with_children((score_ref(SharedScore), constant(0.5)))
+                .label(HealthScore),
+            sum()
+                .with_children((score_ref(SharedScore), constant(0.25)))
+                .label(FuelScore),
+        ));
+
+        let mut world = World::new();
+        let npc = world.spawn((Health(50), Fuel(30))).id();
+        let scores = flow.run(&mut world, npc);
+        assert_eq!(scores.score(SharedScore), Some(Score::new(0.4)));
+        assert_eq!(scores.score(HealthScore), Some(Score::new(0.2)));
+        assert_eq!(scores.score(FuelScore), Some(Score::new(0.65)));
+        assert_eq!(
+            flow.run_label(&world, npc, FuelScore),
+            Some(Score::new(0.65))
+        );
+
+        // Forward references are not added.
+        flow.add_nodes(score_ref(TimeScore).label(TotalHealthScore));
+        assert!(!flow.has_label(TotalHealthScore));
+        assert!(matches!(
+            flow.depends_on(HealthScore, TimeScore),
+            Err(FlowDependencyError::UnknownLabel(label)) if label == TimeScore.intern()
+        ));
+
+        // Neither are references to an ancestor or orderings that form a cycle.
+        flow.add_nodes(
+            maximum()
+                .with_children((score_ref(TotalHealthScore), constant(0.75)))
+                .label(TotalHealthScore),
+        );
+        assert_eq!(
+            flow.run(&mut world, npc).score(TotalHealthScore),
+            Some(Score::new(0.75))
+        );
+        assert!(matches!(
+            flow.depends_on(SharedScore, FuelScore),
+            Err(FlowDependencyError::Cycle(_))
+        ));
+        flow.depends_on(FuelScore, HealthScore).unwrap();
+        assert_eq!(
+            flow.run(&mut world, npc).score(FuelScore),
+            Some(Score::new(0.65))
+        );
+    }
+
+    #[test]
+    fn child_counts() {
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            sum()
+                .with_children((
+                    (0..30)
+                        .map(|_| FlowNodeConfig::evaluator(constant(1.0 / 128.0)))
+                        .collect::<FlowNodeConfigs>(),
+                    sum().with_children((constant(0.125), target::<Health>().label(HealthScore))),
+                ))
+                .label(TotalHealthScore),
+            target::<Health>().label(FuelScore),
+        ));
+        flow.initialize(&mut world);
+        let npc = world.spawn(Health(50)).id();
+
+        let root = flow.graph.labels[&TotalHealthScore.intern()];
+        let inner = flow
+            .graph
+            .dependency
+            .neighbors(flow.graph.labels[&HealthScore.intern()])
+            .next()
+            .unwrap();
+        assert_eq!(flow.graph.child_count(root), 31);
+        assert_eq!(flow.graph.child_count(inner), 2);
+
+        let scores = flow.run_readonly(&world, npc);
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.859375);
+        assert_eq!(scores[&HealthScore.intern()], 0.5);
+
+        assert!(flow.remove_label(HealthScore));
+        assert_eq!(flow.graph.child_count(root), 31);
+        assert_eq!(flow.graph.child_count(inner), 1);
+        let scores = flow.run_readonly(&world, npc);
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.359375);
+    }
+
+    #[test]
+    fn difference_child_order() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.75).difference(constant(0.25)).label(HealthScore),
+                constant(0.25).difference(constant(0.75)).label(FuelScore),
+                constant(0.25)
+                    .abs_difference(constant(0.75))
+                    .label(TotalHealthScore),
+            ),
+        );
+        let npc = world.spawn_empty().id();
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores[&HealthScore.intern()], 0.5);
+        assert_eq!(scores[&FuelScore.intern()], Score::MIN);
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.5);
+    }
+
+    #[test]
+    fn child_order_survives_removal() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            difference()
+                .with_children((
+                    constant(0.1).label(TimeScore),
+                    constant(0.75),
+                    constant(0.25),
+                ))
+                .label(HealthScore),
+        );
+        let npc = world.spawn_empty().id();
+        world.flow_scope(TestFlow, |_, flow| {
+            assert!(flow.remove_label(TimeScore));
+        });
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores[&HealthScore.intern()], 0.5);
+
+        let flow = world.get_flow(TestFlow).unwrap();
+        let inspector = flow.inspect();
+        let children = inspector
+            .node(HealthScore)
+            .unwrap()
+            .children()
+            .map(|child| child.name())
+            .collect::<Vec<_>>();
+        assert_eq!(children, [constant(0.75).name(), constant(0.25).name()]);
+    }
+
+    #[test]
+    fn root_order_survives_removal() {
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            constant(0.1).label(TimeScore),
+            constant(0.2).label(HealthScore),
+            constant(0.3).label(FuelScore),
+            constant(0.4).label(TotalHealthScore),
+        ));
+        assert!(flow.remove_label(TimeScore));
+
+        let roots = flow
+            .inspect()
+            .roots()
+            .map(|root| root.label())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            roots,
+            [
+                Some(HealthScore.intern()),
+                Some(FuelScore.intern()),
+                Some(TotalHealthScore.intern()),
+            ]
+        );
+    }
+
+    #[test]
+    fn difference_missing_children() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                target::<Health>()
+                    .difference(constant(0.25))
+                    .label(HealthScore),
+                target::<Health>()
+                    .difference(target::<Fuel>())
+                    .label(FuelScore),
+            ),
+        );
+        let npc = world.spawn_empty().id();
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores[&HealthScore.intern()], Score::MIN);
+        assert_eq!(scores[&FuelScore.intern()], Score::MIN);
+    }
+
+    #[test]
+    fn ratio_builder() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            target::<Health>()
+                .ratio(parent::<Health>())
+                .label(HealthScore),
+        );
+        let enemy = world.spawn(Health(80)).id();
+        let npc = world.spawn(Health(20)).set_parent(enemy).id();
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores[&HealthScore.intern()], 0.25);
+    }
+
+    #[test]
+    fn childless_aggregator() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            sum()
+                .with_children(target::<Health>().label(HealthScore))
+                .label(TotalHealthScore),
+        );
+        let npc = world.spawn(Health(50)).id();
+        assert_eq!(
+            world.run_flow(TestFlow, npc)[&TotalHealthScore.intern()],
+            0.5
+        );
+
+        world.flow_scope(TestFlow, |_, flow| {
+            assert!(flow.remove_label(HealthScore));
+        });
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[&TotalHealthScore.intern()], Score::MIN);
+    }
+
+    #[test]
+    fn traced_run() {
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes(
+            sum()
+                .with_children((
+                    constant(0.25),
+                    sum().with_children((constant(0.125), target::<Health>())),
+                ))
+                .label(TotalHealthScore),
+        );
+        flow.initialize(&mut world);
+        let npc = world.spawn(Health(50)).id();
+
+        let trace = flow.run_traced(&world, npc);
+        assert_eq!(trace.nodes.len(), 5);
+        assert_eq!(trace.labeled(TotalHealthScore), Some(0.875.into()));
+
+        // The unlabeled inner aggregator is traced too.
+        let inner = trace
+            .iter()
+            .find(|node| node.name == "sum" && node.label.is_none())
+            .unwrap();
+        assert_eq!(inner.score, Some(0.625.into()));
+        assert_eq!(trace.score(inner.handle), inner.score);
+
+        let health = trace
+            .iter()
+            .find(|node| node.name.starts_with("target("))
+            .unwrap();
+        assert_eq!(health.score, Some(0.5.into()));
+    }
+
+    #[test]
+    fn node_handles_across_runs() {
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes(
+            sum()
+                .with_children((constant(0.25), target::<Health>().label(HealthScore)))
+                .label(TotalHealthScore),
+        );
+        flow.initialize(&mut world);
+        let npc = world.spawn(Health(50)).id();
+
+        let first = flow
+            .run_traced(&world, npc)
+            .iter()
+            .map(|node| (node.handle, node.score))
+            .collect::<HashMap<_, _>>();
+        world.get_mut::<Health>(npc).unwrap().0 = 20;
+        let second = flow.run_traced(&world, npc);
+        assert_eq!(first.len(), second.nodes.len());
+
+        let health = flow.inspect().node(HealthScore).unwrap().handle();
+        assert_eq!(first[&health], Some(0.5.into()));
+        assert_eq!(second.score(health), Some(0.2.into()));
+        assert_eq!(
+            flow.inspect().get(health).unwrap().label(),
+            Some(HealthScore.intern())
+        );
+
+        assert!(flow.remove_label(HealthScore));
+        assert!(flow.inspect().get(health).is_none());
+    }
+
+    #[test]
+    fn explained_run() {
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            maximum()
+                .with_children((
+                    constant(0.25),
+                    target::<Health>().label(HealthScore),
+                    parent::<Fuel>(),
+                    constant(0.5),
+                ))
+                .label(TotalHealthScore),
+            sum().with_children(constant(0.5)).label(FuelScore),
+        ));
+        flow.initialize(&mut world);
+        let npc = world.spawn(Health(80)).id();
+
+        let trace = flow.run_traced(&world, npc);
+        let explain = |label| {
+            let node = trace.iter().find(|node| node.label == Some(label)).unwrap();
+            node.dominant.and_then(|handle| trace.get(handle)?.label)
+        };
+        // The highest child dominates, skipping the absent fuel score.
+        assert_eq!(
+            explain(TotalHealthScore.intern()),
+            Some(HealthScore.intern())
+        );
+        // Evaluators and aggregators without an explanation have no dominant
+        // child.
+        assert_eq!(explain(HealthScore.intern()), None);
+        assert!(trace
+            .iter()
+            .find(|node| node.label == Some(FuelScore.intern()))
+            .unwrap()
+            .dominant
+            .is_none());
+    }
+
+    #[test]
+    fn absent_scores_skipped() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            average()
+                .with_children((constant(0.4), target::<Health>().label(HealthScore)))
+                .label(TotalHealthScore),
+        );
+        let npc = world.spawn_empty().id();
+
+        // The absent health score is neither aggregated nor returned.
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.4);
+
+        world.entity_mut(npc).insert(Health(80));
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[&TotalHealthScore.intern()], 0.6);
+    }
+
+    #[test]
+    fn flow_access() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                sum()
+                    .with_children((constant(0.25), target::<Health>()))
+                    .label(TotalHealthScore),
+                resource::<Time<GameTime>>().label(TimeScore),
+            ),
+        );
+
+        world.flow_scope(TestFlow, |world, flow| {
+            flow.initialize(world);
+            let access = flow.access();
+            assert!(!access.reads_all());
+            assert!(access.has_component_read(world.component_id::<Health>().unwrap()));
+            assert!(access.has_resource_read(world.resource_id::<Time<GameTime>>().unwrap()));
+
+            // Nodes with unknown access make the whole flow read everything.
+            flow.add_nodes(sum().score_children::<Health>().label(HealthScore));
+            flow.initialize(world);
+            assert!(flow.access().reads_all());
+        });
+    }
+
+    #[test]
+    fn batched_run() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            sum()
+                .with_children((constant(0.25), target::<Health>().label(HealthScore)))
+                .label(TotalHealthScore),
+        );
+        let targets = [
+            world.spawn(Health(50)).id(),
+            world.spawn_empty().id(),
+            world.spawn(Health(20)).id(),
+        ];
+
+        let batch = world.run_flow_batch(TestFlow, &targets);
+        assert_eq!(batch.len(), targets.len());
+        for (scores, &target) in batch.iter().zip(&targets) {
+            assert_eq!(*scores, world.run_flow(TestFlow, target));
+        }
+        assert_eq!(batch[2][&TotalHealthScore.intern()], 0.45);
+    }
+
+    #[test]
+    fn dot_export() {
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            sum()
+                .threshold(0.2)
+                .with_children((
+                    sum().threshold(0.2).score_children::<Health>(),
+                    target::<Health>().label(HealthScore),
+                ))
+                .label(TotalHealthScore),
+            parent::<Fuel>().weight(0.25).label(FuelScore),
+            resource::<Time<GameTime>>().label(TimeScore),
+        ));
+
+        let dot = flow.to_dot();
+        assert!(dot.starts_with("digraph \"TestFlow\" {"));
+        assert!(dot.contains(
+            "a0 [label=\"sum.threshold(0.2000)\\n(aggregator)\\n[TotalHealthScore]\", shape=box];"
+        ));
+        assert!(dot.contains("sum.threshold(0.2000).score_children("));
+        assert!(dot.contains("\\n[HealthScore]"));
+        assert!(dot.contains("\\n[FuelScore]"));
+        assert!(dot.contains("\\n[TimeScore]"));
+        assert_eq!(dot.matches("shape=").count(), 5);
+        assert_eq!(dot.matches(" -> a0;").count(), 2);
+        assert_eq!(dot.matches(" -> ").count(), 2);
+    }
+
+    #[test]
+    fn check_system_aggregator_change_ticks() {
+        #[derive(Resource)]
+        struct Alarm;
+
+        fn alarm_changed(_: Aggregation, alarm: Res<Alarm>) -> Score {
+            if alarm.is_changed() {
+                Score::MAX
+            } else {
+                Score::MIN
+            }
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Alarm);
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes(
+            alarm_changed
+                .weight(1.)
+                .with_children(constant(0.5))
+                .label(HealthScore),
+        );
+        let npc = world.spawn_empty().id();
+
+        assert_eq!(
+            flow.run(&mut world, npc).score(HealthScore),
+            Some(Score::MAX)
+        );
+        world.increment_change_tick();
+        assert_eq!(
+            flow.run(&mut world, npc).score(HealthScore),
+            Some(Score::MIN)
+        );
+
+        // Checking against a change tick far ahead of the last run clamps the
+        // system's last run tick forward, so the alarm is now older than it.
+        let change_tick = Tick::new(world.change_tick().get() + MAX_CHANGE_AGE + 1000);
+        flow.check_change_ticks(change_tick);
+        assert_eq!(
+            flow.run(&mut world, npc).score(HealthScore),
+            Some(Score::MAX)
+        );
+    }
+
+    #[test]
+    fn format_tree() {
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            sum()
+                .threshold(0.2)
+                .with_children((
+                    sum()
+                        .invert()
+                        .with_children((constant(0.25), constant(0.5))),
+                    target::<Health>().label(HealthScore),
+                ))
+                .label(TotalHealthScore),
+            parent::<Fuel>().weight(0.25).label(FuelScore),
+            resource::<Time<GameTime>>().label(TimeScore),
+        ));
+
+        let tree = flow.format_tree();
+        assert_eq!(
+            tree.lines().collect::<Vec<_>>(),
+            [
+                "TestFlow",
+                "|-- sum.threshold(0.2000) [TotalHealthScore]",
+                "|   |-- sum.invert()",
+                "|   |   |-- constant(0.2500)",
+                "|   |   `-- constant(0.5000)",
+                "|   `-- target(evergreen_utility_ai::tests::Health) [HealthScore]",
+                "|-- parent(evergreen_utility_ai::tests::Fuel).weight(0.2500) [FuelScore]",
+                "`-- resource(bevy_time::time::Time<evergreen_utility_ai::tests::GameTime>) [TimeScore]",
+            ]
+        );
+    }
+
+    #[test]
+    fn inspect_flow() {
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            sum()
+                .threshold(0.2)
+                .with_children((
+                    sum().threshold(0.2).score_children::<Health>(),
+                    target::<Health>().label(HealthScore),
+                ))
+                .label(TotalHealthScore),
+            parent::<Fuel>().weight(0.25).label(FuelScore),
+            resource::<Time<GameTime>>().label(TimeScore),
+            constant(0.5),
+        ));
+
+        let inspector = flow.inspect();
+        assert_eq!(inspector.node_count(), 5);
+        assert_eq!(inspector.labeled_nodes().count(), 4);
+        assert_eq!(inspector.roots().count(), 3);
+
+        let total = inspector.node(TotalHealthScore).unwrap();
+        assert_eq!(total.kind(), "aggregator");
+        assert_eq!(total.name(), "sum.threshold(0.2000)");
+        assert!(total.parent().is_none());
+
+        let children = total.children().collect::<Vec<_>>();
+        assert_eq!(children.len(), 2);
+        assert!(children[0]
+            .name()
+            .starts_with("sum.threshold(0.2000).score_children("));
+        assert_eq!(children[1].label(), Some(HealthScore.intern()));
+        assert_eq!(
+            children[1].parent().and_then(|p| p.label()),
+            Some(TotalHealthScore.intern())
+        );
+
+        let (_, name) = inspector
+            .labeled_nodes()
+            .find(|&(label, _)| label == FuelScore.intern())
+            .unwrap();
+        assert!(name.starts_with("parent("));
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn profiled_run() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                sum()
+                    .with_children((
+                        sum().score_children::<Health>(),
+                        target::<Health>().label(HealthScore),
+                    ))
+                    .label(TotalHealthScore),
+                parent::<Fuel>().label(FuelScore),
+            ),
+        );
+        let npc = world.spawn(Health(50)).id();
+        world.spawn(Fuel(50)).add_child(npc);
+
+        let (scores, profile) = world.flow_scope(TestFlow, |world, flow| {
+            flow.initialize(world);
+            flow.run_profiled(world, npc)
+        });
+        assert_eq!(scores.len(), 3);
+
+        let [total, fuel] = &profile.roots[..] else {
+            panic!("expected two root nodes, got {:?}", profile.roots);
+        };
+        assert_eq!(total.name, "sum");
+        assert_eq!(total.kind, "aggregator");
+        assert_eq!(total.label, Some(TotalHealthScore.intern()));
+        assert_eq!(total.children.len(), 2);
+        assert!(total.children[0].name.starts_with("sum.score_children("));
+        assert_eq!(total.children[1].label, Some(HealthScore.intern()));
+        assert!(total.children.iter().all(|c| c.children.is_empty()));
+        assert!(total.total_time() >= total.self_time);
+
+        assert_eq!(fuel.kind, "evaluator");
+        assert!(fuel.children.is_empty());
+
+        let folded = profile.to_folded();
+        assert_eq!(folded.lines().count(), 4);
+        assert!(folded
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("sum;sum.score_children("));
+    }
+
+    #[test]
+    fn subflow_evaluator() {
+        #[derive(FlowLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        struct ThreatFlow;
+
+        #[derive(FlowLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        struct MissingFlow;
+
+        #[derive(ScoreLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        struct ThreatScore;
+
+        let mut world = World::new();
+        world.add_nodes(
+            ThreatFlow,
+            average()
+                .with_children((target::<Health>(), parent::<Fuel>()))
+                .label(ThreatScore),
+        );
+        world.add_nodes(
+            TestFlow,
+            (
+                sum()
+                    .with_children((constant(0.25), subflow(ThreatFlow, ThreatScore)))
+                    .label(TotalHealthScore),
+                subflow(ThreatFlow, HealthScore).label(HealthScore),
+                subflow(MissingFlow, ThreatScore).label(TimeScore),
+            ),
+        );
+
+        let npc = world.spawn(Health(40)).id();
+        world.spawn(Fuel(60)).add_child(npc);
+
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores[&TotalHealthScore.intern()], Score::new(0.75));
+        // The scores of missing labels and flows are absent.
+        assert!(!scores.contains_key(&HealthScore.intern()));
+        assert!(!scores.contains_key(&TimeScore.intern()));
+
+        // The embedded flow was initialized with the outer flow, so both can
+        // be run without exclusive world access.
+        let scores = world.get_flow(TestFlow).unwrap().run_readonly(&world, npc);
+        assert_eq!(scores[&TotalHealthScore.intern()], Score::new(0.75));
+    }
+
+    #[test]
+    fn recursive_subflows_are_refused() {
+        #[derive(FlowLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        struct OtherFlow;
+
+        let mut world = World::new();
+        let npc = world.spawn_empty().id();
+
+        // The other flow is initialized before the flow it embeds exists.
+        world.add_nodes(
+            OtherFlow,
+            (
+                constant(0.5).label(HealthScore),
+                subflow(TestFlow, HealthScore).label(TotalHealthScore),
+            ),
+        );
+        let scores = world.run_flow(OtherFlow, npc);
+        assert_eq!(scores[&HealthScore.intern()], Score::new(0.5));
+        assert!(!scores.contains_key(&TotalHealthScore.intern()));
+
+        world.add_nodes(
+            TestFlow,
+            (
+                subflow(OtherFlow, HealthScore).label(HealthScore),
+                subflow(TestFlow, HealthScore).label(FuelScore),
+            ),
+        );
+        // Both subflows would lead back to the flow itself, so they are never
+        // run rather than deadlocking.
+        let scores = world.run_flow(TestFlow, npc);
+        assert!(!scores.contains_key(&HealthScore.intern()));
+        assert!(!scores.contains_key(&FuelScore.intern()));
+
+        let scores = world.get_flow(OtherFlow).unwrap().run_readonly(&world, npc);
+        assert_eq!(scores[&HealthScore.intern()], Score::new(0.5));
+        assert!(!scores.contains_key(&TotalHealthScore.intern()));
+    }
+
+    #[test]
+    fn iter_flows() {
+        #[derive(FlowLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        struct OtherFlow;
+
+        let mut flows = Flows::default();
+        flows.add_nodes(TestFlow, constant(0.5).label(HealthScore));
+        flows.entry(OtherFlow);
+
+        assert!(flows.contains(TestFlow));
+        assert!(flows.contains(OtherFlow));
+        flows.remove(OtherFlow);
+        assert!(!flows.contains(OtherFlow));
+        flows.insert(Flow::new(OtherFlow));
+
+        let mut labels = flows.labels().collect::<Vec<_>>();
+        labels.sort_by_key(|label| format!("{label:?}"));
+        assert_eq!(labels, [OtherFlow.intern(), TestFlow.intern()]);
+        let mut iter = flows.iter().map(|(label, _)| label).collect::<Vec<_>>();
+        iter.sort_by_key(|label| format!("{label:?}"));
+        assert_eq!(iter, labels);
+    }
+
+    #[test]
+    fn get_flow_mut() {
+        let mut world = World::new();
+        assert!(world.get_flow_mut(TestFlow).is_none());
+
+        world.add_nodes(TestFlow, constant(0.5).label(HealthScore));
+        let flow = world.get_flow_mut(TestFlow).unwrap();
+        flow.add_nodes(target::<Health>().label(TotalHealthScore));
+        assert!(flow.remove_label(HealthScore));
+
+        let npc = world.spawn(Health(40)).id();
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[&TotalHealthScore.intern()], Score::new(0.4));
+    }
+
+    #[test]
+    fn add_children_incrementally() {
+        let mut world = World::new();
+
+        let mut total = sum().with_children(core::iter::empty().collect::<FlowNodeConfigs>());
+        for _ in 0..4 {
+            total = total.add_child(constant(0.125));
+        }
+        total = total.add_children((constant(0.25), target::<Health>().label(HealthScore)));
+        // Evaluators cannot have children, so these are discarded.
+        let fuel = FlowNodeConfig::evaluator(target::<Fuel>())
+            .add_child(constant(1.))
+            .label(FuelScore);
+        world.add_nodes(TestFlow, (total.label(TotalHealthScore), fuel));
+
+        let npc = world.spawn((Health(10), Fuel(40))).id();
+        let scores = world.run_flow(TestFlow, npc);
+        assert_eq!(scores[&HealthScore.intern()], Score::new(0.1));
+        assert_eq!(scores[&TotalHealthScore.intern()], Score::new(0.85));
+        assert_eq!(scores[&FuelScore.intern()], Score::new(0.4));
+        assert_eq!(world.get_flow(TestFlow).unwrap().inspect().node_count(), 8);
+    }
+
+    #[test]
+    fn add_nodes_checked() {
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes_checked(target::<Health>().label(HealthScore))
+            .unwrap();
+
+        let error = flow
+            .add_nodes_checked((
+                constant(0.5).label(FuelScore),
+                sum()
+                    .with_children(constant(0.25).label(HealthScore))
+                    .label(TotalHealthScore),
+            ))
+            .unwrap_err();
+        assert_eq!(error.label, HealthScore.intern());
+        assert!(error.existing.starts_with("target("));
+        assert_eq!(error.duplicate, "constant(0.2500)");
+        // Nothing is added when a label is duplicated.
+        assert!(!flow.has_label(FuelScore));
+        assert!(!flow.has_label(TotalHealthScore));
+
+        // Labels are also checked against the other new nodes.
+        let error = flow
+            .add_nodes_checked((
+                constant(0.5).label(FuelScore),
+                constant(0.75).label(FuelScore),
+            ))
+            .unwrap_err();
+        assert_eq!(error.existing, "constant(0.5000)");
+        assert_eq!(error.duplicate, "constant(0.7500)");
+        assert!(!flow.has_label(FuelScore));
+    }
+
+    #[test]
+    fn validate_childless_aggregator() {
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            sum()
+                .with_children((
+                    constant(0.25),
+                    average().with_children(core::iter::empty().collect::<FlowNodeConfigs>()),
+                ))
+                .label(TotalHealthScore),
+            sum()
+                .with_children(core::iter::empty().collect::<FlowNodeConfigs>())
+                .label(HealthScore),
+        ));
+        flow.initialize(&mut world);
+
+        let warnings = flow.validate();
+        assert_eq!(warnings.len(), 2);
+        let names = warnings
+            .iter()
+            .map(|warning| match warning {
+                FlowWarning::ChildlessAggregator { name, .. } => name.as_ref(),
+                warning => panic!("unexpected warning: {warning}"),
+            })
+            .collect::<Vec<_>>();
+        assert!(names.contains(&"average"));
+        assert!(names.contains(&"sum"));
+        let handle = FlowNodeHandle(flow.graph.labels[&HealthScore.intern()]);
+        assert!(warnings.contains(&FlowWarning::ChildlessAggregator {
+            handle,
+            name: "sum".into(),
+        }));
+
+        assert!(flow.remove_label(HealthScore));
+        assert_eq!(flow.validate().len(), 1);
+    }
+
+    #[test]
+    fn validate_arity_mismatch() {
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            difference()
+                .with_children((constant(0.5), constant(0.25), constant(0.125)))
+                .label(TotalHealthScore),
+            difference()
+                .with_children((constant(0.5), constant(0.25)))
+                .label(HealthScore),
+            ratio()
+                .invert()
+                .with_children(constant(0.5))
+                .label(FuelScore),
+        ));
+        flow.initialize(&mut world);
+
+        let warnings = flow.validate();
+        assert_eq!(warnings.len(), 2);
+        let handle = FlowNodeHandle(flow.graph.labels[&TotalHealthScore.intern()]);
+        let warning = FlowWarning::ArityMismatch {
+            handle,
+            name: "difference".into(),
+            children: 3,
+            required: 2..=2,
+        };
+        assert_eq!(
+            format!("{warning}"),
+            "Aggregator difference has 3 children but requires exactly 2"
+        );
+        assert!(warnings.contains(&warning));
+        let handle = FlowNodeHandle(flow.graph.labels[&FuelScore.intern()]);
+        assert!(warnings.contains(&FlowWarning::ArityMismatch {
+            handle,
+            name: "ratio.invert()".into(),
+            children: 1,
+            required: 2..=2,
+        }));
+    }
+
+    #[test]
+    fn run_label() {
+        struct CountingEvaluator(Arc<AtomicUsize>);
+
+        impl Evaluator for CountingEvaluator {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Borrowed("counting")
+            }
+
+            fn evaluate(&mut self, _ctx: EvaluationCtx) -> Score {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                Score::new(0.5)
+            }
+        }
+
+        let health_count = Arc::new(AtomicUsize::new(0));
+        let fuel_count = Arc::new(AtomicUsize::new(0));
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        flow.add_nodes((
+            sum()
+                .with_children((
+                    CountingEvaluator(health_count.clone()),
+                    target::<Health>().label(HealthScore),
+                ))
+                .label(TotalHealthScore),
+            average()
+                .with_children((CountingEvaluator(fuel_count.clone()), constant(0.25)))
+                .label(FuelScore),
+        ));
+        flow.initialize(&mut world);
+        let npc = world.spawn(Health(20)).id();
+
+        assert_eq!(
+            flow.run_label(&world, npc, TotalHealthScore),
+            Some(Score::new(0.7))
+        );
+        assert_eq!(health_count.load(Ordering::Relaxed), 1);
+        assert_eq!(fuel_count.load(Ordering::Relaxed), 0);
+
+        assert_eq!(
+            flow.run_label(&world, npc, FuelScore),
+            Some(Score::new(0.375))
+        );
+        assert_eq!(health_count.load(Ordering::Relaxed), 1);
+        assert_eq!(fuel_count.load(Ordering::Relaxed), 1);
+
+        // Intermediate labels and absent scores are supported too.
+        assert_eq!(
+            flow.run_label(&world, npc, HealthScore),
+            Some(Score::new(0.2))
+        );
+        let idle = world.spawn_empty().id();
+        assert_eq!(flow.run_label(&world, idle, HealthScore), None);
+        assert_eq!(flow.run_label(&world, npc, TimeScore), None);
+        assert_eq!(health_count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn short_circuit() {
+        struct CountingEvaluator(Arc<AtomicUsize>, f32);
+
+        impl Evaluator for CountingEvaluator {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Borrowed("counting")
+            }
+
+            fn evaluate(&mut self, _ctx: EvaluationCtx) -> Score {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                Score::new(self.1)
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut world = World::new();
+        let mut flow = Flow::new(TestFlow);
+        // Children are run in the order they were added, so the cheap gating
+        // children come first.
+        flow.add_nodes((
+            product()
+                .with_children((
+                    target::<Health>(),
+                    CountingEvaluator(count.clone(), 0.5),
+                    sum()
+                        .with_children((
+                            CountingEvaluator(count.clone(), 0.25),
+                            CountingEvaluator(count.clone(), 0.25),
+                        ))
+                        .label(HealthScore),
+                ))
+                .label(TotalHealthScore),
+            sum()
+                .input_threshold(0.3)
+                .with_children((constant(0.1), CountingEvaluator(count.clone(), 0.5)))
+                .label(FuelScore),
+        ));
+        flow.initialize(&mut world);
+        let healthy = world.spawn(Health(50)).id();
+        let dead = world.spawn(Health(0)).id();
+
+        let run = |flow: &mut Flow, short_circuit, target| {
+            count.store(0, Ordering::Relaxed);
+            let scores = flow
+                .short_circuit(short_circuit)
+                .run_readonly(&world, target);
+            (scores, count.load(Ordering::Relaxed))
+        };
+
+        let (full, evaluated) = run(&mut flow, false, dead);
+        assert_eq!(evaluated, 4);
+        assert_eq!(full[&TotalHealthScore.intern()], Score::MIN);
+        assert_eq!(full[&FuelScore.intern()], Score::MIN);
+
+        // The scores are unchanged, but skipped nodes are absent.
+        let (short, evaluated) = run(&mut flow, true, dead);
+        assert_eq!(evaluated, 0);
+        assert_eq!(short[&TotalHealthScore.intern()], Score::MIN);
+        assert_eq!(short[&FuelScore.intern()], Score::MIN);
+        assert_eq!(short.get(&HealthScore.intern()), None);
+
+        // Nothing is skipped while no child determines the score.
+        let (full, _) = run(&mut flow, false, healthy);
+        let (short, evaluated) = run(&mut flow, true, healthy);
+        assert_eq!(evaluated, 3);
+        assert_eq!(short[&TotalHealthScore.intern()], Score::new(0.125));
+        assert_eq!(short, full);
+    }
+}
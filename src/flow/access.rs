@@ -0,0 +1,122 @@
+use alloc::vec::Vec;
+
+use bevy_ecs::{
+    component::{ComponentId, Tick},
+    world::EntityRef,
+};
+
+/// The parts of the [`World`] read by a flow node, as reported by
+/// [`Evaluator::access`] and [`Aggregator::access`].
+///
+/// Component reads are split between components read on the target entity
+/// and components read on related entities, such as the target's parent.
+///
+/// [`World`]: bevy_ecs::world::World
+/// [`Evaluator::access`]: crate::evaluator::Evaluator::access
+/// [`Aggregator::access`]: crate::aggregator::Aggregator::access
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct FlowAccess {
+    reads_all: bool,
+    components: Vec<ComponentId>,
+    related_components: Vec<ComponentId>,
+    resources: Vec<ComponentId>,
+}
+
+impl FlowAccess {
+    /// Returns an access that reads nothing from the world.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Returns an access that may read anything from the world.
+    pub fn all() -> Self {
+        Self {
+            reads_all: true,
+            ..Self::default()
+        }
+    }
+
+    /// Adds a read of the given component on the target entity.
+    pub fn add_component_read(&mut self, component: ComponentId) {
+        if !self.components.contains(&component) {
+            self.components.push(component);
+        }
+    }
+
+    /// Adds a read of the given component on entities other than the target.
+    pub fn add_related_component_read(&mut self, component: ComponentId) {
+        if !self.related_components.contains(&component) {
+            self.related_components.push(component);
+        }
+    }
+
+    /// Adds a read of the given resource.
+    pub fn add_resource_read(&mut self, resource: ComponentId) {
+        if !self.resources.contains(&resource) {
+            self.resources.push(resource);
+        }
+    }
+
+    /// Adds all reads of `other` to this access.
+    pub fn extend(&mut self, other: &FlowAccess) {
+        self.reads_all |= other.reads_all;
+        for &component in &other.components {
+            self.add_component_read(component);
+        }
+        for &component in &other.related_components {
+            self.add_related_component_read(component);
+        }
+        for &resource in &other.resources {
+            self.add_resource_read(resource);
+        }
+    }
+
+    /// Returns `true` if this access may read anything from the world.
+    pub fn reads_all(&self) -> bool {
+        self.reads_all
+    }
+
+    /// Returns `true` if the given component is read on the target entity.
+    pub fn has_component_read(&self, component: ComponentId) -> bool {
+        self.reads_all || self.components.contains(&component)
+    }
+
+    /// Returns `true` if the given component is read on related entities.
+    pub fn has_related_component_read(&self, component: ComponentId) -> bool {
+        self.reads_all || self.related_components.contains(&component)
+    }
+
+    /// Returns `true` if the given resource is read.
+    pub fn has_resource_read(&self, resource: ComponentId) -> bool {
+        self.reads_all || self.resources.contains(&resource)
+    }
+
+    /// Returns an iterator over the components read on the target entity.
+    pub fn component_reads(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.components.iter().copied()
+    }
+
+    /// Returns an iterator over the components read on related entities.
+    pub fn related_component_reads(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.related_components.iter().copied()
+    }
+
+    /// Returns an iterator over the resources read.
+    pub fn resource_reads(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.resources.iter().copied()
+    }
+
+    /// Returns `true` if any component read on the given entity was added or
+    /// changed between `last_run` and `this_run`. Always `true` if this access
+    /// reads everything or reads components of related entities, as changes to
+    /// those are not tracked.
+    pub fn is_entity_changed(&self, entity: EntityRef, last_run: Tick, this_run: Tick) -> bool {
+        self.reads_all
+            || !self.related_components.is_empty()
+            || self.components.iter().any(|&component| {
+                entity
+                    .get_change_ticks_by_id(component)
+                    .is_some_and(|ticks| ticks.is_changed(last_run, this_run))
+            })
+    }
+}
@@ -0,0 +1,67 @@
+use alloc::vec::Vec;
+
+use crate::{
+    flow::{Flow, FlowNodeConfigs, IntoFlowNodeConfigs},
+    label::{FlowLabel, InternedFlowLabel},
+};
+
+/// Builds a [`Flow`] without a [`World`], so that reusable flows can be
+/// defined in plain functions and later inserted with [`Flows::insert`].
+///
+/// [`World`]: bevy_ecs::world::World
+/// [`Flows::insert`]: crate::flow::Flows::insert
+pub struct FlowBuilder {
+    label: InternedFlowLabel,
+    nodes: FlowNodeConfigs,
+    skip_unchanged: bool,
+    short_circuit: bool,
+}
+
+impl FlowBuilder {
+    /// Creates a builder for an empty flow with the given label.
+    pub fn new(label: impl FlowLabel) -> Self {
+        Self {
+            label: label.intern(),
+            nodes: FlowNodeConfigs(Vec::new()),
+            skip_unchanged: false,
+            short_circuit: false,
+        }
+    }
+
+    /// Adds the given nodes to the flow.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<M>(mut self, nodes: impl IntoFlowNodeConfigs<M>) -> Self {
+        self.nodes.0.extend(nodes.into_configs().0);
+        self
+    }
+
+    /// Sets whether the flow skips unchanged entities. See
+    /// [`Flow::skip_unchanged`].
+    pub fn skip_unchanged(mut self, skip: bool) -> Self {
+        self.skip_unchanged = skip;
+        self
+    }
+
+    /// Sets whether aggregators in the flow short-circuit. See
+    /// [`Flow::short_circuit`].
+    pub fn short_circuit(mut self, enabled: bool) -> Self {
+        self.short_circuit = enabled;
+        self
+    }
+
+    /// Builds the flow. It must still be initialized before running, which
+    /// happens automatically when run through the world.
+    pub fn build(self) -> Flow {
+        let mut flow = Flow::new(self.label);
+        flow.add_nodes(self.nodes)
+            .skip_unchanged(self.skip_unchanged)
+            .short_circuit(self.short_circuit);
+        flow
+    }
+
+    /// Returns the added nodes, discarding the label and settings, so that
+    /// they can be added to another flow.
+    pub fn into_nodes(self) -> FlowNodeConfigs {
+        self.nodes
+    }
+}
@@ -1,12 +1,12 @@
 use alloc::{borrow::Cow, boxed::Box, vec, vec::Vec};
 
+use tracing::warn;
 use variadics_please::all_tuples_with_size;
 
 use crate::{
-    aggregator::{AggregationCtx, Aggregator, IntoAggregator},
+    aggregator::{abs_difference, difference, ratio, Aggregator, IntoAggregator},
     evaluator::{Evaluator, IntoEvaluator},
     label::{InternedScoreLabel, ScoreLabel},
-    score::Score,
 };
 
 /// Configuration for a flow node.
@@ -42,6 +42,29 @@ impl FlowNodeConfig {
         }
     }
 
+    /// Appends the given node to the children of this aggregator. See
+    /// [`FlowNodeConfig::add_children`].
+    pub fn add_child<M>(self, child: impl IntoFlowNodeConfig<M>) -> Self {
+        self.add_children(child.into_config())
+    }
+
+    /// Appends the given nodes to the children of this aggregator, allowing
+    /// them to be added one at a time, e.g. in a loop. If this config is an
+    /// evaluator, which cannot have children, a warning is logged and the
+    /// nodes are discarded.
+    pub fn add_children<M>(mut self, children: impl IntoFlowNodeConfigs<M>) -> Self {
+        match &mut self.node {
+            FlowNode::Aggregator {
+                children: existing, ..
+            } => existing.0.extend(children.into_configs().0),
+            FlowNode::Evaluator { evaluator } => warn!(
+                "Cannot add children to evaluator `{}`: the children have been discarded",
+                evaluator.name()
+            ),
+        }
+        self
+    }
+
     /// Labels this aggregator or evaluator with the given [`ScoreLabel`].
     pub fn label(mut self, label: impl ScoreLabel) -> Self {
         self.label = Some(label.intern());
@@ -88,29 +111,38 @@ pub trait IntoFlowNodeConfig<Marker> {
     /// Converts this value into a [`FlowNodeConfig`].
     fn into_config(self) -> FlowNodeConfig;
 
-    /// Returns a [`FlowNodeConfig`] for an [`Aggregator`] that computes the
-    /// difference of this value and the other.
+    /// Returns a [`FlowNodeConfig`] for an [`Aggregator`] that subtracts the
+    /// other value from this one. See [`difference`] for details.
+    ///
+    /// [`difference`]: crate::aggregator::difference
     fn difference<M>(self, other: impl IntoFlowNodeConfig<M>) -> FlowNodeConfig
     where
         Self: Sized,
     {
-        struct DifferenceAggregator;
-
-        impl Aggregator for DifferenceAggregator {
-            fn name(&self) -> Cow<'static, str> {
-                Cow::Borrowed("difference")
-            }
+        FlowNodeConfig::aggregator(difference(), (self.into_config(), other.into_config()))
+    }
 
-            fn aggregate(&mut self, ctx: AggregationCtx) -> Score {
-                let [a, b] = [ctx.aggregation.scores[0], ctx.aggregation.scores[1]];
-                a - b
-            }
-        }
+    /// Returns a [`FlowNodeConfig`] for an [`Aggregator`] that computes the
+    /// absolute difference between this value and the other. See
+    /// [`abs_difference`] for details.
+    ///
+    /// [`abs_difference`]: crate::aggregator::abs_difference
+    fn abs_difference<M>(self, other: impl IntoFlowNodeConfig<M>) -> FlowNodeConfig
+    where
+        Self: Sized,
+    {
+        FlowNodeConfig::aggregator(abs_difference(), (self.into_config(), other.into_config()))
+    }
 
-        FlowNodeConfig::aggregator(
-            DifferenceAggregator,
-            (self.into_config(), other.into_config()),
-        )
+    /// Returns a [`FlowNodeConfig`] for an [`Aggregator`] that divides this
+    /// value by the other. See [`ratio`] for details.
+    ///
+    /// [`ratio`]: crate::aggregator::ratio
+    fn ratio<M>(self, other: impl IntoFlowNodeConfig<M>) -> FlowNodeConfig
+    where
+        Self: Sized,
+    {
+        FlowNodeConfig::aggregator(ratio(), (self.into_config(), other.into_config()))
     }
 }
 
@@ -135,6 +167,12 @@ where
 /// A collection of [`FlowNodeConfig`]s.
 pub struct FlowNodeConfigs(pub(super) Vec<FlowNodeConfig>);
 
+impl FromIterator<FlowNodeConfig> for FlowNodeConfigs {
+    fn from_iter<I: IntoIterator<Item = FlowNodeConfig>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 /// Trait for types that can be converted into a [`FlowNodeConfigs`].
 pub trait IntoFlowNodeConfigs<Marker> {
     /// Converts this value into a [`FlowNodeConfigs`].
@@ -0,0 +1,116 @@
+use alloc::borrow::Cow;
+
+use crate::{
+    flow::{FlowGraph, FlowNodeHandle, NodeId},
+    label::{InternedScoreLabel, ScoreLabel},
+};
+
+/// Read-only view over the structure of a [`Flow`], returned by
+/// [`Flow::inspect`].
+///
+/// [`Flow`]: crate::flow::Flow
+/// [`Flow::inspect`]: crate::flow::Flow::inspect
+#[derive(Clone, Copy)]
+pub struct FlowInspector<'a> {
+    pub(super) graph: &'a FlowGraph,
+}
+
+impl<'a> FlowInspector<'a> {
+    /// Returns the number of evaluator and aggregator nodes in the flow.
+    pub fn node_count(&self) -> usize {
+        self.graph.dependency.node_count()
+    }
+
+    /// Returns an iterator over the [`ScoreLabel`]s in the flow and the names
+    /// of the nodes they label, in arbitrary order.
+    pub fn labeled_nodes(
+        &self,
+    ) -> impl Iterator<Item = (InternedScoreLabel, Cow<'static, str>)> + 'a {
+        let graph = self.graph;
+        graph
+            .labels
+            .iter()
+            .map(move |(&label, &node)| (label, graph.describe(node).1))
+    }
+
+    /// Returns an iterator over the nodes without a parent, in the order they
+    /// were added to the flow.
+    pub fn roots(&self) -> impl Iterator<Item = FlowNodeView<'a>> + 'a {
+        let graph = self.graph;
+        graph
+            .roots
+            .iter()
+            .map(move |&node| FlowNodeView { graph, node })
+    }
+
+    /// Returns the node with the given handle, if it is still in the flow.
+    pub fn get(&self, handle: FlowNodeHandle) -> Option<FlowNodeView<'a>> {
+        self.graph
+            .dependency
+            .contains_node(handle.0)
+            .then_some(FlowNodeView {
+                graph: self.graph,
+                node: handle.0,
+            })
+    }
+
+    /// Returns the node labeled with the given [`ScoreLabel`], if any.
+    pub fn node(&self, label: impl ScoreLabel) -> Option<FlowNodeView<'a>> {
+        let node = *self.graph.labels.get(&label.intern())?;
+        Some(FlowNodeView {
+            graph: self.graph,
+            node,
+        })
+    }
+}
+
+/// Read-only view over a single node of a [`Flow`].
+///
+/// [`Flow`]: crate::flow::Flow
+#[derive(Clone, Copy)]
+pub struct FlowNodeView<'a> {
+    graph: &'a FlowGraph,
+    node: NodeId,
+}
+
+impl<'a> FlowNodeView<'a> {
+    /// Returns the handle of the node, which can be used to find it in traces
+    /// and profiles of the flow.
+    pub fn handle(&self) -> FlowNodeHandle {
+        FlowNodeHandle(self.node)
+    }
+
+    /// Returns the name of the evaluator or aggregator.
+    pub fn name(&self) -> Cow<'static, str> {
+        self.graph.describe(self.node).1
+    }
+
+    /// Returns whether the node is an `"evaluator"` or an `"aggregator"`.
+    pub fn kind(&self) -> &'static str {
+        self.graph.describe(self.node).0
+    }
+
+    /// Returns the [`ScoreLabel`] of the node, if any.
+    pub fn label(&self) -> Option<InternedScoreLabel> {
+        self.graph.labeled.get(&self.node).copied()
+    }
+
+    /// Returns the parent of the node, if any.
+    pub fn parent(&self) -> Option<FlowNodeView<'a>> {
+        let node = self.graph.dependency.neighbors(self.node).next()?;
+        Some(FlowNodeView {
+            graph: self.graph,
+            node,
+        })
+    }
+
+    /// Returns an iterator over the children of the node, in the order they
+    /// were added to the flow.
+    pub fn children(&self) -> impl Iterator<Item = FlowNodeView<'a>> + 'a {
+        let graph = self.graph;
+        graph
+            .children(self.node)
+            .iter()
+            .map(move |&node| FlowNodeView { graph, node })
+    }
+}
@@ -0,0 +1,125 @@
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{fmt::Write, time::Duration};
+
+use bevy_utils::{HashMap, Instant};
+
+use crate::{
+    flow::{FlowGraph, FlowNodeHandle, NodeId, NodeObserver},
+    label::InternedScoreLabel,
+    score::Score,
+};
+
+/// Hierarchical timing tree captured by [`Flow::run_profiled`], mirroring the
+/// flow's dependency graph.
+///
+/// [`Flow::run_profiled`]: crate::flow::Flow::run_profiled
+#[derive(Clone, Debug, Default)]
+pub struct FlowProfile {
+    /// The nodes without a parent, in the order they were added to the flow.
+    pub roots: Vec<ProfileNode>,
+}
+
+impl FlowProfile {
+    /// Returns the total time spent running the flow's nodes.
+    pub fn total_time(&self) -> Duration {
+        self.roots.iter().map(ProfileNode::total_time).sum()
+    }
+
+    /// Renders the profile in the folded stack format understood by
+    /// flamegraph tools such as `inferno` and `flamegraph.pl`. Each line is a
+    /// `;`-separated path from a root node followed by the node's self time in
+    /// nanoseconds.
+    pub fn to_folded(&self) -> String {
+        fn fold(node: &ProfileNode, stack: &mut String, out: &mut String) {
+            let len = stack.len();
+            if !stack.is_empty() {
+                stack.push(';');
+            }
+            stack.extend(node.name.chars().map(|c| if c == ';' { ':' } else { c }));
+
+            let _ = writeln!(out, "{stack} {}", node.self_time.as_nanos());
+            for child in &node.children {
+                fold(child, stack, out);
+            }
+
+            stack.truncate(len);
+        }
+
+        let mut out = String::new();
+        let mut stack = String::new();
+        for root in &self.roots {
+            fold(root, &mut stack, &mut out);
+        }
+        out
+    }
+}
+
+/// A single evaluator or aggregator node in a [`FlowProfile`].
+#[derive(Clone, Debug)]
+pub struct ProfileNode {
+    /// The handle of the node in its flow.
+    pub handle: FlowNodeHandle,
+    /// The name of the evaluator or aggregator.
+    pub name: Cow<'static, str>,
+    /// Whether the node is an `"evaluator"` or an `"aggregator"`.
+    pub kind: &'static str,
+    /// The [`ScoreLabel`](crate::label::ScoreLabel) of the node, if any.
+    pub label: Option<InternedScoreLabel>,
+    /// The time spent in this node, excluding its children.
+    pub self_time: Duration,
+    /// The child nodes, in the order they were added to the flow.
+    pub children: Vec<ProfileNode>,
+}
+
+impl ProfileNode {
+    /// Returns the time spent in this node, including its children.
+    pub fn total_time(&self) -> Duration {
+        self.self_time
+            + self
+                .children
+                .iter()
+                .map(ProfileNode::total_time)
+                .sum::<Duration>()
+    }
+}
+
+/// [`NodeObserver`] that times each node.
+#[derive(Default)]
+pub(super) struct Profiler {
+    timings: HashMap<NodeId, Duration>,
+}
+
+impl NodeObserver for Profiler {
+    fn observe(&mut self, node: NodeId, score: impl FnOnce() -> Option<Score>) -> Option<Score> {
+        let start = Instant::now();
+        let score = score();
+        self.timings.insert(node, start.elapsed());
+        score
+    }
+}
+
+impl Profiler {
+    /// Builds the [`FlowProfile`] from the recorded timings.
+    pub(super) fn finish(self, graph: &FlowGraph) -> FlowProfile {
+        let build = |node| self.build(graph, node);
+        FlowProfile {
+            roots: graph.roots.iter().copied().map(build).collect(),
+        }
+    }
+
+    fn build(&self, graph: &FlowGraph, node: NodeId) -> ProfileNode {
+        let (kind, name) = graph.describe(node);
+        ProfileNode {
+            handle: FlowNodeHandle(node),
+            name,
+            kind,
+            label: graph.labeled.get(&node).copied(),
+            self_time: self.timings.get(&node).copied().unwrap_or_default(),
+            children: graph
+                .children(node)
+                .iter()
+                .map(|&child| self.build(graph, child))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{
+    flow::{FlowGraph, FlowNodeHandle, NodeId, NodeObserver},
+    label::{InternedScoreLabel, ScoreLabel},
+    score::Score,
+};
+
+/// Scores of every node computed during a [`Flow::run_traced`] call,
+/// including unlabeled intermediate nodes.
+///
+/// [`Flow::run_traced`]: crate::flow::Flow::run_traced
+#[derive(Clone, Debug, Default)]
+pub struct FlowTrace {
+    /// The traced nodes, in the order they were run.
+    pub nodes: Vec<TracedNode>,
+}
+
+impl FlowTrace {
+    /// Returns the traced node with the given handle, if it was run.
+    pub fn get(&self, handle: FlowNodeHandle) -> Option<&TracedNode> {
+        self.nodes.iter().find(|node| node.handle == handle)
+    }
+
+    /// Returns the score computed for the node with the given handle, if it
+    /// was run and its score was not absent.
+    pub fn score(&self, handle: FlowNodeHandle) -> Option<Score> {
+        self.get(handle)?.score
+    }
+
+    /// Returns the score computed for the node with the given [`ScoreLabel`],
+    /// if it was run and its score was not absent.
+    pub fn labeled(&self, label: impl ScoreLabel) -> Option<Score> {
+        let label = label.intern();
+        self.nodes
+            .iter()
+            .find(|node| node.label == Some(label))?
+            .score
+    }
+
+    /// Returns an iterator over the traced nodes, in the order they were run.
+    pub fn iter(&self) -> impl Iterator<Item = &TracedNode> {
+        self.nodes.iter()
+    }
+}
+
+/// A single evaluator or aggregator node in a [`FlowTrace`].
+#[derive(Clone, Debug)]
+pub struct TracedNode {
+    /// The handle of the node in its flow.
+    pub handle: FlowNodeHandle,
+    /// The name of the evaluator or aggregator.
+    pub name: Cow<'static, str>,
+    /// The [`ScoreLabel`](crate::label::ScoreLabel) of the node, if any.
+    pub label: Option<InternedScoreLabel>,
+    /// The score computed for the node, or [`None`] if the score was absent.
+    pub score: Option<Score>,
+    /// The child node whose score most influenced the score of this
+    /// aggregator node, as reported by [`Aggregator::explain`]. Always [`None`]
+    /// for evaluator nodes.
+    ///
+    /// [`Aggregator::explain`]: crate::aggregator::Aggregator::explain
+    pub dominant: Option<FlowNodeHandle>,
+}
+
+/// [`NodeObserver`] that records the score of each node, and the slot of the
+/// dominant child score of each aggregator node.
+#[derive(Default)]
+pub(super) struct Tracer {
+    scores: Vec<(NodeId, Option<Score>, Option<usize>)>,
+}
+
+impl NodeObserver for Tracer {
+    fn observe(&mut self, node: NodeId, score: impl FnOnce() -> Option<Score>) -> Option<Score> {
+        let score = score();
+        self.scores.push((node, score, None));
+        score
+    }
+
+    fn explain(&mut self, dominant: impl FnOnce() -> Option<usize>) {
+        if let Some((_, _, slot)) = self.scores.last_mut() {
+            *slot = dominant();
+        }
+    }
+}
+
+impl Tracer {
+    /// Attaches the names and labels of the recorded nodes.
+    pub(super) fn finish(self, graph: &FlowGraph) -> FlowTrace {
+        let nodes = self
+            .scores
+            .into_iter()
+            .map(|(node, score, slot)| TracedNode {
+                handle: FlowNodeHandle(node),
+                name: graph.describe(node).1,
+                label: graph.labeled.get(&node).copied(),
+                score,
+                dominant: slot.and_then(|slot| {
+                    let step = graph.plan.steps.iter().find(|step| step.slot == slot)?;
+                    Some(FlowNodeHandle(step.node))
+                }),
+            })
+            .collect();
+        FlowTrace { nodes }
+    }
+}
@@ -24,6 +24,14 @@
 //! - There is no [`ApplyDeferred`] system that run between and after all systems.
 //!   Because all world access is read-only, no commands can be queued.
 //!
+//! ## `no_std`
+//!
+//! The crate itself is `#![no_std]` and only uses `core` and `alloc`, which
+//! the workspace lints enforce. Features that need the standard library, such
+//! as `profiling` and `diagnostic`, enable the `std` feature. The Bevy 0.15
+//! crates it depends on still require `std`, so targets without it are not
+//! supported yet.
+//!
 //! [`Score`]: crate::score::Score
 //! [`Evaluator`]: crate::evaluator::Evaluator
 //! [`Aggregator`]: crate::aggregator::Aggregator
@@ -38,10 +46,15 @@
 pub mod aggregator;
 pub mod command;
 pub mod component;
+pub mod curve;
+#[cfg(feature = "diagnostic")]
+pub mod diagnostic;
 pub mod evaluator;
 pub mod flow;
 pub mod label;
 pub mod mapper;
+#[cfg(feature = "bevy_app")]
+pub mod plugin;
 pub mod score;
 pub mod selector;
 
@@ -93,7 +106,7 @@ mod tests {
     }
 
     #[derive(Component)]
-    pub struct Health(i32);
+    pub struct Health(pub i32);
 
     impl Scoreable for Health {
         fn score(&self) -> Score {
@@ -102,7 +115,7 @@ mod tests {
     }
 
     #[derive(Component)]
-    pub struct Fuel(i32);
+    pub struct Fuel(pub i32);
 
     impl Scoreable for Fuel {
         fn score(&self) -> Score {
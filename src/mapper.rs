@@ -3,12 +3,16 @@
 
 use alloc::{borrow::Cow, boxed::Box};
 
-use bevy_ecs::{entity::Entity, system::SystemInput, world::World};
+use bevy_ecs::{component::Tick, entity::Entity, system::SystemInput, world::World};
 
 use crate::score::Score;
 
+mod clamp_to;
+mod scale_by_resource;
 mod system;
 
+pub use clamp_to::*;
+pub use scale_by_resource::*;
 pub use system::*;
 
 /// Trait for types that view the target [`Entity`] in a [`World`] and maps a
@@ -22,6 +26,13 @@ pub trait Mapper<T>: Send + Sync + 'static {
         let _ = world;
     }
 
+    /// Checks the change ticks stored by the mapper, such as the last run
+    /// tick of a system, and clamps them to prevent change detection false
+    /// positives when the world's change tick overflows.
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        let _ = change_tick;
+    }
+
     /// Maps the value using the given context.
     fn map(&mut self, ctx: MappingCtx<T>) -> T;
 }
@@ -73,3 +84,65 @@ impl<T> SystemInput for Mapping<T> {
         this
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{entity::Entity, system::Resource, world::World};
+
+    use crate::{
+        evaluator::{constant, EvaluationCtx, Evaluator, IntoEvaluator},
+        mapper::{clamp_to, scale_by_resource, Mapper, Mapping, MappingCtx},
+        score::{Score, Scoreable},
+    };
+
+    #[derive(Resource)]
+    struct Difficulty(f32);
+
+    impl Scoreable for Difficulty {
+        fn score(&self) -> Score {
+            Score::new(self.0)
+        }
+    }
+
+    fn map(mapper: &mut impl Mapper<Score>, world: &World, value: f32) -> Score {
+        mapper.map(MappingCtx {
+            world,
+            mapping: Mapping {
+                target: Entity::PLACEHOLDER,
+                value: Score::new(value),
+            },
+        })
+    }
+
+    #[test]
+    fn scale_by_resource_mapper() {
+        let mut world = World::new();
+        let mut mapper = scale_by_resource::<Difficulty>();
+        assert_eq!(map(&mut mapper, &world, 0.5), Score::MIN);
+
+        world.insert_resource(Difficulty(0.5));
+        assert_eq!(map(&mut mapper, &world, 0.5), Score::new(0.25));
+        assert_eq!(map(&mut mapper, &world, 1.), Score::new(0.5));
+
+        let mut evaluator = constant(0.75).map(scale_by_resource::<Difficulty>());
+        evaluator.initialize(&mut world);
+        let score = evaluator.evaluate(EvaluationCtx::new(&world, Entity::PLACEHOLDER));
+        assert_eq!(score, Score::new(0.375));
+    }
+
+    #[test]
+    fn clamp_to_mapper() {
+        let world = World::new();
+        let mut mapper = clamp_to(0.25, 0.75);
+        assert_eq!(mapper.name(), "clamp_to(0.2500, 0.7500)");
+        assert_eq!(map(&mut mapper, &world, 0.), Score::new(0.25));
+        assert_eq!(map(&mut mapper, &world, 0.5), Score::new(0.5));
+        assert_eq!(map(&mut mapper, &world, 1.), Score::new(0.75));
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamp_to_inverted_bounds() {
+        clamp_to(0.75, 0.25);
+    }
+}
@@ -0,0 +1,32 @@
+use alloc::{borrow::Cow, format};
+
+use crate::{
+    mapper::{Mapper, MappingCtx},
+    score::Score,
+};
+
+/// Creates a [`Mapper`] that clamps the score to be between `min` and `max`.
+///
+/// # Panics
+///
+/// If `min` is greater than `max`.
+pub fn clamp_to(min: impl Into<Score>, max: impl Into<Score>) -> impl Mapper<Score> {
+    let (min, max) = (min.into(), max.into());
+    assert!(min <= max, "min must be less than or equal to max");
+    ClampToMapper { min, max }
+}
+
+struct ClampToMapper {
+    min: Score,
+    max: Score,
+}
+
+impl Mapper<Score> for ClampToMapper {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("clamp_to({}, {})", self.min, self.max))
+    }
+
+    fn map(&mut self, ctx: MappingCtx<Score>) -> Score {
+        ctx.mapping.value.clamp(self.min, self.max)
+    }
+}
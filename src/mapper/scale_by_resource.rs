@@ -0,0 +1,33 @@
+use alloc::{borrow::Cow, format};
+use core::marker::PhantomData;
+
+use bevy_ecs::system::Resource;
+
+use crate::{
+    mapper::{Mapper, MappingCtx},
+    score::{Score, Scoreable},
+};
+
+/// Creates a [`Mapper`] that multiplies the score by the score of the given
+/// [`Resource`], such as scaling aggression by a global difficulty. If the
+/// resource is not present in the world, the mapper returns [`Score::MIN`].
+pub fn scale_by_resource<R: Resource + Scoreable>() -> impl Mapper<Score> {
+    ScaleByResourceMapper::<R>(PhantomData)
+}
+
+struct ScaleByResourceMapper<R: Resource + Scoreable>(PhantomData<R>);
+
+impl<R: Resource + Scoreable> Mapper<Score> for ScaleByResourceMapper<R> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "scale_by_resource({})",
+            core::any::type_name::<R>()
+        ))
+    }
+
+    fn map(&mut self, ctx: MappingCtx<Score>) -> Score {
+        ctx.world
+            .get_resource::<R>()
+            .map_or(Score::MIN, |r| ctx.mapping.value * r.score())
+    }
+}
@@ -0,0 +1,359 @@
+//! Provides the [`UtilityAiPlugin`] for running [`Flow`]s in a bevy [`App`].
+//!
+//! [`Flow`]: crate::flow::Flow
+
+use bevy_app::{App, FixedUpdate, Plugin, Update};
+use bevy_ecs::{
+    intern::Interned,
+    schedule::{IntoSystemConfigs, IntoSystemSetConfigs, ScheduleLabel, SystemSet},
+};
+
+use crate::{
+    component::{
+        check_flow_change_ticks, run_action_selectors, run_all_entity_flows,
+        run_multi_action_selectors, ActionChanged,
+    },
+    flow::Flows,
+};
+
+/// [`Plugin`] that inserts the [`Flows`] resource and scores all entities with
+/// an [`EntityFlow`] every time its schedule runs. Afterwards, entities with an
+/// [`ActionSelector`] have their [`Actions`] updated with the selected action
+/// and an [`ActionChanged`] event is sent on every change, and entities with a
+/// [`MultiActionSelector`] have their [`SelectedActions`] updated, unless
+/// disabled with [`UtilityAiPlugin::selection`].
+///
+/// By default the systems run in [`Update`], once per frame. Use
+/// [`UtilityAiPlugin::in_schedule`] to pick any other schedule, or
+/// [`UtilityAiPlugin::fixed`] to run them in [`FixedUpdate`], so that
+/// decisions tick at a fixed rate independently of the frame rate. Evaluators
+/// reading `Time` then observe the fixed timestep, as bevy swaps the default
+/// clock to `Time<Fixed>` while [`FixedUpdate`] runs. Order the
+/// [`UtilityAiSystems`] sets relative to your physics systems to control
+/// whether scores see the pre- or post-physics state of the current step.
+///
+/// [`EntityFlow`]: crate::component::EntityFlow
+/// [`ActionSelector`]: crate::component::ActionSelector
+/// [`MultiActionSelector`]: crate::component::MultiActionSelector
+/// [`SelectedActions`]: crate::component::SelectedActions
+/// [`Actions`]: crate::component::Actions
+/// [`Flows`]: crate::flow::Flows
+pub struct UtilityAiPlugin {
+    schedule: Interned<dyn ScheduleLabel>,
+    selection: bool,
+}
+
+impl UtilityAiPlugin {
+    /// Creates a plugin that runs the utility AI systems in [`FixedUpdate`].
+    pub fn fixed() -> Self {
+        Self::default().in_schedule(FixedUpdate)
+    }
+
+    /// Sets the schedule the utility AI systems run in.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = schedule.intern();
+        self
+    }
+
+    /// Sets whether [`run_action_selectors`] and [`run_multi_action_selectors`]
+    /// run after scoring. Enabled by default.
+    pub fn selection(mut self, enabled: bool) -> Self {
+        self.selection = enabled;
+        self
+    }
+}
+
+impl Default for UtilityAiPlugin {
+    fn default() -> Self {
+        Self {
+            schedule: Update.intern(),
+            selection: true,
+        }
+    }
+}
+
+impl Plugin for UtilityAiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Flows>()
+            .configure_sets(
+                self.schedule,
+                UtilityAiSystems::Score.before(UtilityAiSystems::Select),
+            )
+            .add_systems(
+                self.schedule,
+                (check_flow_change_ticks, run_all_entity_flows)
+                    .chain()
+                    .in_set(UtilityAiSystems::Score),
+            );
+
+        if self.selection {
+            app.add_event::<ActionChanged>().add_systems(
+                self.schedule,
+                (run_action_selectors, run_multi_action_selectors).in_set(UtilityAiSystems::Select),
+            );
+        }
+    }
+}
+
+/// [`SystemSet`]s for the systems added by [`UtilityAiPlugin`].
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UtilityAiSystems {
+    /// Runs the flows associated with entities and stores their
+    /// [`ComputedScores`](crate::component::ComputedScores).
+    Score,
+    /// Runs the selectors associated with entities and updates their
+    /// [`Actions`](crate::component::Actions) and
+    /// [`SelectedActions`](crate::component::SelectedActions). Runs after
+    /// [`UtilityAiSystems::Score`].
+    Select,
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::Cow, sync::Arc, vec::Vec};
+    use core::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use bevy::time::{Fixed, Time, TimePlugin, TimeUpdateStrategy};
+    use bevy_app::{App, FixedUpdate, PostUpdate};
+    use bevy_ecs::{
+        event::Events,
+        system::{ResMut, Resource},
+    };
+
+    use crate::{
+        self as evergreen_utility_ai,
+        component::{
+            ActionChanged, ActionSelector, Actions, ComputedScores, EntityFlow,
+            MultiActionSelector, SelectedActions,
+        },
+        evaluator::{constant, target, EvaluationCtx, Evaluator, IntoEvaluator},
+        flow::{Flows, WorldFlowExt},
+        label::ActionLabel,
+        macros::ActionLabel,
+        plugin::UtilityAiPlugin,
+        score::Score,
+        selector::{highest_score, top_n_actions},
+        tests::{Health, HealthScore, TestFlow, TotalHealthScore},
+    };
+
+    /// Counts how many times it was evaluated.
+    struct CountingEvaluator(Arc<AtomicUsize>);
+
+    impl Evaluator for CountingEvaluator {
+        fn name(&self) -> Cow<'static, str> {
+            Cow::Borrowed("counting")
+        }
+
+        fn evaluate(&mut self, _ctx: EvaluationCtx) -> Score {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Score::MAX
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct FixedSteps(usize);
+
+    #[test]
+    fn scores_after_one_update() {
+        let mut app = App::new();
+        app.add_plugins(UtilityAiPlugin::default().in_schedule(PostUpdate));
+        assert!(app.world().contains_resource::<Flows>());
+
+        let world = app.world_mut();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.25).label(HealthScore),
+                target::<Health>().label(TotalHealthScore),
+            ),
+        );
+        let npcs = [
+            world.spawn((EntityFlow::new(TestFlow), Health(50))).id(),
+            world.spawn((EntityFlow::new(TestFlow), Health(100))).id(),
+        ];
+        assert!(app
+            .world()
+            .get::<ComputedScores>(npcs[0])
+            .unwrap()
+            .get(HealthScore)
+            .is_none());
+
+        app.update();
+
+        for (npc, health) in npcs.into_iter().zip([0.5, 1.0]) {
+            let scores = app.world().get::<ComputedScores>(npc).unwrap();
+            assert_eq!(scores.get(HealthScore), Some(Score::new(0.25)));
+            assert_eq!(scores.get(TotalHealthScore), Some(Score::new(health)));
+        }
+    }
+
+    #[derive(ActionLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    enum TestAction {
+        Idle,
+        Eat,
+        Flee,
+    }
+
+    #[test]
+    fn selection_updates_current_action() {
+        let mut app = App::new();
+        app.add_plugins(UtilityAiPlugin::default());
+
+        let world = app.world_mut();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.25).label(HealthScore),
+                target::<Health>().label(TotalHealthScore),
+            ),
+        );
+        let npc = world
+            .spawn((
+                EntityFlow::new(TestFlow),
+                ActionSelector::new(highest_score()),
+                Actions::new(TestAction::Idle)
+                    .with(HealthScore, TestAction::Eat)
+                    .with(TotalHealthScore, TestAction::Flee),
+                Health(50),
+            ))
+            .id();
+        let current = |app: &App| app.world().get::<Actions>(npc).unwrap().current().intern();
+        assert_eq!(current(&app), TestAction::Idle.intern());
+
+        app.update();
+        assert_eq!(current(&app), TestAction::Flee.intern());
+
+        app.world_mut().get_mut::<Health>(npc).unwrap().0 = 10;
+        app.update();
+        assert_eq!(current(&app), TestAction::Eat.intern());
+    }
+
+    #[test]
+    fn multi_selection_updates_selected_actions() {
+        let mut app = App::new();
+        app.add_plugins(UtilityAiPlugin::default());
+
+        let world = app.world_mut();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.25).label(HealthScore),
+                target::<Health>().label(TotalHealthScore),
+            ),
+        );
+        let npc = world
+            .spawn((
+                EntityFlow::new(TestFlow),
+                MultiActionSelector::new(top_n_actions(2)),
+                Actions::new(TestAction::Idle)
+                    .with(HealthScore, TestAction::Eat)
+                    .with(TotalHealthScore, TestAction::Flee),
+                Health(50),
+            ))
+            .id();
+        let selected = |app: &App| app.world().get::<SelectedActions>(npc).unwrap().clone();
+        assert!(selected(&app).as_slice().is_empty());
+
+        app.update();
+        assert_eq!(
+            selected(&app).as_slice(),
+            [TestAction::Flee.intern(), TestAction::Eat.intern()]
+        );
+
+        app.world_mut().get_mut::<Health>(npc).unwrap().0 = 10;
+        app.update();
+        assert_eq!(
+            selected(&app).as_slice(),
+            [TestAction::Eat.intern(), TestAction::Flee.intern()]
+        );
+        assert!(selected(&app).contains(TestAction::Eat));
+        assert!(!selected(&app).contains(TestAction::Idle));
+    }
+
+    #[test]
+    fn action_changed_events() {
+        let mut app = App::new();
+        app.add_plugins(UtilityAiPlugin::default());
+
+        let world = app.world_mut();
+        world.add_nodes(
+            TestFlow,
+            (
+                constant(0.25).label(HealthScore),
+                target::<Health>().label(TotalHealthScore),
+            ),
+        );
+        let npc = world
+            .spawn((
+                EntityFlow::new(TestFlow),
+                ActionSelector::new(highest_score()),
+                Actions::new(TestAction::Eat)
+                    .with(HealthScore, TestAction::Eat)
+                    .with(TotalHealthScore, TestAction::Flee),
+                Health(10),
+            ))
+            .id();
+        let mut cursor = app.world().resource::<Events<ActionChanged>>().get_cursor();
+        let mut read = |app: &App| {
+            let events = app.world().resource::<Events<ActionChanged>>();
+            cursor.read(events).copied().collect::<Vec<_>>()
+        };
+
+        // Eat is already the current action, so nothing changes.
+        app.update();
+        app.update();
+        assert!(read(&app).is_empty());
+
+        app.world_mut().get_mut::<Health>(npc).unwrap().0 = 50;
+        app.update();
+        app.update();
+        assert_eq!(
+            read(&app),
+            [ActionChanged {
+                entity: npc,
+                from: TestAction::Eat.intern(),
+                to: TestAction::Flee.intern(),
+            }]
+        );
+    }
+
+    #[test]
+    fn fixed_timestep_scoring() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, UtilityAiPlugin::fixed()))
+            .insert_resource(Time::<Fixed>::from_seconds(0.1))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                25,
+            )))
+            .init_resource::<FixedSteps>()
+            .add_systems(FixedUpdate, |mut steps: ResMut<FixedSteps>| steps.0 += 1);
+
+        let world = app.world_mut();
+        world.add_nodes(
+            TestFlow,
+            CountingEvaluator(Arc::clone(&evaluations)).label(HealthScore),
+        );
+        let npc = world.spawn(EntityFlow::new(TestFlow)).id();
+
+        let frames = 20;
+        for _ in 0..frames {
+            app.update();
+        }
+
+        let steps = app.world().resource::<FixedSteps>().0;
+        assert!(steps > 0 && steps < frames, "ran {steps} fixed steps");
+        assert_eq!(evaluations.load(Ordering::Relaxed), steps);
+        assert_eq!(
+            app.world()
+                .get::<ComputedScores>(npc)
+                .unwrap()
+                .get(HealthScore),
+            Some(Score::MAX)
+        );
+    }
+}
@@ -1,15 +1,25 @@
 //! Provides the [`Score`] type for representing a score value in the range `[0, 1]`,
-//! and the [`Scoreable`] trait for converting values into scores.
+//! the [`Scoreable`] and [`ScoreableWith`] traits for converting values into
+//! scores, the [`InvertScore`] adapter for values where lower is better, and the
+//! [`ScoreSliceExt`] trait for arithmetic over slices of scores.
 
 use core::{
     cmp::Ordering,
     fmt,
+    marker::PhantomData,
     ops::{Add, Div, Mul, Sub},
 };
 
+use bevy_ecs::component::Component;
 use bevy_math::curve::Interval;
+use thiserror::Error;
 
 /// A score value in the range [0, 1]. Cannot be NaN.
+///
+/// The arithmetic operators saturate: results outside of `[0, 1]` are clamped
+/// to the nearest bound, and dividing by zero results in [`Score::MIN`]. Use
+/// [`Score::checked_add`] and [`Score::checked_sub`] to detect when a result
+/// would be out of range instead.
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Score {
     value: f32,
@@ -35,6 +45,62 @@ impl Score {
         }
     }
 
+    /// Creates a new score value, returning an error instead of clamping if
+    /// the value is outside of `[0, 1]` or NaN.
+    pub fn try_new(value: f32) -> Result<Self, ScoreError> {
+        if value.is_nan() {
+            Err(ScoreError::NaN)
+        } else if !(Self::MIN.get()..=Self::MAX.get()).contains(&value) {
+            Err(ScoreError::OutOfRange(value))
+        } else {
+            Ok(Self { value })
+        }
+    }
+
+    /// Creates a score from the ratio of `numerator` to `denominator`, such as
+    /// a current value to its maximum. Like dividing scores, a zero
+    /// denominator results in [`Score::MIN`].
+    pub fn from_ratio(numerator: f32, denominator: f32) -> Self {
+        if denominator == 0. {
+            Self::MIN
+        } else {
+            Self::new(numerator / denominator)
+        }
+    }
+
+    /// Creates a score from a percentage, where `100` is [`Score::MAX`].
+    pub fn from_percent(percent: f32) -> Self {
+        Self::new(percent / 100.)
+    }
+
+    /// Creates a new score value without clamping it, for hot paths where the
+    /// value is known to be in `[0, 1]`. The value is only checked in debug
+    /// builds.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the value is outside of `[0, 1]` or NaN.
+    #[inline(always)]
+    pub const fn new_unchecked(value: f32) -> Self {
+        debug_assert!(
+            value >= Self::MIN.get() && value <= Self::MAX.get(),
+            "Score value must be in the range [0, 1]"
+        );
+        Self { value }
+    }
+
+    /// Adds the given score, returning [`None`] if the result would be greater
+    /// than [`Score::MAX`] instead of saturating.
+    pub fn checked_add(self, rhs: impl Into<Score>) -> Option<Self> {
+        Self::try_new(self.value + rhs.into().value).ok()
+    }
+
+    /// Subtracts the given score, returning [`None`] if the result would be
+    /// less than [`Score::MIN`] instead of saturating.
+    pub fn checked_sub(self, rhs: impl Into<Score>) -> Option<Self> {
+        Self::try_new(self.value - rhs.into().value).ok()
+    }
+
     /// Returns the score value.
     #[inline(always)]
     pub const fn get(&self) -> f32 {
@@ -49,6 +115,35 @@ impl Score {
         }
         self.value = value.clamp(Self::MIN.get(), Self::MAX.get());
     }
+
+    /// Rounds the score to the nearest of `levels` evenly spaced values in
+    /// `[0, 1]`, rounding halfway values up.
+    ///
+    /// # Panics
+    ///
+    /// If `levels` is less than 2.
+    pub fn quantize(self, levels: usize) -> Self {
+        assert!(
+            levels >= 2,
+            "cannot quantize scores into fewer than 2 levels"
+        );
+        let steps = (levels - 1) as f32;
+        // Scores are never negative, so truncating after adding a half rounds
+        // to the nearest step.
+        Self::new((self.value * steps + 0.5) as u32 as f32 / steps)
+    }
+}
+
+/// Error type returned by [`Score::try_new`] when the value is not a valid
+/// score.
+#[derive(Error, Clone, Copy, PartialEq, Debug)]
+pub enum ScoreError {
+    /// The value is NaN.
+    #[error("The score value is NaN.")]
+    NaN,
+    /// The value is outside of `[0, 1]`.
+    #[error("The score value {0} is outside of the range [0, 1].")]
+    OutOfRange(f32),
 }
 
 impl From<f32> for Score {
@@ -212,3 +307,287 @@ impl<S: Scoreable> Scoreable for &S {
         (*self).score()
     }
 }
+
+/// Trait for types that describe how [`target`] scores a [`Component`] of the
+/// target entity. Implemented for every [`Scoreable`] component, which scores
+/// itself, and by adapters such as [`InvertScore`], which score another
+/// component differently.
+///
+/// [`target`]: crate::evaluator::target
+pub trait ScoreComponent: Send + Sync + 'static {
+    /// The component read from the target entity.
+    type Component: Component;
+
+    /// Scores the component.
+    fn score_component(component: &Self::Component) -> Score;
+}
+
+impl<C: Component + Scoreable> ScoreComponent for C {
+    type Component = C;
+
+    #[inline(always)]
+    fn score_component(component: &C) -> Score {
+        component.score()
+    }
+}
+
+/// Adapter that scores the inverse (`1 - score`) of the [`Scoreable`]
+/// [`Component`] `C`, for values where lower is better, such as a distance or
+/// the age of a threat. `target::<InvertScore<C>>()` reads `C` from the target
+/// entity like `target::<C>().invert()`, so the component itself stays
+/// unchanged for other systems.
+///
+/// [`target`]: crate::evaluator::target
+pub struct InvertScore<C>(PhantomData<C>);
+
+impl<C: Component + Scoreable> ScoreComponent for InvertScore<C> {
+    type Component = C;
+
+    #[inline(always)]
+    fn score_component(component: &C) -> Score {
+        Score::new(1. - component.score().get())
+    }
+}
+
+/// Trait for types that can be converted into a [`Score`] given some context,
+/// such as the bounds to normalize the value against.
+///
+/// This keeps normalization out of the type itself, e.g. a `Health` component
+/// can be scored against a companion `MaxHealth` component instead of a
+/// hardcoded maximum.
+pub trait ScoreableWith<Ctx: ?Sized> {
+    /// Convert the value into a [`Score`] using the given context.
+    fn score_with(&self, ctx: &Ctx) -> Score;
+}
+
+/// Extension methods for doing arithmetic over a slice of [`Score`]s.
+///
+/// Intermediate results are computed on the raw `f32` values, so they are not
+/// clamped to `[0, 1]` until they are converted back into a [`Score`].
+pub trait ScoreSliceExt {
+    /// Returns the unclamped sum of the scores, or `0.0` if the slice is empty.
+    fn sum_f32(&self) -> f32;
+
+    /// Returns the product of the scores, or `1.0` if the slice is empty.
+    fn product_f32(&self) -> f32;
+
+    /// Returns the arithmetic mean of the scores, or [`Score::MIN`] if the
+    /// slice is empty.
+    fn mean(&self) -> Score;
+
+    /// Returns the lowest score, or [`Score::MIN`] if the slice is empty.
+    fn min_score(&self) -> Score;
+
+    /// Returns the highest score, or [`Score::MIN`] if the slice is empty.
+    fn max_score(&self) -> Score;
+
+    /// Returns the index of the first lowest score, or [`None`] if the slice is
+    /// empty.
+    fn min_index(&self) -> Option<usize>;
+
+    /// Returns the index of the first highest score, or [`None`] if the slice
+    /// is empty.
+    fn max_index(&self) -> Option<usize>;
+}
+
+impl ScoreSliceExt for [Score] {
+    fn sum_f32(&self) -> f32 {
+        self.iter().map(Score::get).sum()
+    }
+
+    fn product_f32(&self) -> f32 {
+        self.iter().map(Score::get).product()
+    }
+
+    fn mean(&self) -> Score {
+        if self.is_empty() {
+            return Score::MIN;
+        }
+        Score::new(self.sum_f32() / self.len() as f32)
+    }
+
+    fn min_score(&self) -> Score {
+        self.iter().copied().min().unwrap_or(Score::MIN)
+    }
+
+    fn max_score(&self) -> Score {
+        self.iter().copied().max().unwrap_or(Score::MIN)
+    }
+
+    fn min_index(&self) -> Option<usize> {
+        let (index, _) = self.iter().enumerate().min_by_key(|&(_, &score)| score)?;
+        Some(index)
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        // `max_by_key` returns the last maximum, so search from the back.
+        let (index, _) = self
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, &score)| score)?;
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as evergreen_utility_ai,
+        macros::Scoreable,
+        score::{Score, ScoreError, ScoreSliceExt, Scoreable},
+    };
+
+    const SCORES: [Score; 3] = [Score::new(0.6), Score::new(0.8), Score::new(0.5)];
+    const EMPTY: [Score; 0] = [];
+
+    #[test]
+    fn try_new() {
+        assert_eq!(Score::try_new(0.0), Ok(Score::MIN));
+        assert_eq!(Score::try_new(0.4), Ok(Score::new(0.4)));
+        assert_eq!(Score::try_new(1.0), Ok(Score::MAX));
+        assert_eq!(Score::try_new(5.0), Err(ScoreError::OutOfRange(5.0)));
+        assert_eq!(Score::try_new(-0.1), Err(ScoreError::OutOfRange(-0.1)));
+        assert_eq!(
+            Score::try_new(f32::INFINITY),
+            Err(ScoreError::OutOfRange(f32::INFINITY))
+        );
+        assert_eq!(Score::try_new(f32::NAN), Err(ScoreError::NaN));
+    }
+
+    #[test]
+    fn derive_scoreable() {
+        #[derive(Scoreable)]
+        #[score(max = 100)]
+        struct Stamina(u8);
+
+        #[derive(Scoreable)]
+        #[score(max = 50.0, invert)]
+        struct Hunger(f32);
+
+        #[derive(Scoreable)]
+        #[score(min = 10, max = 20, field = celsius)]
+        struct Temperature {
+            celsius: i32,
+            #[allow(dead_code)]
+            unit: char,
+        }
+
+        assert_eq!(Stamina(25).score(), Score::new(0.25));
+        assert_eq!(Stamina(250).score(), Score::MAX);
+        assert_eq!(Hunger(10.).score(), Score::new(0.8));
+        assert_eq!(Hunger(60.).score(), Score::MIN);
+        let temperature = |celsius| Temperature { celsius, unit: 'C' }.score();
+        assert_eq!(temperature(15), Score::new(0.5));
+        assert_eq!(temperature(0), Score::MIN);
+    }
+
+    #[test]
+    fn from_ratio_and_percent() {
+        assert_eq!(Score::from_ratio(3., 4.), Score::new(0.75));
+        assert_eq!(Score::from_ratio(5., 4.), Score::MAX);
+        assert_eq!(Score::from_ratio(-1., 4.), Score::MIN);
+        assert_eq!(Score::from_ratio(3., 0.), Score::MIN);
+        assert_eq!(Score::from_ratio(0., 0.), Score::MIN);
+        assert_eq!(Score::from_percent(25.), Score::new(0.25));
+        assert_eq!(Score::from_percent(150.), Score::MAX);
+        assert_eq!(Score::from_percent(-10.), Score::MIN);
+    }
+
+    #[test]
+    fn new_unchecked() {
+        assert_eq!(Score::new_unchecked(0.4), Score::new(0.4));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn new_unchecked_out_of_range() {
+        let _ = Score::new_unchecked(5.0);
+    }
+
+    #[test]
+    fn saturating_arithmetic() {
+        assert_eq!(Score::new(0.75) + Score::new(0.5), Score::MAX);
+        assert_eq!(Score::new(0.25) - Score::new(0.5), Score::MIN);
+        assert_eq!(Score::new(0.5) / Score::MIN, Score::MIN);
+        assert_eq!(Score::new(0.5) / 0.25, Score::MAX);
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let a = Score::new(0.75);
+        assert_eq!(a.checked_add(0.25), Some(Score::MAX));
+        assert_eq!(a.checked_add(Score::new(0.5)), None);
+        assert_eq!(a.checked_sub(0.5), Some(Score::new(0.25)));
+        assert_eq!(a.checked_sub(a), Some(Score::MIN));
+        assert_eq!(Score::new(0.25).checked_sub(0.5), None);
+    }
+
+    #[test]
+    fn quantize() {
+        let quantize = |score: f32, levels| Score::new(score).quantize(levels).get();
+        assert_eq!(quantize(0.2, 2), 0.0);
+        assert_eq!(quantize(0.5, 2), 1.0);
+        assert_eq!(quantize(0.2, 3), 0.0);
+        assert_eq!(quantize(0.3, 3), 0.5);
+        assert_eq!(quantize(0.74, 3), 0.5);
+        assert_eq!(quantize(0.75, 3), 1.0);
+        assert_eq!(quantize(0.0, 5), 0.0);
+        assert_eq!(quantize(0.4, 5), 0.5);
+        assert_eq!(quantize(1.0, 5), 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn quantize_single_level() {
+        Score::MAX.quantize(1);
+    }
+
+    #[test]
+    fn slice_sum() {
+        assert!((SCORES.sum_f32() - 1.9).abs() < 1e-6);
+        assert_eq!(EMPTY.sum_f32(), 0.0);
+    }
+
+    #[test]
+    fn slice_product() {
+        assert!((SCORES.product_f32() - 0.24).abs() < 1e-6);
+        assert_eq!(EMPTY.product_f32(), 1.0);
+    }
+
+    #[test]
+    fn slice_mean() {
+        // The sum exceeds 1.0, so it must not be clamped before dividing.
+        assert!((SCORES.mean().get() - 1.9 / 3.0).abs() < 1e-6);
+        assert_eq!(EMPTY.mean(), Score::MIN);
+    }
+
+    #[test]
+    fn slice_min_score() {
+        assert_eq!(SCORES.min_score(), Score::new(0.5));
+        assert_eq!(EMPTY.min_score(), Score::MIN);
+    }
+
+    #[test]
+    fn slice_max_score() {
+        assert_eq!(SCORES.max_score(), Score::new(0.8));
+        assert_eq!(EMPTY.max_score(), Score::MIN);
+    }
+
+    #[test]
+    fn slice_min_max_index() {
+        let tied = [
+            Score::new(0.2),
+            Score::new(0.9),
+            Score::new(0.2),
+            Score::new(0.9),
+        ];
+        assert_eq!(SCORES.min_index(), Some(2));
+        assert_eq!(SCORES.max_index(), Some(1));
+        assert_eq!(tied.min_index(), Some(0));
+        assert_eq!(tied.max_index(), Some(1));
+        assert_eq!(EMPTY.min_index(), None);
+        assert_eq!(EMPTY.max_index(), None);
+    }
+}
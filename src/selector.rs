@@ -1,17 +1,31 @@
 //! Provides the [`Selector`] trait for selecting actions based on computed scores.
 
-use alloc::{borrow::Cow, boxed::Box};
+use alloc::{borrow::Cow, boxed::Box, format};
 
-use bevy_ecs::{entity::Entity, system::SystemInput, world::World};
+use bevy_ecs::{component::Tick, entity::Entity, system::SystemInput, world::World};
+use smallvec::SmallVec;
 
 use crate::{
     component::{Actions, ComputedScores},
     label::InternedActionLabel,
+    score::Score,
 };
 
+mod constant;
+#[cfg(feature = "bevy_time")]
+mod cooldown;
+mod highest_score;
 mod system;
+mod targeted;
+mod top_n;
 
+pub use constant::*;
+#[cfg(feature = "bevy_time")]
+pub use cooldown::*;
+pub use highest_score::*;
 pub use system::*;
+pub use targeted::*;
+pub use top_n::*;
 
 /// Trait for types that select an action based on computed scores.
 pub trait Selector: Send + Sync + 'static {
@@ -23,6 +37,13 @@ pub trait Selector: Send + Sync + 'static {
         let _ = world;
     }
 
+    /// Checks the change ticks stored by the selector, such as the last run
+    /// tick of a system, and clamps them to prevent change detection false
+    /// positives when the world's change tick overflows.
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        let _ = change_tick;
+    }
+
     /// Selects an action label for the given selection context.
     fn select(&mut self, ctx: SelectionCtx) -> Option<InternedActionLabel>;
 }
@@ -30,6 +51,38 @@ pub trait Selector: Send + Sync + 'static {
 /// Verifies that [`Selector`] is dyn-compatible.
 const _: Option<Box<dyn Selector>> = None;
 
+/// Trait for selectors that select an action through a shared reference, so
+/// that a single instance can be shared by many entities and run in parallel
+/// without locking, such as with [`ActionSelector::stateless`]. Any state they
+/// keep must be synchronized internally, and is shared by all entities using
+/// the selector, so it is updated in whatever order the entities happen to be
+/// run in parallel. For example, the pseudo-random generator of
+/// [`TieBreak::Random`] is not deterministic when shared.
+///
+/// All stateless selectors are also [`Selector`]s.
+///
+/// [`ActionSelector::stateless`]: crate::component::ActionSelector::stateless
+pub trait StatelessSelector: Send + Sync + 'static {
+    /// Returns the name of the selector.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Selects an action label for the given selection context.
+    fn select(&self, ctx: SelectionCtx) -> Option<InternedActionLabel>;
+}
+
+/// Verifies that [`StatelessSelector`] is dyn-compatible.
+const _: Option<Box<dyn StatelessSelector>> = None;
+
+impl<S: StatelessSelector> Selector for S {
+    fn name(&self) -> Cow<'static, str> {
+        StatelessSelector::name(self)
+    }
+
+    fn select(&mut self, ctx: SelectionCtx) -> Option<InternedActionLabel> {
+        StatelessSelector::select(self, ctx)
+    }
+}
+
 /// Trait for types that can be converted into a [`Selector`].
 pub trait IntoSelector<Marker>: Sized {
     /// The type of [`Selector`] that this value will be converted into.
@@ -37,6 +90,100 @@ pub trait IntoSelector<Marker>: Sized {
 
     /// Converts this value into a [`Selector`].
     fn into_selector(self) -> Self::Selector;
+
+    /// Falls back to the other selector when this selector selects no action.
+    fn or<M>(self, other: impl IntoSelector<M>) -> impl Selector {
+        struct OrSelector<A, B> {
+            selector: A,
+            fallback: B,
+        }
+
+        impl<A, B> Selector for OrSelector<A, B>
+        where
+            A: Selector,
+            B: Selector,
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!(
+                    "{}.or({})",
+                    self.selector.name(),
+                    self.fallback.name()
+                ))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.selector.initialize(world);
+                self.fallback.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.selector.check_change_tick(change_tick);
+                self.fallback.check_change_tick(change_tick);
+            }
+
+            fn select(&mut self, ctx: SelectionCtx) -> Option<InternedActionLabel> {
+                self.selector
+                    .select(ctx)
+                    .or_else(|| self.fallback.select(ctx))
+            }
+        }
+
+        OrSelector {
+            selector: self.into_selector(),
+            fallback: other.into_selector(),
+        }
+    }
+
+    /// Discards the action selected by this selector, selecting no action
+    /// instead, when the given predicate returns `false` for it. The predicate
+    /// receives the selected action and its score, which is the highest
+    /// computed score mapped to that action, or [`Score::MIN`] if there is
+    /// none, such as for the default action.
+    fn filter(
+        self,
+        predicate: impl Fn(InternedActionLabel, Score) -> bool + Send + Sync + 'static,
+    ) -> impl Selector {
+        struct FilterSelector<S, F> {
+            selector: S,
+            predicate: F,
+        }
+
+        impl<S, F> Selector for FilterSelector<S, F>
+        where
+            S: Selector,
+            F: Fn(InternedActionLabel, Score) -> bool + Send + Sync + 'static,
+        {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Owned(format!("{}.filter()", self.selector.name()))
+            }
+
+            fn initialize(&mut self, world: &mut World) {
+                self.selector.initialize(world);
+            }
+
+            fn check_change_tick(&mut self, change_tick: Tick) {
+                self.selector.check_change_tick(change_tick);
+            }
+
+            fn select(&mut self, ctx: SelectionCtx) -> Option<InternedActionLabel> {
+                let selection = ctx.selection;
+                let action = self.selector.select(ctx)?;
+                let score = selection
+                    .actions
+                    .iter()
+                    .filter(|&(_, mapped)| mapped == action)
+                    .filter_map(|(score, _)| selection.scores.get(score))
+                    .max()
+                    .unwrap_or(Score::MIN);
+                (self.predicate)(action, score).then_some(action)
+            }
+        }
+
+        FilterSelector {
+            selector: self.into_selector(),
+            predicate,
+        }
+    }
 }
 
 /// All [`Selector`]s can be converted into themselves.
@@ -48,7 +195,59 @@ impl<S: Selector> IntoSelector<()> for S {
     }
 }
 
+/// Trait for types that select any number of actions based on computed scores,
+/// for agents that can perform several actions concurrently.
+pub trait MultiSelector: Send + Sync + 'static {
+    /// Returns the name of the selector.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Initializes the selector using the given world.
+    fn initialize(&mut self, world: &mut World) {
+        let _ = world;
+    }
+
+    /// Checks the change ticks stored by the selector, such as the last run
+    /// tick of a system, and clamps them to prevent change detection false
+    /// positives when the world's change tick overflows.
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        let _ = change_tick;
+    }
+
+    /// Selects action labels for the given selection context.
+    fn select_many(&mut self, ctx: SelectionCtx) -> SmallVec<[InternedActionLabel; 4]>;
+}
+
+/// Verifies that [`MultiSelector`] is dyn-compatible.
+const _: Option<Box<dyn MultiSelector>> = None;
+
+/// Trait for types that select an action along with the entity it should
+/// target, such as which enemy to attack, based on computed scores.
+pub trait TargetedSelector: Send + Sync + 'static {
+    /// Returns the name of the selector.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Initializes the selector using the given world.
+    fn initialize(&mut self, world: &mut World) {
+        let _ = world;
+    }
+
+    /// Checks the change ticks stored by the selector, such as the last run
+    /// tick of a system, and clamps them to prevent change detection false
+    /// positives when the world's change tick overflows.
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        let _ = change_tick;
+    }
+
+    /// Selects an action label and target entity for the given selection
+    /// context.
+    fn select_targeted(&mut self, ctx: SelectionCtx) -> Option<(InternedActionLabel, Entity)>;
+}
+
+/// Verifies that [`TargetedSelector`] is dyn-compatible.
+const _: Option<Box<dyn TargetedSelector>> = None;
+
 /// The context passed to [`Selector`]s when selecting an action.
+#[derive(Clone, Copy)]
 pub struct SelectionCtx<'w, 's> {
     /// The world state.
     pub world: &'w World,
@@ -57,6 +256,7 @@ pub struct SelectionCtx<'w, 's> {
 }
 
 /// [`SystemInput`] type for [`Selector`] systems.
+#[derive(Clone, Copy)]
 pub struct Selection<'s> {
     /// The entity that is being selected for.
     pub target: Entity,
@@ -74,3 +274,437 @@ impl SystemInput for Selection<'_> {
         this
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use bevy_ecs::{
+        entity::Entity,
+        system::{Query, RunSystemOnce},
+        world::World,
+    };
+
+    use crate::{
+        self as evergreen_utility_ai,
+        component::{Actions, ComputedScores},
+        evaluator::{target, IntoEvaluator},
+        flow::WorldFlowExt,
+        label::ActionLabel,
+        macros::{ActionLabel, ScoreLabel},
+        score::Score,
+        selector::{
+            constant_action, highest_score, highest_target_score, never, top_n_actions,
+            IntoSelector, MultiSelector, Selection, SelectionCtx, Selector, TargetedSelector,
+            TieBreak,
+        },
+        tests::{Fuel, Health, TestFlow},
+    };
+
+    #[derive(ScoreLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    struct TestScore(u8);
+
+    #[derive(ActionLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    enum TestAction {
+        Idle,
+        Eat,
+        Flee,
+        Sleep,
+    }
+
+    /// Scores `Flee` and `Eat` equally, above `Sleep`.
+    fn tied() -> (ComputedScores, Actions) {
+        let mut scores = ComputedScores::default();
+        scores.insert(TestScore(0), Score::new(0.8));
+        scores.insert(TestScore(1), Score::new(0.8));
+        scores.insert(TestScore(2), Score::new(0.3));
+
+        let actions = Actions::new(TestAction::Idle)
+            .with(TestScore(0), TestAction::Flee)
+            .with(TestScore(1), TestAction::Eat)
+            .with(TestScore(2), TestAction::Sleep);
+
+        (scores, actions)
+    }
+
+    fn select(
+        selector: &mut impl Selector,
+        scores: &ComputedScores,
+        actions: &Actions,
+    ) -> Option<TestAction> {
+        let world = World::new();
+        let selected = selector.select(SelectionCtx {
+            world: &world,
+            selection: Selection {
+                target: Entity::PLACEHOLDER,
+                scores,
+                actions,
+            },
+        })?;
+
+        [
+            TestAction::Idle,
+            TestAction::Eat,
+            TestAction::Flee,
+            TestAction::Sleep,
+        ]
+        .into_iter()
+        .find(|action| action.intern() == selected)
+    }
+
+    #[test]
+    fn highest_score_selector() {
+        let mut scores = ComputedScores::default();
+        scores.insert(TestScore(0), Score::new(0.2));
+        scores.insert(TestScore(1), Score::new(0.6));
+        let actions = Actions::new(TestAction::Idle)
+            .with(TestScore(0), TestAction::Flee)
+            .with(TestScore(1), TestAction::Eat);
+
+        let mut selector = highest_score();
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Eat)
+        );
+
+        let empty = ComputedScores::default();
+        assert_eq!(
+            select(&mut selector, &empty, &actions),
+            Some(TestAction::Idle)
+        );
+    }
+
+    #[test]
+    fn top_n_actions_selector() {
+        let (scores, actions) = tied();
+        let world = World::new();
+
+        let select_many = |n| {
+            top_n_actions(n).select_many(SelectionCtx {
+                world: &world,
+                selection: Selection {
+                    target: Entity::PLACEHOLDER,
+                    scores: &scores,
+                    actions: &actions,
+                },
+            })
+        };
+
+        assert_eq!(
+            select_many(2).as_slice(),
+            [TestAction::Flee.intern(), TestAction::Eat.intern()]
+        );
+        // Asking for more actions than are mapped returns all scored actions.
+        assert_eq!(
+            select_many(10).as_slice(),
+            [
+                TestAction::Flee.intern(),
+                TestAction::Eat.intern(),
+                TestAction::Sleep.intern()
+            ]
+        );
+        assert!(select_many(0).is_empty());
+    }
+
+    #[test]
+    fn top_n_actions_shared_action() {
+        let mut scores = ComputedScores::default();
+        scores.insert(TestScore(0), Score::new(0.8));
+        scores.insert(TestScore(1), Score::new(0.9));
+        scores.insert(TestScore(2), Score::new(0.3));
+        scores.insert(TestScore(3), Score::new(0.7));
+        let actions = Actions::new(TestAction::Idle)
+            .with(TestScore(0), TestAction::Eat)
+            .with(TestScore(1), TestAction::Eat)
+            .with(TestScore(2), TestAction::Sleep)
+            .with(TestScore(3), TestAction::Flee);
+
+        // Eat is ranked by its higher score, and selected only once.
+        let selected = top_n_actions(2).select_many(SelectionCtx {
+            world: &World::new(),
+            selection: Selection {
+                target: Entity::PLACEHOLDER,
+                scores: &scores,
+                actions: &actions,
+            },
+        });
+        assert_eq!(
+            selected.as_slice(),
+            [TestAction::Eat.intern(), TestAction::Flee.intern()]
+        );
+    }
+
+    #[test]
+    fn highest_score_tie_break_first_inserted() {
+        let (scores, actions) = tied();
+
+        let mut selector = highest_score().tie_break(TieBreak::FirstInserted);
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Flee)
+        );
+    }
+
+    #[test]
+    fn highest_score_tie_break_action_label() {
+        let (scores, actions) = tied();
+
+        let mut selector = highest_score().tie_break(TieBreak::LowestActionLabel);
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Eat)
+        );
+
+        let mut selector = highest_score().tie_break(TieBreak::HighestActionLabel);
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Flee)
+        );
+    }
+
+    #[test]
+    fn highest_score_tie_break_keep_current() {
+        let (scores, mut actions) = tied();
+        let mut selector = highest_score().tie_break(TieBreak::KeepCurrent);
+
+        // The current action is not tied, so the first inserted tie wins.
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Flee)
+        );
+
+        actions.set_current(TestAction::Eat);
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Eat)
+        );
+    }
+
+    #[test]
+    fn highest_score_tie_break_random() {
+        let (scores, actions) = tied();
+
+        let mut a = highest_score().tie_break(TieBreak::Random(42));
+        let mut b = highest_score().tie_break(TieBreak::Random(42));
+        for _ in 0..16 {
+            let selected = select(&mut a, &scores, &actions);
+            assert!(matches!(selected, Some(TestAction::Flee | TestAction::Eat)));
+            assert_eq!(selected, select(&mut b, &scores, &actions));
+        }
+    }
+
+    #[test]
+    fn constant_selectors() {
+        let (scores, actions) = tied();
+        let empty = ComputedScores::default();
+
+        let mut selector = constant_action(TestAction::Sleep);
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Sleep)
+        );
+        assert_eq!(
+            select(&mut selector, &empty, &actions),
+            Some(TestAction::Sleep)
+        );
+
+        let mut selector = never();
+        assert_eq!(select(&mut selector, &scores, &actions), None);
+        assert_eq!(select(&mut selector, &empty, &actions), None);
+
+        let mut selector = never().or(constant_action(TestAction::Eat));
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Eat)
+        );
+    }
+
+    #[test]
+    fn filter_or_selector() {
+        let (scores, actions) = tied();
+
+        let mut selector = highest_score().filter(|_, score| score >= 0.5);
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Flee)
+        );
+
+        // The restrictive selector rejects every action, so the fallback runs.
+        let mut selector = highest_score()
+            .filter(|_, score| score >= 0.9)
+            .or(highest_score().tie_break(TieBreak::LowestActionLabel));
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Eat)
+        );
+
+        let mut selector = highest_score().filter(|action, _| action != TestAction::Flee.intern());
+        assert_eq!(select(&mut selector, &scores, &actions), None);
+        let mut selector = selector.or(highest_score());
+        assert_eq!(
+            select(&mut selector, &scores, &actions),
+            Some(TestAction::Flee)
+        );
+
+        // The default action has no score of its own.
+        let empty = ComputedScores::default();
+        let mut selector = highest_score().filter(|_, score| score > Score::MIN);
+        assert_eq!(select(&mut selector, &empty, &actions), None);
+    }
+
+    #[cfg(feature = "bevy_time")]
+    #[test]
+    fn cooldown_selector() {
+        use core::time::Duration;
+
+        use bevy::time::Time;
+
+        use crate::{
+            component::{run_action_selectors, ActionCooldowns, ActionSelector},
+            selector::cooldown,
+        };
+
+        let mut world = World::new();
+        world.init_resource::<Time>();
+
+        let (mut scores, actions) = tied();
+        scores.insert(TestScore(1), Score::new(0.9));
+        let npc = world
+            .spawn((
+                ActionSelector::new(cooldown(highest_score())),
+                ActionCooldowns::default().with(TestAction::Eat, Duration::from_secs(1)),
+                scores,
+                actions,
+            ))
+            .id();
+        let tick = |world: &mut World, secs: f32| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(secs));
+            world.run_system_cached(run_action_selectors).unwrap();
+            world.get::<Actions>(npc).unwrap().current().intern()
+        };
+
+        assert_eq!(tick(&mut world, 0.0), TestAction::Eat.intern());
+        // Eat is cooling down, so the next best action is picked.
+        assert_eq!(tick(&mut world, 0.5), TestAction::Flee.intern());
+        assert_eq!(tick(&mut world, 0.25), TestAction::Flee.intern());
+        assert_eq!(
+            world
+                .get::<ActionCooldowns>(npc)
+                .unwrap()
+                .remaining(TestAction::Eat, Duration::from_secs_f32(0.75)),
+            Duration::from_secs_f32(0.25)
+        );
+        // After the cooldown elapses, Eat is eligible again.
+        assert_eq!(tick(&mut world, 0.25), TestAction::Eat.intern());
+        assert_eq!(tick(&mut world, 0.1), TestAction::Flee.intern());
+    }
+
+    #[test]
+    fn highest_target_score_selector() {
+        let mut world = World::new();
+        world.add_nodes(
+            TestFlow,
+            (
+                target::<Health>().invert().label(TestScore(0)),
+                target::<Fuel>().label(TestScore(1)),
+            ),
+        );
+        let healthy = world.spawn((Health(90), Fuel(20))).id();
+        let wounded = world.spawn((Health(30), Fuel(10))).id();
+        let fueled = world.spawn((Health(80), Fuel(50))).id();
+        let healer = world.spawn_empty().id();
+
+        let actions = Actions::new(TestAction::Idle)
+            .with(TestScore(0), TestAction::Eat)
+            .with(TestScore(1), TestAction::Flee);
+        let scores = ComputedScores::default();
+        let select = |selector: &mut dyn TargetedSelector, world: &World| {
+            selector.select_targeted(SelectionCtx {
+                world,
+                selection: Selection {
+                    target: healer,
+                    scores: &scores,
+                    actions: &actions,
+                },
+            })
+        };
+
+        let mut selector = highest_target_score(TestFlow, move |_, entity| {
+            assert_eq!(entity, healer);
+            Vec::from([healthy, wounded, fueled])
+        });
+        selector.initialize(&mut world);
+
+        assert_eq!(
+            select(&mut selector, &world),
+            Some((TestAction::Eat.intern(), wounded))
+        );
+        world.entity_mut(wounded).insert(Health(100));
+        assert_eq!(
+            select(&mut selector, &world),
+            Some((TestAction::Flee.intern(), fueled))
+        );
+        for entity in [healthy, wounded, fueled] {
+            world.entity_mut(entity).insert(Fuel(0));
+        }
+        assert_eq!(
+            select(&mut selector, &world),
+            Some((TestAction::Eat.intern(), fueled))
+        );
+
+        let mut selector = highest_target_score(TestFlow, |_, _| Vec::new());
+        selector.initialize(&mut world);
+        assert_eq!(select(&mut selector, &world), None);
+    }
+
+    #[test]
+    fn targeted_selector_in_system() {
+        let mut world = World::new();
+        world.add_nodes(TestFlow, target::<Health>().invert().label(TestScore(0)));
+        world.spawn(Health(90));
+        let wounded = world.spawn(Health(30)).id();
+        let actions = || Actions::new(TestAction::Idle).with(TestScore(0), TestAction::Eat);
+        let healers = [
+            world.spawn((ComputedScores::default(), actions())).id(),
+            world.spawn((ComputedScores::default(), actions())).id(),
+        ];
+
+        let mut selector = highest_target_score(TestFlow, |world, _| {
+            world
+                .iter_entities()
+                .filter(|entity| entity.contains::<Health>())
+                .map(|entity| entity.id())
+                .collect()
+        });
+        selector.initialize(&mut world);
+
+        let selected = world
+            .run_system_once(
+                move |world: &World, healers: Query<(Entity, &ComputedScores, &Actions)>| {
+                    healers
+                        .iter()
+                        .map(|(target, scores, actions)| {
+                            let selection = Selection {
+                                target,
+                                scores,
+                                actions,
+                            };
+                            (
+                                target,
+                                selector.select_targeted(SelectionCtx { world, selection }),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(selected.len(), 2);
+        for (healer, selection) in selected {
+            assert!(healers.contains(&healer));
+            assert_eq!(selection, Some((TestAction::Eat.intern(), wounded)));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use alloc::{borrow::Cow, format};
+
+use crate::{
+    label::{ActionLabel, InternedActionLabel},
+    selector::{SelectionCtx, StatelessSelector},
+};
+
+/// Creates a [`StatelessSelector`] that always selects the given action,
+/// ignoring the computed scores.
+pub fn constant_action(label: impl ActionLabel) -> impl StatelessSelector {
+    ConstantActionSelector(label.intern())
+}
+
+/// Creates a [`StatelessSelector`] that never selects an action. This is
+/// useful to end a chain of [`IntoSelector::or`] fallbacks.
+///
+/// [`IntoSelector::or`]: crate::selector::IntoSelector::or
+pub fn never() -> impl StatelessSelector {
+    NeverSelector
+}
+
+struct ConstantActionSelector(InternedActionLabel);
+
+impl StatelessSelector for ConstantActionSelector {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("constant_action({:?})", self.0))
+    }
+
+    fn select(&self, _ctx: SelectionCtx) -> Option<InternedActionLabel> {
+        Some(self.0)
+    }
+}
+
+struct NeverSelector;
+
+impl StatelessSelector for NeverSelector {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("never")
+    }
+
+    fn select(&self, _ctx: SelectionCtx) -> Option<InternedActionLabel> {
+        None
+    }
+}
@@ -0,0 +1,61 @@
+use alloc::{borrow::Cow, format};
+
+use bevy_ecs::{component::Tick, world::World};
+use bevy_time::Time;
+
+use crate::{
+    component::ActionCooldowns,
+    label::InternedActionLabel,
+    selector::{IntoSelector, Selection, SelectionCtx, Selector},
+};
+
+/// Creates a [`Selector`] that hides the actions still cooling down in the
+/// target's [`ActionCooldowns`] from the given selector. Cooldowns are measured
+/// against the elapsed time of the [`Time`] resource. If the target has no
+/// [`ActionCooldowns`] or there is no [`Time`] resource, all actions are
+/// eligible.
+pub fn cooldown<M>(selector: impl IntoSelector<M>) -> impl Selector {
+    CooldownSelector(selector.into_selector())
+}
+
+struct CooldownSelector<S>(S);
+
+impl<S: Selector> Selector for CooldownSelector<S> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("cooldown({})", self.0.name()))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.0.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.0.check_change_tick(change_tick);
+    }
+
+    fn select(&mut self, ctx: SelectionCtx) -> Option<InternedActionLabel> {
+        let selection = ctx.selection;
+        let (Some(cooldowns), Some(time)) = (
+            ctx.world.get::<ActionCooldowns>(selection.target),
+            ctx.world.get_resource::<Time>(),
+        ) else {
+            return self.0.select(SelectionCtx {
+                world: ctx.world,
+                selection,
+            });
+        };
+
+        let now = time.elapsed();
+        let actions = selection
+            .actions
+            .filtered(|action| cooldowns.is_ready(action, now));
+        self.0.select(SelectionCtx {
+            world: ctx.world,
+            selection: Selection {
+                target: selection.target,
+                scores: selection.scores,
+                actions: &actions,
+            },
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use alloc::{borrow::Cow, format};
+use core::{
+    cmp::Ordering,
+    sync::atomic::{self, AtomicU64},
+};
+
+use smallvec::SmallVec;
+
+use crate::{
+    label::{ActionLabel, InternedActionLabel},
+    score::Score,
+    selector::{SelectionCtx, StatelessSelector},
+};
+
+/// Creates a [`StatelessSelector`] that selects the action associated with the
+/// highest computed score. Ties are resolved using [`TieBreak::FirstInserted`]
+/// unless configured otherwise with [`HighestScoreSelector::tie_break`]. If
+/// none of the actions have a score above [`Score::MIN`], the default action
+/// is selected.
+pub fn highest_score() -> HighestScoreSelector {
+    HighestScoreSelector {
+        tie_break: TieBreak::default(),
+        rng: AtomicU64::new(0),
+    }
+}
+
+/// Strategy used by [`highest_score`] to pick between actions that share the
+/// highest score.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TieBreak {
+    /// Picks the tied action that was added to the [`Actions`] first.
+    ///
+    /// [`Actions`]: crate::component::Actions
+    #[default]
+    FirstInserted,
+    /// Picks the tied action whose [`ActionLabel`] has the lowest `Debug`
+    /// representation.
+    LowestActionLabel,
+    /// Picks the tied action whose [`ActionLabel`] has the highest `Debug`
+    /// representation.
+    HighestActionLabel,
+    /// Keeps the current action if it is one of the tied actions, otherwise
+    /// falls back to [`TieBreak::FirstInserted`].
+    KeepCurrent,
+    /// Picks a pseudo-random tied action. The sequence of choices is fully
+    /// determined by the seed, as long as the selector is not run for several
+    /// entities in parallel.
+    ///
+    /// The generator state is a single atomic shared by every entity using
+    /// the same selector, such as one shared with
+    /// [`ActionSelector::stateless`]. When those entities are run in parallel,
+    /// they contend for the atomic, and which entity gets which choice depends
+    /// on scheduling, so the choices are not reproducible. Give each entity its
+    /// own selector to keep them deterministic.
+    ///
+    /// [`ActionSelector::stateless`]: crate::component::ActionSelector::stateless
+    Random(u64),
+}
+
+/// [`StatelessSelector`] returned by [`highest_score`].
+pub struct HighestScoreSelector {
+    tie_break: TieBreak,
+    /// State of the pseudo-random generator used by [`TieBreak::Random`].
+    rng: AtomicU64,
+}
+
+impl HighestScoreSelector {
+    /// Sets the strategy used to pick between actions sharing the highest
+    /// score.
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        if let TieBreak::Random(seed) = tie_break {
+            *self.rng.get_mut() = seed;
+        }
+        self
+    }
+
+    /// Returns the next value of the SplitMix64 sequence.
+    fn next_random(&self) -> u64 {
+        const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut z = self
+            .rng
+            .fetch_add(GAMMA, atomic::Ordering::Relaxed)
+            .wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl StatelessSelector for HighestScoreSelector {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("highest_score({:?})", self.tie_break))
+    }
+
+    fn select(&self, ctx: SelectionCtx) -> Option<InternedActionLabel> {
+        let selection = ctx.selection;
+
+        let mut best = Score::MIN;
+        let mut tied = SmallVec::<[InternedActionLabel; 4]>::new();
+        for (score, action) in selection.actions.iter() {
+            let Some(score) = selection.scores.get(score) else {
+                continue;
+            };
+            match score.cmp(&best) {
+                Ordering::Greater => {
+                    best = score;
+                    tied.clear();
+                    tied.push(action);
+                }
+                Ordering::Equal if best > Score::MIN => tied.push(action),
+                _ => {}
+            }
+        }
+
+        let action = match (self.tie_break, tied.as_slice()) {
+            (_, []) => selection.actions.default().intern(),
+            (_, [action]) => *action,
+            (TieBreak::FirstInserted, [first, ..]) => *first,
+            (TieBreak::LowestActionLabel, _) => {
+                *tied.iter().min_by_key(|action| format!("{action:?}"))?
+            }
+            (TieBreak::HighestActionLabel, _) => {
+                *tied.iter().max_by_key(|action| format!("{action:?}"))?
+            }
+            (TieBreak::KeepCurrent, [first, ..]) => {
+                let current = selection.actions.current().intern();
+                if tied.contains(&current) {
+                    current
+                } else {
+                    *first
+                }
+            }
+            (TieBreak::Random(_), _) => {
+                let index = self.next_random() % tied.len() as u64;
+                tied[index as usize]
+            }
+        };
+
+        Some(action)
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::{borrow::Cow, format, vec::Vec};
+
+use bevy_ecs::{entity::Entity, world::World};
+
+use crate::{
+    flow::WorldFlowExt,
+    label::{FlowLabel, InternedActionLabel, InternedFlowLabel},
+    score::Score,
+    selector::{SelectionCtx, TargetedSelector},
+};
+
+/// Creates a [`TargetedSelector`] that runs the [`Flow`] with the given label
+/// on each candidate entity returned by `candidates` for the selecting
+/// entity, and selects the action and candidate with the highest score, using
+/// the selecting entity's [`Actions`] to map scores to actions. For example,
+/// a healer's candidates could be its allies, scored by how much they need
+/// healing.
+///
+/// Scores of candidates are computed when selecting and are not stored in
+/// their [`ComputedScores`]. Ties are resolved in favor of the first candidate,
+/// then the first inserted action. If no candidate has a score above
+/// [`Score::MIN`] for any mapped action, or the flow does not exist, nothing is
+/// selected. The flow is initialized along with this selector.
+///
+/// [`Flow`]: crate::flow::Flow
+/// [`Actions`]: crate::component::Actions
+/// [`ComputedScores`]: crate::component::ComputedScores
+pub fn highest_target_score(
+    flow: impl FlowLabel,
+    candidates: impl Fn(&World, Entity) -> Vec<Entity> + Send + Sync + 'static,
+) -> impl TargetedSelector {
+    HighestTargetScoreSelector {
+        flow: flow.intern(),
+        candidates,
+    }
+}
+
+struct HighestTargetScoreSelector<F> {
+    flow: InternedFlowLabel,
+    candidates: F,
+}
+
+impl<F> TargetedSelector for HighestTargetScoreSelector<F>
+where
+    F: Fn(&World, Entity) -> Vec<Entity> + Send + Sync + 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("highest_target_score({:?})", self.flow))
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        world
+            .try_flow_scope(self.flow, |world, flow| flow.initialize(world))
+            .ok();
+    }
+
+    fn select_targeted(&mut self, ctx: SelectionCtx) -> Option<(InternedActionLabel, Entity)> {
+        let flow = ctx
+            .world
+            .get_flow(self.flow)
+            .filter(|flow| flow.is_initialized())?;
+        let actions = ctx.selection.actions;
+
+        let mut best = None;
+        let mut best_score = Score::MIN;
+        for candidate in (self.candidates)(ctx.world, ctx.selection.target) {
+            let scores = flow.run_readonly(ctx.world, candidate);
+            for (label, action) in actions.iter() {
+                if let Some(score) = scores.score(label).filter(|&score| score > best_score) {
+                    best = Some((action, candidate));
+                    best_score = score;
+                }
+            }
+        }
+        best
+    }
+}
@@ -0,0 +1,52 @@
+use alloc::{borrow::Cow, format};
+
+use smallvec::SmallVec;
+
+use crate::{
+    label::InternedActionLabel,
+    score::Score,
+    selector::{MultiSelector, SelectionCtx},
+};
+
+/// Creates a [`MultiSelector`] that selects the actions associated with the
+/// `n` highest computed scores, ordered from highest to lowest. Actions whose
+/// score is missing or equal to [`Score::MIN`] are never selected, so fewer
+/// than `n` actions may be returned. An action associated with several scores
+/// is selected at most once, ranked by the highest of them. Ties keep the order
+/// the actions were added in.
+pub fn top_n_actions(n: usize) -> impl MultiSelector {
+    TopNSelector(n)
+}
+
+struct TopNSelector(usize);
+
+impl MultiSelector for TopNSelector {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("top_n_actions({})", self.0))
+    }
+
+    fn select_many(&mut self, ctx: SelectionCtx) -> SmallVec<[InternedActionLabel; 4]> {
+        let selection = ctx.selection;
+
+        // Several scores can map to the same action, which is ranked by the
+        // highest of them.
+        let mut candidates = SmallVec::<[(Score, InternedActionLabel); 8]>::new();
+        for (score, action) in selection.actions.iter() {
+            let Some(score) = selection.scores.get(score).filter(|&s| s > Score::MIN) else {
+                continue;
+            };
+            match candidates.iter_mut().find(|(_, other)| *other == action) {
+                Some((best, _)) => *best = (*best).max(score),
+                None => candidates.push((score, action)),
+            }
+        }
+        // Stable sort so that ties keep their insertion order.
+        candidates.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        candidates
+            .into_iter()
+            .take(self.0)
+            .map(|(_, action)| action)
+            .collect()
+    }
+}